@@ -2,6 +2,7 @@ use egui::{
     Align, Align2, FontId, Image, ImageSource, Label, Layout, ScrollArea, Vec2, WidgetText,
     include_image, vec2,
 };
+use std::sync::RwLock;
 use sys_locale::get_locales;
 
 use crate::*;
@@ -79,6 +80,26 @@ fn find_translation(source: StandardReplyTranslation, locale: String) -> Option<
     None
 }
 
+/// Texts of the standard replies, which are used instead of the translations
+/// of the system locale (e.g. to follow the language of the application).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StandardReplyTexts {
+    pub ok: String,
+    pub cancel: String,
+    pub yes: String,
+    pub no: String,
+}
+
+static STANDARD_REPLY_TEXTS: RwLock<Option<StandardReplyTexts>> = RwLock::new(None);
+
+/// Sets the texts of the standard replies of the dialogs, which are shown later.
+/// `None` restores the translations of the system locale.
+pub fn set_standard_reply_texts(texts: Option<StandardReplyTexts>) {
+    if let Ok(mut current) = STANDARD_REPLY_TEXTS.write() {
+        *current = texts;
+    }
+}
+
 #[inline]
 fn translate_standard_reply(source: StandardReplyTranslation) -> String {
     let locales = get_locales();
@@ -103,6 +124,18 @@ pub enum StandardReply {
 
 impl StandardReply {
     pub fn localize(self) -> String {
+        let texts = STANDARD_REPLY_TEXTS
+            .read()
+            .ok()
+            .and_then(|texts| texts.clone());
+        if let Some(texts) = texts {
+            return match self {
+                StandardReply::Ok => texts.ok,
+                StandardReply::Cancel => texts.cancel,
+                StandardReply::Yes => texts.yes,
+                StandardReply::No => texts.no,
+            };
+        }
         match self {
             StandardReply::Ok => translate_standard_reply(STANDARD_OK_REPLY),
             StandardReply::Cancel => translate_standard_reply(STANDARD_CANCEL_REPLY),
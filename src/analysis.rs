@@ -0,0 +1,511 @@
+use std::collections::HashSet;
+
+use crate::{
+    commands::is_valid_name,
+    error::{ErrorKind, Span, TurtleError},
+    keywords::KeywordTable,
+    locale::Locale,
+    message::Argument,
+    parsing::{parse, tokenize, tokens_span, tokens_text, Statement, Token, TokenKind},
+};
+
+// The names, which are defined by the script
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum SymbolKind {
+    Procedure,
+    // The assigned variables, the parameters of the procedures and the loop variables
+    Variable,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Symbol<'a> {
+    pub kind: SymbolKind,
+    // The token, where the name is defined
+    pub name: &'a Token,
+}
+
+// The loop variable of the loop, e.g. "i" in repeat(i, 0, 10) {...} or foreach(i, [1, 2]) {...}
+fn loop_variable<'a>(
+    name: &Token,
+    arguments: &'a [Vec<Token>],
+    keywords: &KeywordTable,
+) -> Option<&'a Token> {
+    let is_loop = match keywords.command(&name.text) {
+        Some("repeat") => arguments.len() > 1,
+        Some("foreach") => true,
+        _ => false,
+    };
+    match arguments.first().map(Vec::as_slice) {
+        Some([variable]) if is_loop && variable.kind == TokenKind::Word => Some(variable),
+        _ => None,
+    }
+}
+
+// The procedures and the variables, which are defined by the statements (in the order of the source code)
+pub fn symbols<'a>(statements: &'a [Statement], keywords: &KeywordTable) -> Vec<Symbol<'a>> {
+    let mut symbols: Vec<Symbol> = vec![];
+    for statement in statements {
+        let block = match statement {
+            Statement::Assignment { name, .. } => {
+                symbols.push(Symbol {
+                    kind: SymbolKind::Variable,
+                    name,
+                });
+                None
+            }
+            Statement::Procedure {
+                name,
+                parameters,
+                block,
+                ..
+            } => {
+                symbols.push(Symbol {
+                    kind: SymbolKind::Procedure,
+                    name,
+                });
+                for parameter in parameters {
+                    if let [parameter] = parameter.as_slice() {
+                        symbols.push(Symbol {
+                            kind: SymbolKind::Variable,
+                            name: parameter,
+                        });
+                    }
+                }
+                block.as_deref()
+            }
+            Statement::Command {
+                name,
+                arguments,
+                block,
+                ..
+            } => {
+                if let Some(variable) = loop_variable(name, arguments, keywords) {
+                    symbols.push(Symbol {
+                        kind: SymbolKind::Variable,
+                        name: variable,
+                    });
+                }
+                block.as_deref()
+            }
+        };
+        if let Some(block) = block {
+            symbols.extend(self::symbols(block, keywords));
+        }
+    }
+    symbols
+}
+
+// Finds the errors, which can be found without executing the script: the syntax errors,
+// the unknown commands, the invalid procedure definitions, the procedure calls
+// with the wrong number of arguments and the break/continue commands outside of the loops
+// The syntax error is the only error, if there is one
+pub fn check_script(source: &str, keywords: &KeywordTable) -> Vec<TurtleError> {
+    let statements = match parse(&tokenize(source)) {
+        Ok(statements) => statements,
+        Err(error) => return vec![error],
+    };
+    let procedures: Vec<&Statement> = procedures(&statements);
+    let mut errors: Vec<TurtleError> = vec![];
+    check_block(&statements, false, &procedures, keywords, &mut errors);
+    errors
+}
+
+// The definitions of the procedures, which can be called from anywhere in the script
+fn procedures(statements: &[Statement]) -> Vec<&Statement> {
+    let mut procedures: Vec<&Statement> = vec![];
+    for statement in statements {
+        if let Statement::Procedure { .. } = statement {
+            procedures.push(statement);
+        }
+        if let Statement::Procedure {
+            block: Some(block), ..
+        }
+        | Statement::Command {
+            block: Some(block), ..
+        } = statement
+        {
+            procedures.extend(self::procedures(block));
+        }
+    }
+    procedures
+}
+
+fn check_block(
+    statements: &[Statement],
+    in_loop: bool,
+    procedures: &[&Statement],
+    keywords: &KeywordTable,
+    errors: &mut Vec<TurtleError>,
+) {
+    for statement in statements {
+        match statement {
+            Statement::Assignment { .. } => {}
+            Statement::Procedure {
+                keyword,
+                name,
+                parameters,
+                block,
+                span,
+            } => {
+                if keywords.command(&keyword.text) != Some("procedure") {
+                    // Two words after each other, which don't define a procedure
+                    errors.push(TurtleError::new(
+                        ErrorKind::UnexpectedToken,
+                        &name.text,
+                        name.span,
+                    ));
+                    continue;
+                }
+                if !is_valid_name(&name.text) || keywords.command(&name.text).is_some() {
+                    errors.push(TurtleError::new(
+                        ErrorKind::InvalidProcedureName,
+                        &name.text,
+                        name.span,
+                    ));
+                }
+                for parameter in parameters {
+                    if parameter.len() != 1 || !is_valid_name(&tokens_text(parameter)) {
+                        errors.push(TurtleError::new(
+                            ErrorKind::InvalidVariableName,
+                            &tokens_text(parameter),
+                            tokens_span(parameter),
+                        ));
+                    }
+                }
+                match block {
+                    // The loops can't be continued from the procedures
+                    Some(block) => check_block(block, false, procedures, keywords, errors),
+                    None => {
+                        errors.push(TurtleError::new(ErrorKind::MissingBlock, &name.text, *span))
+                    }
+                }
+            }
+            Statement::Command {
+                name,
+                arguments,
+                block,
+                span,
+            } => {
+                let command = keywords.command(&name.text);
+                let definition = procedures.iter().find_map(|procedure| match procedure {
+                    Statement::Procedure {
+                        name: procedure,
+                        parameters,
+                        ..
+                    } if procedure.text == name.text => Some(parameters.len()),
+                    _ => None,
+                });
+                match (command, definition) {
+                    (Some("break" | "continue"), _) if !in_loop => errors.push(TurtleError::new(
+                        ErrorKind::LoopControlOutsideLoop,
+                        &name.text,
+                        *span,
+                    )),
+                    (Some(_), _) => {}
+                    (None, Some(expected)) if expected != arguments.len() => {
+                        errors.push(TurtleError::new(
+                            ErrorKind::WrongArgumentCount { expected },
+                            &name.text,
+                            *span,
+                        ))
+                    }
+                    (None, Some(_)) => {}
+                    (None, None) => errors.push(TurtleError::new(
+                        ErrorKind::UnknownCommand,
+                        &name.text,
+                        name.span,
+                    )),
+                }
+                if let Some(block) = block {
+                    let is_loop = matches!(command, Some("repeat" | "foreach"));
+                    check_block(block, in_loop || is_loop, procedures, keywords, errors);
+                }
+            }
+        }
+    }
+}
+
+// The problems of the script, which don't stop its execution (or not until the line is reached)
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum WarningKind {
+    // The variable is defined, but its value is never used
+    UnusedVariable,
+    // The loop variable has the same name as the variable of an outer loop or the procedure
+    ShadowedLoopVariable,
+    // The statement after break, continue or stop in the same block
+    UnreachableCode,
+    // The loop variable is changed in its loop (it's an error, when it's executed)
+    LoopVariableWrite,
+}
+
+impl WarningKind {
+    // The warning codes never change (like the error codes)
+    pub fn code(&self) -> u16 {
+        match self {
+            WarningKind::UnusedVariable => 1,
+            WarningKind::ShadowedLoopVariable => 2,
+            WarningKind::UnreachableCode => 3,
+            WarningKind::LoopVariableWrite => 4,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Warning {
+    pub kind: WarningKind,
+    // The variable or the command, which the warning is about
+    pub token: String,
+    pub span: Span,
+}
+
+impl Warning {
+    pub fn new(kind: WarningKind, token: &str, span: Span) -> Self {
+        Self {
+            kind,
+            token: token.to_string(),
+            span,
+        }
+    }
+    // The description of the warning in the language of the locale (without its location)
+    pub fn text(&self, locale: &Locale) -> String {
+        let token = Argument::from(self.token.as_str());
+        match self.kind {
+            WarningKind::UnusedVariable => {
+                locale.format(&locale.unused_variable_warning, &[("name", token)])
+            }
+            WarningKind::ShadowedLoopVariable => {
+                locale.format(&locale.shadowed_loop_variable_warning, &[("name", token)])
+            }
+            WarningKind::UnreachableCode => {
+                locale.format(&locale.unreachable_code_warning, &[("command", token)])
+            }
+            WarningKind::LoopVariableWrite => {
+                locale.format(&locale.loop_variable_write_warning, &[("name", token)])
+            }
+        }
+    }
+    // e.g. "3:5 W001: The "size" variable is never used!"
+    pub fn message(&self, locale: &Locale) -> String {
+        format!(
+            "{}:{} W{:03}: {}",
+            self.span.line,
+            self.span.column,
+            self.kind.code(),
+            self.text(locale)
+        )
+    }
+}
+
+// Finds the suspicious parts of the script: the unused variables, the shadowed loop variables,
+// the unreachable statements after break/continue/stop and the changed loop variables
+// The scripts with syntax errors have no warnings (their errors are reported by check_script)
+pub fn lint_script(source: &str, keywords: &KeywordTable) -> Vec<Warning> {
+    let Ok(statements) = parse(&tokenize(source)) else {
+        return vec![];
+    };
+    let mut warnings: Vec<Warning> = vec![];
+    let mut reads: HashSet<String> = HashSet::new();
+    read_variables(&statements, keywords, &mut reads);
+    // Every unused variable is reported only once, where it's defined first
+    let mut unused: HashSet<&str> = HashSet::new();
+    for symbol in symbols(&statements, keywords) {
+        if symbol.kind == SymbolKind::Variable
+            && !reads.contains(&symbol.name.text)
+            && unused.insert(&symbol.name.text)
+        {
+            warnings.push(Warning::new(
+                WarningKind::UnusedVariable,
+                &symbol.name.text,
+                symbol.name.span,
+            ));
+        }
+    }
+    lint_block(&statements, &mut vec![], keywords, &mut warnings);
+    warnings.sort_by_key(|warning| warning.span.start);
+    warnings
+}
+
+// The words of the expressions in the braces of the string, e.g. "x = {x * 2}" -> ["x"]
+fn interpolated_words(token: &Token) -> Vec<String> {
+    let content = token.text.strip_prefix('"').unwrap_or(&token.text);
+    let content = content.strip_suffix('"').unwrap_or(content);
+    let mut words: Vec<String> = vec![];
+    let mut chars = content.char_indices().peekable();
+    while let Some((index, char)) = chars.next() {
+        match char {
+            '\\' => {
+                chars.next();
+            }
+            '{' if chars.peek().is_some_and(|(_, next)| *next == '{') => {
+                chars.next();
+            }
+            '{' => {
+                let Some(length) = content[index + 1..].find('}') else {
+                    break;
+                };
+                let expression = &content[index + 1..index + 1 + length];
+                words.extend(
+                    tokenize(expression)
+                        .into_iter()
+                        .filter(|token| token.kind == TokenKind::Word)
+                        .map(|token| token.text),
+                );
+                while chars
+                    .next_if(|(next, _)| *next <= index + length + 1)
+                    .is_some()
+                {}
+            }
+            _ => {}
+        }
+    }
+    words
+}
+
+// The names, which are read by the expressions of the statements
+fn read_variables(statements: &[Statement], keywords: &KeywordTable, reads: &mut HashSet<String>) {
+    let mut read = |tokens: &[Token]| {
+        for token in tokens {
+            match token.kind {
+                TokenKind::Word => {
+                    reads.insert(token.text.clone());
+                }
+                TokenKind::String => reads.extend(interpolated_words(token)),
+                _ => {}
+            }
+        }
+    };
+    let mut blocks: Vec<&[Statement]> = vec![];
+    for statement in statements {
+        match statement {
+            Statement::Assignment { value, .. } => read(value),
+            Statement::Procedure { block, .. } => blocks.extend(block.as_deref()),
+            Statement::Command {
+                name,
+                arguments,
+                block,
+                ..
+            } => {
+                // The loop variable is written by the loop
+                let skipped = usize::from(loop_variable(name, arguments, keywords).is_some());
+                arguments
+                    .iter()
+                    .skip(skipped)
+                    .for_each(|argument| read(argument));
+                blocks.extend(block.as_deref());
+            }
+        }
+    }
+    for block in blocks {
+        read_variables(block, keywords, reads);
+    }
+}
+
+// The span of the whole statement (without its block)
+fn statement_span(statement: &Statement) -> Span {
+    match statement {
+        Statement::Assignment {
+            declaration,
+            name,
+            value,
+        } => {
+            let first = declaration.as_ref().unwrap_or(name);
+            value
+                .last()
+                .map_or(first.span, |last| first.span.to(last.span))
+        }
+        Statement::Command { span, .. } | Statement::Procedure { span, .. } => *span,
+    }
+}
+
+// The variables around the statements are (name, whether it's a loop variable)
+fn lint_block<'a>(
+    statements: &'a [Statement],
+    variables: &mut Vec<(&'a str, bool)>,
+    keywords: &KeywordTable,
+    warnings: &mut Vec<Warning>,
+) {
+    let outer = variables.len();
+    // The break, continue or stop command, which leaves the block
+    let mut exit: Option<&Token> = None;
+    let mut unreachable_reported = false;
+    for statement in statements {
+        if let Some(command) = exit.filter(|_| !unreachable_reported) {
+            warnings.push(Warning::new(
+                WarningKind::UnreachableCode,
+                &command.text,
+                statement_span(statement),
+            ));
+            unreachable_reported = true;
+        }
+        match statement {
+            Statement::Assignment {
+                declaration: Some(_),
+                name,
+                ..
+            } => variables.push((&name.text, false)),
+            Statement::Assignment {
+                declaration: None,
+                name,
+                ..
+            } => {
+                let is_loop_variable = variables
+                    .iter()
+                    .rev()
+                    .find(|(variable, _)| *variable == name.text)
+                    .is_some_and(|(_, is_loop)| *is_loop);
+                if is_loop_variable {
+                    warnings.push(Warning::new(
+                        WarningKind::LoopVariableWrite,
+                        &name.text,
+                        name.span,
+                    ));
+                }
+            }
+            Statement::Procedure {
+                parameters, block, ..
+            } => {
+                // The variables outside of the procedure can't be seen from its body
+                let mut parameters: Vec<(&str, bool)> = parameters
+                    .iter()
+                    .filter_map(|parameter| match parameter.as_slice() {
+                        [parameter] => Some((parameter.text.as_str(), false)),
+                        _ => None,
+                    })
+                    .collect();
+                if let Some(block) = block {
+                    lint_block(block, &mut parameters, keywords, warnings);
+                }
+            }
+            Statement::Command {
+                name,
+                arguments,
+                block,
+                ..
+            } => {
+                if matches!(
+                    keywords.command(&name.text),
+                    Some("break" | "continue" | "stop")
+                ) {
+                    exit = exit.or(Some(name));
+                }
+                let variable = loop_variable(name, arguments, keywords);
+                if let Some(variable) = variable {
+                    if variables.iter().any(|(name, _)| *name == variable.text) {
+                        warnings.push(Warning::new(
+                            WarningKind::ShadowedLoopVariable,
+                            &variable.text,
+                            variable.span,
+                        ));
+                    }
+                }
+                if let Some(block) = block {
+                    let inner = variables.len();
+                    variables.extend(variable.map(|variable| (variable.text.as_str(), true)));
+                    lint_block(block, variables, keywords, warnings);
+                    variables.truncate(inner);
+                }
+            }
+        }
+    }
+    variables.truncate(outer);
+}
@@ -1,15 +1,37 @@
-use std::{f32::consts::PI, ops::RangeInclusive};
+use std::{
+    f32::consts::PI,
+    ops::{Range, RangeInclusive},
+    sync::Arc,
+};
 
 use egui::{
-    self, color_picker::Alpha, include_image, menu, Align2, CentralPanel, Color32, Rect,
-    ScrollArea, Shadow, Stroke, TextStyle, TopBottomPanel, Vec2, Visuals, Widget,
+    self,
+    color_picker::Alpha,
+    include_image, menu,
+    text::{CCursor, CCursorRange},
+    text::{LayoutJob, TextFormat},
+    Align2, CentralPanel, Color32, Galley, Key, Modifiers, Rect, RichText, ScrollArea, Shadow,
+    Stroke, TextEdit, TextStyle, TopBottomPanel, Vec2, Visuals, Widget,
+};
+use egui_dialogs::{
+    dialog_window, set_standard_reply_texts, Dialog, DialogContext, DialogDetails, Dialogs,
+    StandardReplyTexts,
 };
-use egui_dialogs::{dialog_window, Dialog, DialogContext, DialogDetails, Dialogs};
 use egui_extras::install_image_loaders;
 
 use crate::{
+    analysis::{lint_script, Warning},
+    canvas::{label_shapes, to_svg, CanvasCache},
     commands::execute_command,
+    completion::{complete, usage_hint},
+    documentation::{Documentation, Manual},
+    error::TurtleError,
+    formatting::format_script,
+    highlighting::highlight,
+    history::{history_lines, HistoryEntry, InputHistory, MessageId},
+    keywords::{KeywordTable, Keywords},
     locale::{get_text, import_locales, Locale},
+    project::{FileEvent, Project, ProjectError, ProjectFiles},
     turtle::Turtle,
 };
 
@@ -26,44 +48,109 @@ fn android_main(app: winit::platform::android::activity::AndroidApp) {
         ..Default::default()
     };
     eframe::run_native(
-        "Rugged turtle",
+        crate::APP_NAME,
         options,
         Box::new(|cc| Ok(Box::new(RuggedTurtleApp::new(cc)))),
     )
     .unwrap()
 }
 
+// Storage key of the turtle's state from the last session
+const SESSION_KEY: &str = "session";
+// The id of the console's input field
+const CONSOLE_INPUT_ID: &str = "console_input";
+// The id of the script editor's text field
+const SCRIPT_EDITOR_ID: &str = "script_editor";
+
 /// We derive Deserialize/Serialize so we can persist app state on shutdown.
 #[derive(serde::Deserialize, serde::Serialize)]
 #[serde(default)] // if we add new fields, give them default values when deserializing old state
 pub struct RuggedTurtleApp<'a> {
     #[serde(skip)]
     input: String,
+    // The inputs of the console, which can be recalled
+    input_history: InputHistory,
+    // The names, which the last completed word can be completed to
+    #[serde(skip)]
+    completions: Vec<String>,
     text_editor: String,
+    show_script_editor: bool,
+    // The formatter replaces the aliases of the keywords with the aliases of the selected language
+    normalize_keywords: bool,
+    // The warnings of the linter with the script, which they were found in
+    #[serde(skip)]
+    editor_warnings: Option<(String, Vec<Warning>)>,
     #[serde(skip)]
     locale: Vec<Locale>,
     selected_locale: usize,
+    // The languages, whose ambiguous keywords were reported since they were loaded
+    #[serde(skip)]
+    reported_locales: Vec<usize>,
     #[serde(skip)]
     turtle: Turtle,
     dark_mode: bool,
+    restore_session: bool,
+    // The variables and procedures are kept between the inputs (REPL mode)
+    keep_variables: bool,
+    include_drawing: bool,
+    canvas_background: Option<Color32>,
+    #[serde(skip)]
+    project_files: ProjectFiles,
     #[serde(skip)]
     dialogs: Dialogs<'a>,
     #[serde(skip)]
     dialogopen: bool,
+    #[serde(skip)]
+    canvas_cache: CanvasCache,
+    // The last error with the source code that caused it
+    #[serde(skip)]
+    error: Option<(String, TurtleError)>,
+    // The keywords and the documentation of the selected language (and the English ones)
+    #[serde(skip)]
+    keywords: KeywordTable,
+    #[serde(skip)]
+    show_help: bool,
+    #[serde(skip)]
+    help_search: String,
+    // The lines of the console's history in the selected language
+    #[serde(skip)]
+    console: Vec<String>,
+    // The number of history entries and the language, which the lines of the console were written from
+    // (None - the lines have to be written again)
+    #[serde(skip)]
+    console_source: Option<(usize, usize)>,
 }
 
 impl Default for RuggedTurtleApp<'_> {
     fn default() -> Self {
-        let mut locale = vec![];
         Self {
             input: String::new(),
+            input_history: InputHistory::default(),
+            completions: vec![],
             text_editor: "".to_string(),
-            locale: import_locales(&mut locale),
+            show_script_editor: false,
+            normalize_keywords: false,
+            editor_warnings: None,
+            // The languages are loaded, when the application is started
+            locale: vec![Locale::default()],
             selected_locale: 0_usize,
+            reported_locales: vec![],
             turtle: Turtle::default(),
             dark_mode: false,
+            restore_session: true,
+            keep_variables: true,
+            include_drawing: true,
+            canvas_background: None,
+            project_files: ProjectFiles::default(),
             dialogs: Dialogs::default(),
             dialogopen: false,
+            canvas_cache: CanvasCache::default(),
+            error: None,
+            keywords: KeywordTable::default(),
+            show_help: false,
+            help_search: String::new(),
+            console: vec![],
+            console_source: None,
         }
     }
 }
@@ -83,7 +170,31 @@ impl RuggedTurtleApp<'_> {
         let mut application: RuggedTurtleApp = Default::default();
         if let Some(storage) = cc.storage {
             application = eframe::get_value(storage, eframe::APP_KEY).unwrap_or_default();
+            // Restoring the turtle (drawing, variables, command history) from the last session
+            if application.restore_session {
+                application.turtle = eframe::get_value(storage, SESSION_KEY).unwrap_or_default();
+            }
+            // The help message is shown after the history of the last session too
+            let help = HistoryEntry::message(MessageId::TerminalHelp, &[]);
+            let history = &mut application.turtle.command_history;
+            if !history.is_empty() && history.last() != Some(&help) {
+                history.push(help);
+            }
+        }
+        let (locale, errors) = import_locales();
+        application.locale = locale;
+        application.reported_locales.clear();
+        // The language of the last session may have been removed since then
+        if application.selected_locale >= application.locale.len() {
+            application.selected_locale = 0;
         }
+        for error in errors {
+            let text = get_text(&application.locale, application.selected_locale);
+            application
+                .dialogs
+                .error(text.locale_error_title.to_string(), error.message(text));
+        }
+        application.select_locale(application.selected_locale);
         if application.dark_mode {
             cc.egui_ctx.set_visuals(Visuals::dark());
             cc.egui_ctx.set_pixels_per_point(1.25);
@@ -93,77 +204,344 @@ impl RuggedTurtleApp<'_> {
         }
         application
     }
+
+    // Puts the turtle to the center of the canvas with the default pen
+    fn initialize_turtle(&mut self, ctx: &egui::Context, canvas: Rect) {
+        self.turtle.command_history.clear();
+        self.turtle
+            .command_history
+            .push(HistoryEntry::message(MessageId::TerminalHelp, &[]));
+        self.console_source = None;
+        let height = ctx.content_rect().width().max(ctx.content_rect().height()) * 0.030;
+        self.turtle.set_size(0.75 * height, height);
+        self.turtle
+            .set_position(canvas.center().x, canvas.center().y);
+        self.turtle.canvas = Some(canvas);
+        self.turtle.angle = 0.0;
+        self.turtle.pen.color = Color32::from_rgba_unmultiplied(0, 0, 0, 255);
+        if self.dark_mode {
+            self.turtle.pen.color = Color32::from_rgba_unmultiplied(255, 255, 255, 255);
+        }
+        self.turtle.pen.width = 1.0;
+        self.turtle.pen_up = false;
+    }
+
+    // Selects the language of the application, and reports its ambiguous keywords
+    // (only the first time it's selected, the keywords don't change until the languages are loaded again)
+    fn select_locale(&mut self, index: usize) {
+        self.selected_locale = index;
+        // The history of the console is displayed in the selected language
+        self.console_source = None;
+        self.editor_warnings = None;
+        let locale = get_text(&self.locale, self.selected_locale);
+        // The keywords of the selected language and the English keywords can be used
+        self.keywords = KeywordTable::new(&[&locale.keywords, &Keywords::default()])
+            .documented(&[&locale.documentation, &Manual::default()]);
+        if !self.reported_locales.contains(&index) {
+            self.reported_locales.push(index);
+            for ambiguity in locale.keywords.ambiguities() {
+                self.turtle.command_history.push(HistoryEntry::message(
+                    MessageId::AmbiguousKeyword,
+                    &[
+                        ("alias", ambiguity.alias.into()),
+                        ("names", ambiguity.names.join(", ").into()),
+                    ],
+                ));
+            }
+        }
+        // The buttons of the dialogs are in the selected language too
+        set_standard_reply_texts(Some(StandardReplyTexts {
+            ok: locale.ok_button.clone(),
+            cancel: locale.cancel_button.clone(),
+            yes: locale.yes_button.clone(),
+            no: locale.no_button.clone(),
+        }));
+    }
+
+    // Executes the source code, and reports the error if there is one
+    fn run(&mut self, source: String) {
+        if !self.keep_variables {
+            self.turtle.variables.clear();
+            self.turtle.procedures.clear();
+        }
+        self.turtle.keywords = self.keywords.clone();
+        // The script can clear the history of the console
+        self.console_source = None;
+        match execute_command(source.clone(), &mut self.turtle) {
+            Ok(()) => self.error = None,
+            Err(error) => {
+                self.turtle
+                    .command_history
+                    .push(HistoryEntry::Error(error.clone()));
+                self.error = Some((source, error));
+            }
+        }
+    }
+
+    // Formats the script of the editor, the syntax error is shown like the errors of the execution
+    fn format_editor(&mut self) {
+        let keywords = self.normalize_keywords.then_some(&self.keywords);
+        match format_script(&self.text_editor, keywords) {
+            Ok(formatted) => {
+                self.text_editor = formatted;
+                self.error = None;
+            }
+            Err(error) => {
+                self.turtle
+                    .command_history
+                    .push(HistoryEntry::Error(error.clone()));
+                self.error = Some((self.text_editor.clone(), error));
+            }
+        }
+    }
+
+    // Executes the input of the console, and clears it if it was executed without errors
+    fn run_input(&mut self) {
+        let input = self.input.clone();
+        self.input_history.push(&input);
+        self.completions.clear();
+        self.run(input);
+        if self.error.is_none() {
+            self.input.clear();
+        }
+    }
+
+    // Writes the lines of the new history entries, or every line, if the history
+    // or the language has been changed
+    fn update_console(&mut self) {
+        let locale = get_text(&self.locale, self.selected_locale);
+        let history = &self.turtle.command_history;
+        match self.console_source {
+            Some((entries, language))
+                if language == self.selected_locale && entries <= history.len() =>
+            {
+                self.console
+                    .extend(history_lines(&history[entries..], locale, &self.keywords));
+            }
+            _ => self.console = history_lines(history, locale, &self.keywords),
+        }
+        self.console_source = Some((history.len(), self.selected_locale));
+    }
+
+    // The color of the canvas, that the erasing pen paints with
+    fn background(&self, ctx: &egui::Context) -> Color32 {
+        self.canvas_background
+            .unwrap_or(ctx.global_style().visuals.panel_fill)
+    }
+
+    fn to_project(&self, ctx: &egui::Context) -> Project {
+        let canvas = self.turtle.canvas.unwrap_or(ctx.content_rect());
+        Project::new(
+            self.text_editor.clone(),
+            canvas.size(),
+            self.background(ctx),
+            get_text(&self.locale, self.selected_locale)
+                .language_id
+                .clone(),
+            self.include_drawing.then(|| self.turtle.clone()),
+        )
+    }
+
+    fn open_project(&mut self, ctx: &egui::Context, contents: &str) {
+        let project = match Project::from_json(contents) {
+            Ok(project) => project,
+            Err(error) => {
+                let text = get_text(&self.locale, self.selected_locale);
+                let message = match error {
+                    ProjectError::Invalid(reason) => {
+                        text.format(&text.project_invalid_error, &[("reason", reason.into())])
+                    }
+                    ProjectError::UnsupportedVersion(version) => text.format(
+                        &text.project_version_error,
+                        &[("version", version.to_string().into())],
+                    ),
+                };
+                self.dialogs.error(
+                    get_text(&self.locale, self.selected_locale)
+                        .project_error_title
+                        .to_string(),
+                    message,
+                );
+                return;
+            }
+        };
+        if let Some(index) = self
+            .locale
+            .iter()
+            .position(|locale| locale.language_id == project.locale)
+        {
+            self.select_locale(index);
+        }
+        self.text_editor = project.script.clone();
+        self.show_script_editor = true;
+        self.canvas_background = Some(project.background());
+        // The canvas is placed to the center of the window with the size of the project's canvas
+        let canvas = Rect::from_center_size(ctx.content_rect().center(), project.canvas_size());
+        self.console_source = None;
+        match project.turtle {
+            Some(turtle) => {
+                self.turtle = turtle;
+                if self.turtle.canvas.is_none() {
+                    self.turtle.canvas = Some(canvas);
+                }
+            }
+            None => {
+                self.turtle = Turtle::default();
+                self.initialize_turtle(ctx, canvas);
+            }
+        }
+    }
 }
 
 impl eframe::App for RuggedTurtleApp<'_> {
     /// Called by the frame work to save state before shutdown.
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
         eframe::set_value(storage, eframe::APP_KEY, self);
+        if !self.restore_session {
+            eframe::set_value(storage, SESSION_KEY, &Turtle::default());
+        } else {
+            self.turtle
+                .save_session(|turtle| eframe::set_value(storage, SESSION_KEY, turtle));
+        }
     }
 
     /// Called each time the UI needs repainting, which may be many times per second.
-    fn update(&mut self, ctx: &egui::context::Context, _frame: &mut eframe::Frame) {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         const COLOR_PICKER_DIALOG_ID: &str = "color_picker_dialog";
         const WIDTH_INPUT_DIALOG_ID: &str = "width_input_dialog";
+        #[cfg(target_os = "android")]
+        const OPEN_PROJECT_DIALOG_ID: &str = "open_project_dialog";
+        #[cfg(target_os = "android")]
+        const SAVE_PROJECT_DIALOG_ID: &str = "save_project_dialog";
         // Logic for showing the dialogs and handling the reply is there is one
         if let Some(res) = self.dialogs.show(ctx) {
+            // On Android the path of the project file is typed in, because there are no file dialogs
+            #[cfg(target_os = "android")]
+            if res.is_reply_of(OPEN_PROJECT_DIALOG_ID) {
+                if let Some(Some(path)) = res.reply_ref::<Option<String>>() {
+                    self.project_files.open_path(path);
+                }
+            } else if res.is_reply_of(SAVE_PROJECT_DIALOG_ID) {
+                if let Some(Some(path)) = res.reply_ref::<Option<String>>() {
+                    self.project_files
+                        .save_path(path, self.to_project(ctx).to_json());
+                }
+            }
             if res.is_reply_of(COLOR_PICKER_DIALOG_ID) {
                 if let Ok(picked_color) = res.reply() {
-                    self.turtle.pencolor = picked_color;
-                    self.turtle.path.push(vec![]);
-                    self.turtle.path_color.push(self.turtle.pencolor);
-                    self.turtle.path_width.push(self.turtle.penwidth);
+                    self.turtle.pen.color = picked_color;
                     self.dialogopen = false;
                 }
             } else if res.is_reply_of(WIDTH_INPUT_DIALOG_ID) {
                 if let Ok(new_width) = res.reply() {
-                    self.turtle.penwidth = new_width;
-                    self.turtle.path.push(vec![]);
-                    self.turtle.path_color.push(self.turtle.pencolor);
-                    self.turtle.path_width.push(self.turtle.penwidth);
+                    self.turtle.pen.width = new_width;
                     self.dialogopen = false;
                 }
             }
         }
+        // Handling the finished file operations
+        while let Some(event) = self.project_files.poll() {
+            match event {
+                FileEvent::Opened(contents) => self.open_project(ctx, &contents),
+                FileEvent::Saved => self
+                    .turtle
+                    .command_history
+                    .push(HistoryEntry::message(MessageId::ProjectSaved, &[])),
+                FileEvent::Failed(error) => {
+                    let text = get_text(&self.locale, self.selected_locale);
+                    self.dialogs.error(
+                        text.project_error_title.to_string(),
+                        text.format(&text.project_io_error, &[("error", error.into())]),
+                    )
+                }
+            }
+        }
 
-        // Executes at the start of the program to initialize the turtle
+        // The turtle is placed on the canvas, when it has no canvas yet: at the start of the program
+        // (without a session, or with an empty one), and after the turtle is reset
         let mut bottom_size = 0_f32;
         let turtle_icon: egui::ImageSource = include_image!("assets/rugged_turtle.svg");
-        if self.turtle == Turtle::default() {
-            self.turtle.command_history.clear();
-            self.turtle.command_history.push(
-                get_text(&self.locale, self.selected_locale)
-                    .terminal_help_message
-                    .to_string(),
-            );
-            let height = ctx.screen_rect().width().max(ctx.screen_rect().height()) * 0.030;
-            self.turtle.set_size(0.75 * height, height);
-            self.turtle
-                .set_position(ctx.screen_rect().center().x, ctx.screen_rect().center().y);
-            self.turtle.path.push(vec![]);
-            self.turtle.angle = 0.0;
-            self.turtle.pencolor = Color32::from_rgba_unmultiplied(0, 0, 0, 255);
-            if self.dark_mode {
-                self.turtle.pencolor = Color32::from_rgba_unmultiplied(255, 255, 255, 255);
-            }
-            self.turtle.path_color.push(self.turtle.pencolor);
-            self.turtle.penwidth = 1.0;
-            self.turtle.pen_up = false;
-            self.turtle.path_width.push(self.turtle.penwidth);
+        if self.turtle.canvas.is_none() {
+            self.initialize_turtle(ctx, ctx.content_rect());
             ctx.forget_image(turtle_icon.uri().unwrap());
         }
         TopBottomPanel::bottom("Console").show(ctx, |ui| {
             bottom_size = ui.available_size_before_wrap().y;
+            let mut layouter = |ui: &egui::Ui, text: &dyn egui::TextBuffer, _wrap_width: f32| {
+                let text_color = if self.dark_mode {
+                    ui.visuals().text_color()
+                } else {
+                    Color32::BLACK
+                };
+                let format = TextFormat::simple(TextStyle::Body.resolve(ui.style()), text_color);
+                underline_error(
+                    ui,
+                    text.as_str(),
+                    self.error.as_ref(),
+                    vec![(0..text.as_str().len(), format)],
+                    f32::INFINITY,
+                )
+            };
+            let input_id = egui::Id::new(CONSOLE_INPUT_ID);
+            // The keys are handled before the text field, so it doesn't move the cursor or the focus
+            if ui.memory(|memory| memory.has_focus(input_id)) {
+                let (up, down, tab) = ui.input_mut(|input| {
+                    (
+                        input.consume_key(Modifiers::NONE, Key::ArrowUp),
+                        input.consume_key(Modifiers::NONE, Key::ArrowDown),
+                        input.consume_key(Modifiers::NONE, Key::Tab),
+                    )
+                });
+                let recalled = if up {
+                    self.input_history.recall_previous(&self.input)
+                } else if down {
+                    self.input_history.recall_next()
+                } else {
+                    None
+                };
+                if let Some(recalled) = recalled {
+                    self.input = recalled.to_string();
+                    self.completions.clear();
+                    set_cursor(ui.ctx(), input_id, self.input.chars().count());
+                }
+                if tab {
+                    let cursor = get_cursor(ui.ctx(), input_id)
+                        .unwrap_or_else(|| self.input.chars().count());
+                    if let Some(completion) =
+                        complete(&self.input, cursor, &self.keywords, &self.turtle)
+                    {
+                        self.input = completion.input;
+                        set_cursor(ui.ctx(), input_id, completion.cursor);
+                        self.completions = completion.candidates;
+                    }
+                }
+            }
+            let mut input = egui::widgets::TextEdit::singleline(&mut self.input)
+                .id(input_id)
+                .lock_focus(true)
+                .desired_width(f32::INFINITY)
+                .layouter(&mut layouter);
             if !self.dark_mode {
-                egui::widgets::TextEdit::singleline(&mut self.input)
-                    .desired_width(f32::INFINITY)
-                    .background_color(Color32::KHAKI)
-                    .text_color(Color32::BLACK)
-                    .ui(ui);
-            } else if self.dark_mode {
-                egui::widgets::TextEdit::singleline(&mut self.input)
-                    .desired_width(f32::INFINITY)
-                    .ui(ui);
+                input = input.background_color(Color32::KHAKI);
+            }
+            let response = input.ui(ui);
+            if response.changed() {
+                self.completions.clear();
+            }
+            if response.lost_focus() && ui.input(|input| input.key_pressed(Key::Enter)) {
+                self.run_input();
+                response.request_focus();
+            }
+            // The names, which the word can be completed to, or the usage of the command that is being typed
+            let hint = if self.completions.len() > 1 {
+                Some(self.completions.join("  "))
+            } else {
+                let cursor =
+                    get_cursor(ui.ctx(), input_id).unwrap_or_else(|| self.input.chars().count());
+                usage_hint(&self.input, cursor, &self.keywords).map(Documentation::summary)
+            };
+            if let Some(hint) = hint {
+                ui.label(RichText::new(hint).weak());
             }
             ui.horizontal(|ui| {
                 if ui
@@ -174,13 +552,7 @@ impl eframe::App for RuggedTurtleApp<'_> {
                     )
                     .clicked()
                 {
-                    self.turtle.variables.clear();
-                    execute_command(
-                        self.input.clone(),
-                        &mut self.turtle,
-                        &self.locale,
-                        self.selected_locale,
-                    );
+                    self.run_input();
                 }
                 if ui
                     .button(
@@ -191,7 +563,7 @@ impl eframe::App for RuggedTurtleApp<'_> {
                     .clicked()
                 {
                     DialogDetails::new(ColorPickerDialog::new(
-                        self.turtle.pencolor,
+                        self.turtle.pen.color,
                         &self.locale,
                         self.selected_locale,
                     ))
@@ -208,7 +580,7 @@ impl eframe::App for RuggedTurtleApp<'_> {
                     .clicked()
                 {
                     DialogDetails::new(WidthInputDialog::new(
-                        self.turtle.penwidth,
+                        self.turtle.pen.width,
                         &self.locale,
                         self.selected_locale,
                     ))
@@ -234,6 +606,71 @@ impl eframe::App for RuggedTurtleApp<'_> {
                             .clicked()
                         {
                             self.turtle = Turtle::default();
+                            self.canvas_background = None;
+                        }
+                        if ui
+                            .button(
+                                get_text(&self.locale, self.selected_locale)
+                                    .open_project_menu
+                                    .to_string(),
+                            )
+                            .clicked()
+                        {
+                            #[cfg(not(target_os = "android"))]
+                            self.project_files.open(
+                                &get_text(&self.locale, self.selected_locale).open_project_menu,
+                            );
+                            #[cfg(target_os = "android")]
+                            DialogDetails::new(ProjectPathDialog::new(
+                                &self.locale,
+                                self.selected_locale,
+                            ))
+                            .with_id(OPEN_PROJECT_DIALOG_ID)
+                            .show(&mut self.dialogs);
+                        }
+                        if ui
+                            .button(
+                                get_text(&self.locale, self.selected_locale)
+                                    .save_project_menu
+                                    .to_string(),
+                            )
+                            .clicked()
+                        {
+                            #[cfg(not(target_os = "android"))]
+                            self.project_files.save(
+                                &get_text(&self.locale, self.selected_locale).save_project_menu,
+                                self.to_project(ctx).to_json(),
+                            );
+                            #[cfg(target_os = "android")]
+                            DialogDetails::new(ProjectPathDialog::new(
+                                &self.locale,
+                                self.selected_locale,
+                            ))
+                            .with_id(SAVE_PROJECT_DIALOG_ID)
+                            .show(&mut self.dialogs);
+                        }
+                        ui.checkbox(
+                            &mut self.include_drawing,
+                            get_text(&self.locale, self.selected_locale)
+                                .include_drawing_menu
+                                .to_string(),
+                        );
+                        ui.checkbox(
+                            &mut self.show_script_editor,
+                            get_text(&self.locale, self.selected_locale)
+                                .script_editor_title
+                                .to_string(),
+                        );
+                        if ui
+                            .button(
+                                get_text(&self.locale, self.selected_locale)
+                                    .copy_svg_menu
+                                    .to_string(),
+                            )
+                            .clicked()
+                        {
+                            ui.ctx()
+                                .copy_text(to_svg(&self.turtle, self.background(ctx)));
                         }
                     },
                 );
@@ -264,6 +701,18 @@ impl eframe::App for RuggedTurtleApp<'_> {
                             ctx.set_visuals(Visuals::light());
                             self.dark_mode = false;
                         }
+                        ui.checkbox(
+                            &mut self.restore_session,
+                            get_text(&self.locale, self.selected_locale)
+                                .restore_session_menu
+                                .to_string(),
+                        );
+                        ui.checkbox(
+                            &mut self.keep_variables,
+                            get_text(&self.locale, self.selected_locale)
+                                .keep_variables_menu
+                                .to_string(),
+                        );
                         ui.menu_button(
                             get_text(&self.locale, self.selected_locale)
                                 .languages_menu
@@ -274,15 +723,150 @@ impl eframe::App for RuggedTurtleApp<'_> {
                                         .button(self.locale[languages].get_language_name())
                                         .clicked()
                                     {
-                                        self.selected_locale = languages;
+                                        self.select_locale(languages);
                                     }
                                 }
                             },
                         );
                     },
                 );
+                if ui
+                    .button(
+                        get_text(&self.locale, self.selected_locale)
+                            .help_menu
+                            .to_string(),
+                    )
+                    .clicked()
+                {
+                    self.show_help = !self.show_help;
+                }
             });
         });
+        let mut show_script_editor = self.show_script_editor;
+        egui::containers::Window::new(
+            get_text(&self.locale, self.selected_locale)
+                .script_editor_title
+                .to_string(),
+        )
+        .open(&mut show_script_editor)
+        .resizable(true)
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                let locale = get_text(&self.locale, self.selected_locale);
+                let run = ui.button(locale.run_button.to_string()).clicked();
+                let format = ui.button(locale.format_button.to_string()).clicked();
+                ui.checkbox(
+                    &mut self.normalize_keywords,
+                    locale.normalize_keywords_checkbox.to_string(),
+                );
+                if run {
+                    self.run(self.text_editor.clone());
+                }
+                if format {
+                    self.format_editor();
+                }
+            });
+            // The script is only checked again, when it's changed
+            if self
+                .editor_warnings
+                .as_ref()
+                .is_none_or(|(script, _)| *script != self.text_editor)
+            {
+                let warnings = lint_script(&self.text_editor, &self.keywords);
+                self.editor_warnings = Some((self.text_editor.clone(), warnings));
+            }
+            if let Some((_, warnings)) = &self.editor_warnings {
+                let locale = get_text(&self.locale, self.selected_locale);
+                for warning in warnings {
+                    ui.label(
+                        RichText::new(warning.message(locale)).color(ui.visuals().warn_fg_color),
+                    );
+                }
+            }
+            let editor_id = egui::Id::new(SCRIPT_EDITOR_ID);
+            let mut layouter = |ui: &egui::Ui, text: &dyn egui::TextBuffer, wrap_width: f32| {
+                // The brackets are matched at the cursor, while the editor has the focus
+                let cursor = ui
+                    .memory(|memory| memory.has_focus(editor_id))
+                    .then(|| get_cursor(ui.ctx(), editor_id))
+                    .flatten();
+                let font_id = TextStyle::Monospace.resolve(ui.style());
+                let parts = highlight(text.as_str(), &self.keywords, &self.turtle, cursor)
+                    .into_iter()
+                    .map(|(range, highlight)| {
+                        (range, highlight.format(font_id.clone(), ui.visuals()))
+                    })
+                    .collect();
+                underline_error(ui, text.as_str(), self.error.as_ref(), parts, wrap_width)
+            };
+            ScrollArea::vertical().show(ui, |ui| {
+                egui::widgets::TextEdit::multiline(&mut self.text_editor)
+                    .id(editor_id)
+                    .code_editor()
+                    .desired_width(f32::INFINITY)
+                    .layouter(&mut layouter)
+                    .ui(ui);
+            });
+        });
+        self.show_script_editor = show_script_editor;
+        let mut show_help = self.show_help;
+        let mut example: Option<String> = None;
+        egui::containers::Window::new(
+            get_text(&self.locale, self.selected_locale)
+                .help_menu
+                .to_string(),
+        )
+        .open(&mut show_help)
+        .resizable(true)
+        .default_width(400_f32)
+        .show(ctx, |ui| {
+            let locale = get_text(&self.locale, self.selected_locale);
+            egui::widgets::TextEdit::singleline(&mut self.help_search)
+                .hint_text(locale.help_search_hint.to_string())
+                .desired_width(f32::INFINITY)
+                .ui(ui);
+            ui.weak(locale.help_example_hint.to_string());
+            let Some(manual) = self.keywords.manual() else {
+                return;
+            };
+            let search = self.help_search.to_lowercase();
+            ScrollArea::vertical().show(ui, |ui| {
+                for (title, records) in [
+                    (&locale.help_commands_title, manual.commands()),
+                    (&locale.help_functions_title, manual.functions()),
+                ] {
+                    ui.heading(title.to_string());
+                    for documentation in records
+                        .into_iter()
+                        .filter(|documentation| matches_search(documentation, &search))
+                    {
+                        ui.separator();
+                        ui.label(RichText::new(&documentation.usage).monospace().strong());
+                        ui.label(&documentation.description);
+                        ui.weak(format!(
+                            "{} {}",
+                            manual.aliases_label,
+                            documentation.aliases.join(", ")
+                        ));
+                        for text in &documentation.examples {
+                            if ui.link(RichText::new(text).monospace()).clicked() {
+                                example = Some(text.clone());
+                            }
+                        }
+                    }
+                }
+            });
+        });
+        self.show_help = show_help;
+        // The example is inserted to the end of the script
+        if let Some(example) = example {
+            if !self.text_editor.is_empty() && !self.text_editor.ends_with('\n') {
+                self.text_editor.push('\n');
+            }
+            self.text_editor.push_str(&example);
+            self.show_script_editor = true;
+        }
+        self.update_console();
         CentralPanel::default().show(ctx, |ui| {
             if !self.dialogopen {
                 ctx.style_mut(|style| style.visuals.window_shadow = Shadow::NONE);
@@ -309,37 +893,26 @@ impl eframe::App for RuggedTurtleApp<'_> {
                             egui::scroll_area::ScrollBarVisibility::AlwaysVisible,
                         )
                         .stick_to_bottom(true)
-                        .show_rows(
-                            ui,
-                            row_height,
-                            self.turtle.command_history.len(),
-                            |ui, row_range| {
-                                for row in row_range {
-                                    ui.label(" ".to_string() + &self.turtle.command_history[row]);
-                                }
-                            },
-                        );
+                        .show_rows(ui, row_height, self.console.len(), |ui, row_range| {
+                            for row in row_range {
+                                ui.label(" ".to_string() + &self.console[row]);
+                            }
+                        });
                 });
             }
             ScrollArea::new([true, true]).show(ui, |ui| {
-                // Painting the lines drawn by the turtle
-                for i in 0..self.turtle.path_color.len() {
-                    ui.painter().line(
-                        self.turtle
-                            .path
-                            .get(i)
-                            .unwrap_or(&vec![self.turtle.position])
-                            .to_vec(),
-                        Stroke::new(
-                            *self.turtle.path_width.get(i).unwrap(),
-                            *self.turtle.path_color.get(i).unwrap(),
-                        ),
-                    );
+                // Painting the background of the canvas and the lines drawn by the turtle
+                let background = self.background(ctx);
+                if let (Some(color), Some(canvas)) = (self.canvas_background, self.turtle.canvas) {
+                    ui.painter().rect_filled(canvas, 0_f32, color);
                 }
+                ui.painter()
+                    .add(self.canvas_cache.shape(ctx, &self.turtle.path, background));
+                ui.painter().extend(label_shapes(ctx, &self.turtle.path));
                 // Plus function: Implementing customizable turtle images
                 //self.turtle.set_icon(turtle_icon.uri().unwrap());
                 egui::widgets::Image::new(turtle_icon.clone())
-                    .rotate((2_f32 * PI) - self.turtle.angle, Vec2::splat(0.5))
+                    .rotate((2_f32 * PI) - self.turtle.angle as f32, Vec2::splat(0.5))
                     .paint_at(
                         ui,
                         Rect::from_center_size(
@@ -354,6 +927,76 @@ impl eframe::App for RuggedTurtleApp<'_> {
     fn ui(&mut self, _ui: &mut egui::Ui, _frame: &mut eframe::Frame) {}
 }
 
+// The documentation is found by its usage, its description or its aliases
+fn matches_search(documentation: &Documentation, search: &str) -> bool {
+    [&documentation.usage, &documentation.description]
+        .into_iter()
+        .chain(&documentation.aliases)
+        .any(|text| text.to_lowercase().contains(search))
+}
+
+// Lays out the text of the console or the script editor,
+// the part of the text that caused the last error is underlined
+// The position of the text field's cursor in characters
+fn get_cursor(ctx: &egui::Context, id: egui::Id) -> Option<usize> {
+    TextEdit::load_state(ctx, id)
+        .and_then(|state| state.cursor.char_range())
+        .map(|range| range.primary.index)
+}
+
+// Moves the cursor of the text field (the field has to be shown after it)
+fn set_cursor(ctx: &egui::Context, id: egui::Id, index: usize) {
+    let mut state = TextEdit::load_state(ctx, id).unwrap_or_default();
+    state
+        .cursor
+        .set_char_range(Some(CCursorRange::one(CCursor::new(index))));
+    state.store(ctx, id);
+}
+
+// Lays out the parts of the text with their formats, and underlines the part, which caused the error
+fn underline_error(
+    ui: &egui::Ui,
+    text: &str,
+    error: Option<&(String, TurtleError)>,
+    parts: Vec<(Range<usize>, TextFormat)>,
+    wrap_width: f32,
+) -> Arc<Galley> {
+    let mut job = LayoutJob::default();
+    let underlined = match error {
+        // The error is only shown, while the text is the same as the one that was run
+        Some((source, error)) if source == text && error.span.end <= text.len() => {
+            let mut start = error.span.start;
+            let mut end = error.span.end;
+            // Empty spans (e.g. missing values) are widened to the next (or previous) character
+            if start == end {
+                match text[end..].chars().next() {
+                    Some(char) => end += char.len_utf8(),
+                    None => start -= text[..start].chars().last().map_or(0, char::len_utf8),
+                }
+            }
+            start..end
+        }
+        _ => 0..0,
+    };
+    for (range, format) in parts {
+        // The part is split at the edges of the underlined text
+        let start = underlined.start.clamp(range.start, range.end);
+        let end = underlined.end.clamp(start, range.end);
+        job.append(&text[range.start..start], 0_f32, format.clone());
+        job.append(
+            &text[start..end],
+            0_f32,
+            TextFormat {
+                underline: Stroke::new(2_f32, ui.visuals().error_fg_color),
+                ..format.clone()
+            },
+        );
+        job.append(&text[end..range.end], 0_f32, format);
+    }
+    job.wrap.max_width = wrap_width;
+    ui.fonts_mut(|fonts| fonts.layout_job(job))
+}
+
 pub struct ColorPickerDialog {
     pub picked_color: Color32,
     pub original_color: Color32,
@@ -568,3 +1211,70 @@ impl Dialog<Vec2> for NewCanvasDialog {
         res
     }
 }
+
+// Asks for the path of the project file, where there are no file dialogs (on Android)
+#[cfg(target_os = "android")]
+pub struct ProjectPathDialog {
+    pub path: String,
+    pub locale: Vec<Locale>,
+    pub selected_locale: usize,
+}
+
+#[cfg(target_os = "android")]
+impl ProjectPathDialog {
+    pub fn new(locale: &[Locale], selected_locale: usize) -> Self {
+        Self {
+            path: String::from("/sdcard/Documents/project.rturtle"),
+            locale: locale.to_vec(),
+            selected_locale,
+        }
+    }
+}
+#[cfg(target_os = "android")]
+impl Dialog<Option<String>> for ProjectPathDialog {
+    fn show(&mut self, ctx: &egui::Context, dctx: &DialogContext) -> Option<Option<String>> {
+        // Return None if the user hasn't selected something
+        let mut res = None;
+
+        // Draw the dialog ui
+        dialog_window(
+            ctx,
+            dctx,
+            get_text(&self.locale, self.selected_locale)
+                .project_path_dialog_title
+                .to_string(),
+        )
+        .show(ctx, |ui| {
+            ui.label(
+                get_text(&self.locale, self.selected_locale)
+                    .project_path_dialog_text
+                    .to_string(),
+            );
+            egui::widgets::TextEdit::singleline(&mut self.path).ui(ui);
+            ui.horizontal(|ui| {
+                if ui
+                    .button(
+                        get_text(&self.locale, self.selected_locale)
+                            .done_button
+                            .to_string(),
+                    )
+                    .clicked()
+                {
+                    res = Some(Some(self.path.clone()));
+                }
+                if ui
+                    .button(
+                        get_text(&self.locale, self.selected_locale)
+                            .cancel_button
+                            .to_string(),
+                    )
+                    .clicked()
+                {
+                    res = Some(None);
+                }
+            });
+        });
+
+        res
+    }
+}
@@ -1,236 +1,785 @@
-use dyn_fmt::AsStrFormatExt;
-
 use crate::{
-    locale::{get_text, Locale},
-    parsing::trim_whitespace,
+    commands::VariableTypes,
+    error::{ErrorKind, Span, TurtleError},
+    parsing::{tokenize, tokens_span, tokens_text, Token, TokenKind},
     turtle::Turtle,
 };
 
-pub fn parse_number_value(
-    input: String,
+// Names of the functions, that can be used in the expressions (their aliases are in the locale files)
+// length(<text or list>) - the number of characters in the text or the number of elements in the list
+// substring(<text>, <start>, <count>) - <count> characters of the text from the <start>th character (from 0)
+// text(<value>) - converts the value to text
+// number(<text>) - converts the text to number
+// range(<from>, <to>, <step>) - list of the numbers from <from> by <step> until <to> (exclusive boundary),
+// the step is optional (1 by default)
+// random(<max>) - random whole number from 0 to <max>-1
+// random(<min>, <max>) - random whole number from <min> to <max> (both of them are included)
+// randomfloat() - random number from 0 (included) to 1 (excluded)
+// pick(<list>) - random element of the list (or random character of the text)
+// heading() - the direction of the turtle (in the angle unit and the orientation)
+// sin(<angle>), cos(<angle>), tan(<angle>) - the angle is in the angle unit (degrees or radians)
+// asin(<number>), acos(<number>), atan(<number>) - the angle is given in the angle unit
+const LENGTH_FUNCTION: &str = "length";
+const SUBSTRING_FUNCTION: &str = "substring";
+const TEXT_FUNCTION: &str = "text";
+const NUMBER_FUNCTION: &str = "number";
+const RANGE_FUNCTION: &str = "range";
+const RANDOM_FUNCTION: &str = "random";
+const RANDOM_FLOAT_FUNCTION: &str = "randomfloat";
+const PICK_FUNCTION: &str = "pick";
+const HEADING_FUNCTION: &str = "heading";
+const SIN_FUNCTION: &str = "sin";
+const COS_FUNCTION: &str = "cos";
+const TAN_FUNCTION: &str = "tan";
+const ASIN_FUNCTION: &str = "asin";
+const ACOS_FUNCTION: &str = "acos";
+const ATAN_FUNCTION: &str = "atan";
+
+// The greatest whole number, which can be stored exactly as a number (2^53)
+const MAX_WHOLE_NUMBER: f64 = 9_007_199_254_740_992_f64;
+
+// The expressions can be nested this deep in the parentheses, the lists, the function calls,
+// the indexes and the signs (the deeper nesting would overflow the stack)
+const MAX_DEPTH: usize = 100;
+
+// The values can't be larger than this (see VariableTypes::size),
+// so doubling a list or a text in a loop can't use up the memory (e.g. l = l + l)
+pub const MAX_VALUE_SIZE: usize = 1_000_000;
+
+// Returns the value, if it isn't too large
+fn limit_size(value: VariableTypes, token: &str, span: Span) -> Result<VariableTypes, TurtleError> {
+    if value.size() > MAX_VALUE_SIZE {
+        return Err(TurtleError::new(
+            ErrorKind::ValueSize {
+                max: MAX_VALUE_SIZE,
+            },
+            token,
+            span,
+        ));
+    }
+    Ok(value)
+}
+
+// The longest list of range(), so a small step can't use up the memory (e.g. range(0, 1e9, 0.001))
+const MAX_RANGE_LENGTH: usize = 100_000;
+
+// Evaluates the expression made up of the tokens, the result has to be a number
+pub fn parse_number_value(tokens: &[Token], turtle: &mut Turtle) -> Result<f64, TurtleError> {
+    number_value(tokens, 0, turtle)
+}
+
+// <depth> is the number of the expressions around the tokens
+fn number_value(tokens: &[Token], depth: usize, turtle: &mut Turtle) -> Result<f64, TurtleError> {
+    let value = evaluate_nested(tokens, depth, turtle)?;
+    expect_number(&value, tokens)
+}
+
+// The value has to be a number, <tokens> are the source of the value
+fn expect_number(value: &VariableTypes, tokens: &[Token]) -> Result<f64, TurtleError> {
+    match value {
+        VariableTypes::Text { .. } | VariableTypes::List { .. } => Err(TurtleError::new(
+            ErrorKind::ExpectedNumber,
+            &tokens_text(tokens),
+            tokens_span(tokens),
+        )),
+        value => Ok(value.get_value()),
+    }
+}
+
+// The number as a whole number (if it's a whole number, which can be stored exactly)
+pub fn whole_number(number: f64) -> Option<i64> {
+    (number.fract() == 0_f64 && number.abs() <= MAX_WHOLE_NUMBER).then_some(number as i64)
+}
+
+// Evaluates the expression made up of the tokens
+// The operators are evaluated by their precedence (from the lowest to the highest):
+// "+ -", "* / : %", the signs (e.g. -x), "^"
+// e.g. 2+3*4 = 14, -2^2 = -4, 2^-1 = 0.5
+// The operators with the same precedence are evaluated from left to right (e.g. 8/4*2 = 4),
+// except the exponentiation, which is evaluated from right to left (e.g. 2^3^2 = 2^9)
+pub fn evaluate(tokens: &[Token], turtle: &mut Turtle) -> Result<VariableTypes, TurtleError> {
+    evaluate_nested(tokens, 0, turtle)
+}
+
+// <depth> is the number of the expressions around the tokens
+fn evaluate_nested(
+    tokens: &[Token],
+    depth: usize,
     turtle: &mut Turtle,
-    locale: &[Locale],
-    selected_locale: usize,
-) -> f64 {
-    // Removes whitespaces
-    let mut trimmed_input = trim_whitespace(input.as_str());
-    trimmed_input.push('#');
-    // We split up the input to tokens
-    // e.g. input:  15+9+8+(6+5)+x
-    //      output: ["15","9", "(6", "5)", x]
-    let mut tokens: Vec<String> = vec![];
-    let mut operators: Vec<char> = vec![];
+) -> Result<VariableTypes, TurtleError> {
+    if depth > MAX_DEPTH {
+        return Err(TurtleError::new(
+            ErrorKind::ExpressionNestingLimit,
+            &tokens_text(tokens),
+            tokens_span(tokens),
+        ));
+    }
     let mut parenthesis_counter = 0;
-    let mut new_token = String::new().to_owned();
-    let allowed_operators = ['+', '-', '*', '/', ':', '%'];
-    trimmed_input.chars().for_each(|char| {
-        if allowed_operators.iter().any(|op| op == &char) && parenthesis_counter == 0 {
-            operators.push(char);
-            tokens.push(new_token.clone());
-            new_token = String::new();
-            return;
-        } else if char == '(' {
-            new_token.push(char);
-            parenthesis_counter += 1;
-            return;
-        } else if char == ')' {
-            new_token.push(char);
-            parenthesis_counter -= 1;
-            return;
+    for token in tokens {
+        match token.kind {
+            TokenKind::OpenParenthesis | TokenKind::OpenBracket => parenthesis_counter += 1,
+            TokenKind::CloseParenthesis | TokenKind::CloseBracket => parenthesis_counter -= 1,
+            _ => {}
         }
-        // Terminating the input
-        else if char == '#' {
-            tokens.push(new_token.clone());
-            new_token = String::new();
-            return;
+        // A parenthesis is closed, that hasn't been opened
+        if parenthesis_counter < 0 {
+            return Err(TurtleError::new(
+                ErrorKind::UnbalancedBrackets,
+                &token.text,
+                token.span,
+            ));
         }
-        new_token.push(char);
-    });
-    //
-    // Printing out tokens and operators
-    //
-    // println!("\nTokens{:?}\nOperators: {:?}", tokens, operators);
-    for op in 0..operators.len() {
-        // Multiplication
-        let mut result = 0_f64;
-        if operators[op] == '*' {
-            let nth_num = parse_to_number(
-                tokens.get(op).unwrap().clone(),
-                turtle,
-                locale,
-                selected_locale,
-            );
-            let nplusth_num = parse_to_number(
-                tokens.get(op + 1).unwrap().clone(),
-                turtle,
-                locale,
-                selected_locale,
-            );
-            result = nth_num * nplusth_num;
-            tokens[op + 1] = result.to_string();
-            tokens[op] = '#'.into();
-            operators[op] = '#';
+    }
+    // A parenthesis is opened, that is never closed
+    if parenthesis_counter != 0 {
+        return Err(TurtleError::new(
+            ErrorKind::UnbalancedBrackets,
+            &tokens_text(tokens),
+            tokens_span(tokens),
+        ));
+    }
+    let mut expression = Expression {
+        tokens,
+        position: 0,
+        depth,
+    };
+    let value = expression.parse_binary(0, turtle)?;
+    // The tokens after the expression don't belong to it (e.g. "2 3")
+    let rest = &tokens[expression.position.min(tokens.len())..];
+    if !rest.is_empty() {
+        return Err(TurtleError::new(
+            ErrorKind::InvalidExpression,
+            &tokens_text(rest),
+            tokens_span(rest),
+        ));
+    }
+    Ok(value)
+}
+
+// The precedence of the signs, they are evaluated before the other operators, except "^"
+const SIGN_PRECEDENCE: u8 = 3;
+
+// The precedence of the operators between two values
+fn precedence(operator: &str) -> Option<u8> {
+    match operator {
+        "+" | "-" => Some(1),
+        "*" | "/" | ":" | "%" => Some(2),
+        "^" => Some(4),
+        _ => None,
+    }
+}
+
+// Evaluates the tokens by precedence climbing, <position> is the next token to evaluate
+struct Expression<'a> {
+    tokens: &'a [Token],
+    position: usize,
+    // The number of the expressions and the signs around the next token
+    depth: usize,
+}
+
+impl<'a> Expression<'a> {
+    fn peek(&self) -> Option<&'a Token> {
+        self.tokens.get(self.position)
+    }
+    // Evaluates the operators with at least <min_precedence> precedence
+    // e.g. 1+2*3-4 (min_precedence: 0) -> 1 + (2*3) - 4
+    //      2*3-4 (min_precedence: 2) -> 2 * 3, and "-4" is left for the caller
+    fn parse_binary(
+        &mut self,
+        min_precedence: u8,
+        turtle: &mut Turtle,
+    ) -> Result<VariableTypes, TurtleError> {
+        let start = self.position;
+        let mut value = self.parse_unary(turtle)?;
+        while let Some(operator) = self
+            .peek()
+            .filter(|token| token.kind == TokenKind::Operator)
+        {
+            let Some(precedence) =
+                precedence(&operator.text).filter(|precedence| *precedence >= min_precedence)
+            else {
+                break;
+            };
+            let operator_position = self.position;
+            self.position += 1;
+            // The right side of "^" can contain another "^" (right associativity),
+            // but the right side of the other operators can only contain stronger operators
+            let right_start = self.position;
+            let right = if operator.text == "^" {
+                self.parse_binary(precedence, turtle)?
+            } else {
+                self.parse_binary(precedence + 1, turtle)?
+            };
+            value = apply_operator(
+                operator,
+                (value, &self.tokens[start..operator_position]),
+                (right, &self.tokens[right_start..self.position]),
+            )?;
         }
+        Ok(value)
     }
-    tokens.retain(|t| t != "#");
-    operators.retain(|&o| o != '#');
-    for op in 0..operators.len() {
-        // Float division
-        let mut result = 0_f64;
-        if operators[op] == '/' {
-            let nth_num = parse_to_number(
-                tokens.get(op).unwrap().clone(),
-                turtle,
-                locale,
-                selected_locale,
-            );
-            let nplusth_num = parse_to_number(
-                tokens.get(op + 1).unwrap().clone(),
-                turtle,
-                locale,
-                selected_locale,
-            );
-            result = nth_num / nplusth_num;
-            tokens[op + 1] = result.to_string();
-            tokens[op] = '#'.into();
-            operators[op] = '#';
+    // Evaluates the signs before the values (e.g. -x, +2, --3)
+    fn parse_unary(&mut self, turtle: &mut Turtle) -> Result<VariableTypes, TurtleError> {
+        let Some(sign) = self.peek().filter(|token| {
+            token.kind == TokenKind::Operator && (token.text == "-" || token.text == "+")
+        }) else {
+            return self.parse_primary(turtle);
+        };
+        // The signs are evaluated recursively like the nested expressions, e.g. "---x"
+        if self.depth >= MAX_DEPTH {
+            return Err(TurtleError::new(
+                ErrorKind::ExpressionNestingLimit,
+                &sign.text,
+                sign.span,
+            ));
         }
+        self.position += 1;
+        self.depth += 1;
+        let start = self.position;
+        let value = self.parse_binary(SIGN_PRECEDENCE, turtle)?;
+        self.depth -= 1;
+        let number = expect_number(&value, &self.tokens[start..self.position])?;
+        Ok(VariableTypes::Number {
+            value: if sign.text == "-" { -number } else { number },
+        })
     }
-    tokens.retain(|t| t != "#");
-    operators.retain(|&o| o != '#');
-    for op in 0..operators.len() {
-        // Integer division
-        let mut result = 0_f64;
-        if operators[op] == ':' {
-            let nth_num = parse_to_number(
-                tokens.get(op).unwrap().clone(),
-                turtle,
-                locale,
-                selected_locale,
-            );
-            let nplusth_num = parse_to_number(
-                tokens.get(op + 1).unwrap().clone(),
-                turtle,
-                locale,
-                selected_locale,
-            );
-            result = (nth_num / nplusth_num).floor();
-            tokens[op + 1] = result.to_string();
-            tokens[op] = '#'.into();
-            operators[op] = '#';
+    // Evaluates a value (a number, a text, a variable, a function call,
+    // an expression between parentheses or a list) and the indexes after it
+    fn parse_primary(&mut self, turtle: &mut Turtle) -> Result<VariableTypes, TurtleError> {
+        let start = self.position;
+        let Some(token) = self.peek() else {
+            // Missing values are reported at the token before them (e.g. at the operator)
+            let (text, span) = start
+                .checked_sub(1)
+                .and_then(|previous| self.tokens.get(previous))
+                .map_or((String::new(), Span::default()), |token| {
+                    (token.text.clone(), token.span)
+                });
+            return Err(TurtleError::new(ErrorKind::InvalidExpression, &text, span));
+        };
+        let next_kind = self.tokens.get(start + 1).map(|token| token.kind);
+        let mut value = match token.kind {
+            TokenKind::Number => match token.text.parse() {
+                Ok(value) => {
+                    self.position += 1;
+                    VariableTypes::Number { value }
+                }
+                Err(_) => {
+                    return Err(TurtleError::new(
+                        ErrorKind::InvalidExpression,
+                        &token.text,
+                        token.span,
+                    ))
+                }
+            },
+            TokenKind::String => {
+                self.position += 1;
+                let value = interpolate(token, self.depth + 1, turtle)?;
+                limit_size(VariableTypes::Text { value }, &token.text, token.span)?
+            }
+            // Calling a function, e.g. length("turtle")
+            TokenKind::Word if next_kind == Some(TokenKind::OpenParenthesis) => {
+                let end = self.closing(start + 1, TokenKind::CloseParenthesis)?;
+                self.position = end + 1;
+                let span = token.span.to(self.tokens[end].span);
+                let arguments = split_arguments(&self.tokens[start + 2..end]);
+                call_function(token, &arguments, span, self.depth + 1, turtle)?
+            }
+            TokenKind::Word => match turtle.get_variable(&token.text) {
+                Some(variable) => {
+                    self.position += 1;
+                    variable.variable_type.clone()
+                }
+                None => {
+                    return Err(TurtleError::new(
+                        ErrorKind::UndefinedVariable,
+                        &token.text,
+                        token.span,
+                    ))
+                }
+            },
+            // Evaluating the expression between the parentheses
+            TokenKind::OpenParenthesis => {
+                let end = self.closing(start, TokenKind::CloseParenthesis)?;
+                self.position = end + 1;
+                if end == start + 1 {
+                    let parentheses = &self.tokens[start..=end];
+                    return Err(TurtleError::new(
+                        ErrorKind::InvalidExpression,
+                        &tokens_text(parentheses),
+                        tokens_span(parentheses),
+                    ));
+                }
+                evaluate_nested(&self.tokens[start + 1..end], self.depth + 1, turtle)?
+            }
+            // Creating a list, e.g. [1, 2, 3]
+            TokenKind::OpenBracket => {
+                let end = self.closing(start, TokenKind::CloseBracket)?;
+                self.position = end + 1;
+                let mut values: Vec<VariableTypes> = vec![];
+                for element in split_arguments(&self.tokens[start + 1..end]) {
+                    values.push(evaluate_nested(element, self.depth + 1, turtle)?);
+                }
+                let list = &self.tokens[start..=end];
+                limit_size(
+                    VariableTypes::List { value: values },
+                    &tokens_text(list),
+                    tokens_span(list),
+                )?
+            }
+            _ => {
+                return Err(TurtleError::new(
+                    ErrorKind::InvalidExpression,
+                    &token.text,
+                    token.span,
+                ))
+            }
+        };
+        // Indexing a list or a text, e.g. points[2][0], "turtle"[0] (the indexes start from 0)
+        while self
+            .peek()
+            .is_some_and(|token| token.kind == TokenKind::OpenBracket)
+        {
+            let opening = self.position;
+            let end = self.closing(opening, TokenKind::CloseBracket)?;
+            self.position = end + 1;
+            let index_tokens = &self.tokens[opening + 1..end];
+            let index = number_value(index_tokens, self.depth + 1, turtle)?;
+            let element = match &value {
+                VariableTypes::List { value } => usize_index(index)
+                    .and_then(|index| value.get(index))
+                    .cloned()
+                    .ok_or(value.len()),
+                VariableTypes::Text { value } => usize_index(index)
+                    .and_then(|index| value.chars().nth(index))
+                    .map(|char| VariableTypes::Text {
+                        value: char.to_string(),
+                    })
+                    .ok_or(value.chars().count()),
+                _ => {
+                    let indexed = &self.tokens[start..opening];
+                    return Err(TurtleError::new(
+                        ErrorKind::ExpectedList,
+                        &tokens_text(indexed),
+                        tokens_span(indexed),
+                    ));
+                }
+            };
+            value = element.map_err(|length| {
+                TurtleError::new(
+                    ErrorKind::InvalidIndex { length },
+                    &tokens_text(index_tokens),
+                    tokens_span(&self.tokens[opening..=end]),
+                )
+            })?;
         }
+        Ok(value)
     }
-    tokens.retain(|t| t != "#");
-    operators.retain(|&o| o != '#');
-    for op in 0..operators.len() {
-        // Remainder
-        let mut result = 0_f64;
-        if operators[op] == '%' {
-            let nth_num = parse_to_number(
-                tokens.get(op).unwrap().clone(),
-                turtle,
-                locale,
-                selected_locale,
-            );
-            let nplusth_num = parse_to_number(
-                tokens.get(op + 1).unwrap().clone(),
-                turtle,
-                locale,
-                selected_locale,
-            );
-            result = nth_num % nplusth_num;
-            tokens[op + 1] = result.to_string();
-            tokens[op] = '#'.into();
-            operators[op] = '#';
+    // The position of the parenthesis or the bracket, which closes the one at <opening>
+    // (the parentheses are balanced, but "(" can't be closed by "]")
+    fn closing(&self, opening: usize, kind: TokenKind) -> Result<usize, TurtleError> {
+        let mut depth = 0;
+        for (index, token) in self.tokens.iter().enumerate().skip(opening) {
+            match token.kind {
+                TokenKind::OpenParenthesis | TokenKind::OpenBracket => depth += 1,
+                TokenKind::CloseParenthesis | TokenKind::CloseBracket => depth -= 1,
+                _ => {}
+            }
+            if depth == 0 {
+                if token.kind != kind {
+                    break;
+                }
+                return Ok(index);
+            }
         }
+        Err(TurtleError::new(
+            ErrorKind::UnbalancedBrackets,
+            &self.tokens[opening].text,
+            self.tokens[opening].span,
+        ))
     }
-    tokens.retain(|t| t != "#");
-    operators.retain(|&o| o != '#');
-
-    // Additions and subtraction
-    let mut result: f64 = parse_to_number(
-        tokens.first().unwrap().clone(),
-        turtle,
-        locale,
-        selected_locale,
-    );
-    for op in 0..operators.len() {
-        if operators[op] == '+' {
-            let new_number = parse_to_number(
-                tokens.get(op + 1).unwrap().clone(),
-                turtle,
-                locale,
-                selected_locale,
-            );
-            result += new_number;
-        } else if operators[op] == '-' {
-            let new_number = parse_to_number(
-                tokens.get(op + 1).unwrap().clone(),
-                turtle,
-                locale,
-                selected_locale,
-            );
-            result -= new_number;
+}
+
+// Applies the operator to the values, the values are given with their tokens
+// The texts and the lists can only be added together
+fn apply_operator(
+    operator: &Token,
+    (left, left_tokens): (VariableTypes, &[Token]),
+    (right, right_tokens): (VariableTypes, &[Token]),
+) -> Result<VariableTypes, TurtleError> {
+    let is_text = |value: &VariableTypes| matches!(value, VariableTypes::Text { .. });
+    if operator.text == "+" {
+        let span = tokens_span(left_tokens).to(tokens_span(right_tokens));
+        if let (VariableTypes::List { value: left }, VariableTypes::List { value: right }) =
+            (&left, &right)
+        {
+            let value = [left.as_slice(), right.as_slice()].concat();
+            return limit_size(VariableTypes::List { value }, &operator.text, span);
+        }
+        if is_text(&left) || is_text(&right) {
+            let value = left.to_string() + &right.to_string();
+            return limit_size(VariableTypes::Text { value }, &operator.text, span);
+        }
+    }
+    let left = expect_number(&left, left_tokens)?;
+    let right = expect_number(&right, right_tokens)?;
+    let value = match operator.text.as_str() {
+        "*" => left * right,
+        "/" => left / right,
+        ":" => (left / right).floor(),
+        "%" => left % right,
+        "^" => left.powf(right),
+        "+" => left + right,
+        _ => left - right,
+    };
+    Ok(VariableTypes::Number { value })
+}
+
+// The index of the element, if it's a whole number that isn't negative
+fn usize_index(index: f64) -> Option<usize> {
+    (index >= 0_f64 && index % 1_f64 == 0_f64).then_some(index as usize)
+}
+
+// Splits up the arguments of a function (or the elements of a list)
+// at the commas outside of the parentheses and the brackets
+fn split_arguments(tokens: &[Token]) -> Vec<&[Token]> {
+    if tokens.is_empty() {
+        return vec![];
+    }
+    let mut arguments: Vec<&[Token]> = vec![];
+    let mut depth = 0;
+    let mut start = 0_usize;
+    for (index, token) in tokens.iter().enumerate() {
+        match token.kind {
+            TokenKind::OpenParenthesis | TokenKind::OpenBracket => depth += 1,
+            TokenKind::CloseParenthesis | TokenKind::CloseBracket => depth -= 1,
+            TokenKind::Comma if depth == 0 => {
+                arguments.push(&tokens[start..index]);
+                start = index + 1;
+            }
+            _ => {}
         }
     }
-    //
-    // Printing out tokens and operators
-    //
-    //println!("\nTokens{:?}\nOperators: {:?}", tokens, operators);
-    result
+    arguments.push(&tokens[start..]);
+    arguments
 }
 
-fn parse_to_number(
-    raw_number: String,
+// <depth> is the number of the expressions around the arguments
+fn call_function(
+    name: &Token,
+    arguments: &[&[Token]],
+    span: Span,
+    depth: usize,
     turtle: &mut Turtle,
-    locale: &[Locale],
-    selected_locale: usize,
-) -> f64 {
-    let mut value = 0_f64;
-    if raw_number.contains(&['(', ')'][..]) {
-        /*// Searching for the beginning of the parenthesis
-        let parenthesis_begin_index: Vec<_> = raw_number.match_indices("(").collect();
-        // We split it off the string after the "("
-        let new_raw_number = raw_number.split_at(parenthesis_begin_index.first().unwrap().0 + 1);
-        // We search for the last ")"" parenthesis and we split it off the string
-        let parenthesis_end_index: Vec<_> = new_raw_number.1.match_indices(")").collect();
-        let final_raw_number = new_raw_number
-            .1
-            .split_at(parenthesis_end_index.last().unwrap().0);*/
-        let parenthesis_begin_index: Vec<_> = raw_number.match_indices("(").collect();
-        let parenthesis_begin = raw_number.split_at(parenthesis_begin_index.first().unwrap().0 + 1);
-        let parenthesis_begin_remainder = parenthesis_begin.1;
-        let parenthesis_end_index: Vec<_> =
-            parenthesis_begin_remainder.match_indices(")").collect();
-        let parenthesis_end =
-            parenthesis_begin_remainder.split_at(parenthesis_end_index.last().unwrap().0);
-        let final_raw_number = parenthesis_end.0;
-        value = parse_number_value(
-            final_raw_number.to_string(),
-            turtle,
-            locale,
-            selected_locale,
-        );
-    } else if turtle.variables.contains_key(&raw_number) {
-        value = turtle
-            .variables
-            .get(&raw_number)
-            .unwrap()
-            .variable_type
-            .get_value();
+) -> Result<VariableTypes, TurtleError> {
+    // Finding the function by its alias in the language of the application
+    let keyword = turtle.keywords.function(&name.text).map(str::to_string);
+    let is_function = |function: &str| keyword.as_deref() == Some(function);
+    let expected = if is_function(SUBSTRING_FUNCTION) {
+        3
+    } else if is_function(RANGE_FUNCTION) {
+        // The step of the range is optional
+        if arguments.len() == 3 {
+            3
+        } else {
+            2
+        }
+    } else if is_function(RANDOM_FUNCTION) {
+        // The minimum of the random number is optional
+        if arguments.len() == 2 {
+            2
+        } else {
+            1
+        }
+    } else if is_function(LENGTH_FUNCTION)
+        || is_function(TEXT_FUNCTION)
+        || is_function(NUMBER_FUNCTION)
+        || is_function(PICK_FUNCTION)
+        || is_function(SIN_FUNCTION)
+        || is_function(COS_FUNCTION)
+        || is_function(TAN_FUNCTION)
+        || is_function(ASIN_FUNCTION)
+        || is_function(ACOS_FUNCTION)
+        || is_function(ATAN_FUNCTION)
+    {
+        1
+    } else if is_function(RANDOM_FLOAT_FUNCTION) || is_function(HEADING_FUNCTION) {
+        0
+    } else {
+        return Err(TurtleError::new(
+            ErrorKind::UnknownFunction,
+            &name.text,
+            name.span,
+        ));
+    };
+    if arguments.len() != expected {
+        return Err(TurtleError::new(
+            ErrorKind::WrongArgumentCount { expected },
+            &name.text,
+            span,
+        ));
+    }
+    let mut values: Vec<VariableTypes> = vec![];
+    for argument in arguments {
+        values.push(evaluate_nested(argument, depth, turtle)?);
+    }
+    let text = values
+        .first()
+        .map(|value| value.to_string())
+        .unwrap_or_default();
+    if is_function(LENGTH_FUNCTION) {
+        let length = match &values[0] {
+            VariableTypes::List { value } => value.len(),
+            _ => text.chars().count(),
+        };
+        Ok(VariableTypes::Number {
+            value: length as f64,
+        })
+    } else if is_function(RANGE_FUNCTION) {
+        range(&values, arguments)
+    } else if is_function(RANDOM_FUNCTION) {
+        random(&values, arguments, span, turtle)
+    } else if is_function(RANDOM_FLOAT_FUNCTION) {
+        Ok(VariableTypes::Number {
+            value: turtle.random.float(),
+        })
+    } else if is_function(PICK_FUNCTION) {
+        pick(&values[0], arguments[0], turtle)
+    } else if is_function(HEADING_FUNCTION) {
+        Ok(VariableTypes::Number {
+            value: turtle.heading(),
+        })
+    } else if is_function(SIN_FUNCTION) || is_function(COS_FUNCTION) || is_function(TAN_FUNCTION) {
+        let angle = turtle
+            .angle_unit
+            .to_radians(expect_number(&values[0], arguments[0])?);
+        let value = if is_function(SIN_FUNCTION) {
+            angle.sin()
+        } else if is_function(COS_FUNCTION) {
+            angle.cos()
+        } else {
+            angle.tan()
+        };
+        Ok(VariableTypes::Number { value })
+    } else if is_function(ASIN_FUNCTION) || is_function(ACOS_FUNCTION) || is_function(ATAN_FUNCTION)
+    {
+        let number = expect_number(&values[0], arguments[0])?;
+        let radians = if is_function(ASIN_FUNCTION) {
+            number.asin()
+        } else if is_function(ACOS_FUNCTION) {
+            number.acos()
+        } else {
+            number.atan()
+        };
+        Ok(VariableTypes::Number {
+            value: turtle.angle_unit.from_radians(radians),
+        })
+    } else if is_function(SUBSTRING_FUNCTION) {
+        // The start and the count are rounded down, the negative values count as 0
+        let start = expect_number(&values[1], arguments[1])?.max(0_f64) as usize;
+        let count = expect_number(&values[2], arguments[2])?.max(0_f64) as usize;
+        Ok(VariableTypes::Text {
+            value: text.chars().skip(start).take(count).collect(),
+        })
+    } else if is_function(TEXT_FUNCTION) {
+        Ok(VariableTypes::Text { value: text })
     } else {
-        let value_result: Result<f64, _> = raw_number.parse();
-        value = match value_result {
-            Ok(val) => val,
-            Err(_e) => {
-                turtle.command_history.push(
-                    get_text(&locale.to_vec(), selected_locale)
-                        .invalid_expression
-                        .format(&[raw_number]),
-                );
-                return f64::NAN;
+        match text.trim().parse() {
+            Ok(value) => Ok(VariableTypes::Number { value }),
+            Err(_) => Err(TurtleError::new(
+                ErrorKind::ExpectedNumber,
+                &text,
+                tokens_span(arguments[0]),
+            )),
+        }
+    }
+}
+
+// Creates the list of the numbers from the start by the step until the end (exclusive boundary)
+// e.g. range(0, 10, 3) -> [0, 3, 6, 9], range(3, 0, -1) -> [3, 2, 1]
+fn range(values: &[VariableTypes], arguments: &[&[Token]]) -> Result<VariableTypes, TurtleError> {
+    let from = expect_number(&values[0], arguments[0])?;
+    let to = expect_number(&values[1], arguments[1])?;
+    let step = match values.get(2) {
+        Some(step) => expect_number(step, arguments[2])?,
+        None => 1_f64,
+    };
+    for (index, (value, kind)) in [
+        (from, ErrorKind::InvalidLoopStart),
+        (to, ErrorKind::InvalidLoopEnd),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        if !value.is_finite() {
+            return Err(TurtleError::new(
+                kind,
+                &tokens_text(arguments[index]),
+                tokens_span(arguments[index]),
+            ));
+        }
+    }
+    if !step.is_finite() || step == 0_f64 {
+        let argument = arguments.get(2).copied().unwrap_or_default();
+        return Err(TurtleError::new(
+            ErrorKind::InvalidLoopStep,
+            &tokens_text(argument),
+            tokens_span(argument),
+        ));
+    }
+    if (to - from) / step > MAX_RANGE_LENGTH as f64 {
+        return Err(TurtleError::new(
+            ErrorKind::RangeLength {
+                max: MAX_RANGE_LENGTH,
+            },
+            &arguments
+                .iter()
+                .map(|argument| tokens_text(argument))
+                .collect::<Vec<String>>()
+                .join(", "),
+            tokens_span(arguments[0]).to(tokens_span(arguments[arguments.len() - 1])),
+        ));
+    }
+    // The numbers are counted like in the loops, so the rounding errors don't pile up
+    let mut numbers: Vec<VariableTypes> = vec![];
+    let mut value = from;
+    while (step > 0_f64 && value < to) || (step < 0_f64 && value > to) {
+        numbers.push(VariableTypes::Number { value });
+        value = from + numbers.len() as f64 * step;
+    }
+    Ok(VariableTypes::List { value: numbers })
+}
+
+// Chooses a random whole number, random(<max>) is from 0 to <max>-1,
+// random(<min>, <max>) is from <min> to <max> (both of them are included)
+fn random(
+    values: &[VariableTypes],
+    arguments: &[&[Token]],
+    span: Span,
+    turtle: &mut Turtle,
+) -> Result<VariableTypes, TurtleError> {
+    let mut bounds: Vec<i64> = vec![];
+    for (value, argument) in values.iter().zip(arguments) {
+        let Some(bound) = whole_number(expect_number(value, argument)?) else {
+            return Err(TurtleError::new(
+                ErrorKind::InvalidRandomRange,
+                &tokens_text(argument),
+                tokens_span(argument),
+            ));
+        };
+        bounds.push(bound);
+    }
+    let (min, max) = if bounds.len() == 1 {
+        (0, bounds[0] - 1)
+    } else {
+        (bounds[0], bounds[1])
+    };
+    // e.g. random(0), random(5, 1)
+    if min > max {
+        let bounds: Vec<String> = arguments
+            .iter()
+            .map(|argument| tokens_text(argument))
+            .collect();
+        return Err(TurtleError::new(
+            ErrorKind::InvalidRandomRange,
+            &bounds.join(","),
+            span,
+        ));
+    }
+    Ok(VariableTypes::Number {
+        value: turtle.random.integer(min, max) as f64,
+    })
+}
+
+// Chooses a random element of the list (or a random character of the text)
+fn pick(
+    value: &VariableTypes,
+    argument: &[Token],
+    turtle: &mut Turtle,
+) -> Result<VariableTypes, TurtleError> {
+    let elements = match value {
+        VariableTypes::List { value } => value.clone(),
+        VariableTypes::Text { value } => value
+            .chars()
+            .map(|char| VariableTypes::Text {
+                value: char.to_string(),
+            })
+            .collect(),
+        _ => {
+            return Err(TurtleError::new(
+                ErrorKind::ExpectedList,
+                &tokens_text(argument),
+                tokens_span(argument),
+            ))
+        }
+    };
+    if elements.is_empty() {
+        return Err(TurtleError::new(
+            ErrorKind::InvalidRandomRange,
+            &tokens_text(argument),
+            tokens_span(argument),
+        ));
+    }
+    let index = turtle.random.integer(0, elements.len() as i64 - 1) as usize;
+    Ok(elements[index].clone())
+}
+
+// Creates the text of the string, the expressions between the braces are replaced with their values
+// e.g. input:  "x = {x * 2}\n" (x = 5)
+//      output: x = 10 (with a line break)
+// "{{" and "}}" are the braces themselves, and the backslash escapes the next character
+// <depth> is the number of the expressions around the expressions of the string
+fn interpolate(token: &Token, depth: usize, turtle: &mut Turtle) -> Result<String, TurtleError> {
+    let content = token.text.strip_prefix('"').unwrap_or(&token.text);
+    let content = content.strip_suffix('"').unwrap_or(content);
+    let mut text = String::new();
+    let mut chars = content.char_indices().peekable();
+    while let Some((index, char)) = chars.next() {
+        match char {
+            '\\' => match chars.next() {
+                Some((_, 'n')) => text.push('\n'),
+                Some((_, 't')) => text.push('\t'),
+                Some((_, escaped)) => text.push(escaped),
+                None => {}
+            },
+            '{' | '}' if chars.peek().is_some_and(|(_, next)| *next == char) => {
+                chars.next();
+                text.push(char);
+            }
+            '{' => {
+                let start = index + 1;
+                let end = match content[start..].find('}') {
+                    Some(length) => start + length,
+                    None => {
+                        return Err(TurtleError::new(
+                            ErrorKind::UnbalancedBrackets,
+                            &token.text,
+                            token.span,
+                        ))
+                    }
+                };
+                // The expression's tokens are moved to their place in the source code
+                let mut tokens = tokenize(&content[start..end]);
+                if tokens.is_empty() {
+                    return Err(TurtleError::new(
+                        ErrorKind::InvalidExpression,
+                        &token.text,
+                        token.span,
+                    ));
+                }
+                let offset = token.span.start + '"'.len_utf8() + start;
+                let column = token.span.column + content[..start].chars().count();
+                tokens.iter_mut().for_each(|expression_token| {
+                    expression_token.span.start += offset;
+                    expression_token.span.end += offset;
+                    expression_token.span.line = token.span.line;
+                    expression_token.span.column += column;
+                });
+                text.push_str(&evaluate_nested(&tokens, depth, turtle)?.to_string());
+                while chars.next_if(|(index, _)| *index <= end).is_some() {}
             }
+            _ => text.push(char),
         }
     }
-    value
+    Ok(text)
 }
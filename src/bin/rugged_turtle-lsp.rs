@@ -0,0 +1,10 @@
+// The language server of the turtle language, it's started by the code editors
+#[cfg(not(target_arch = "wasm32"))]
+fn main() {
+    let exit_code = rugged_turtle::lsp::serve(std::io::stdin().lock(), std::io::stdout().lock());
+    std::process::exit(exit_code);
+}
+
+// There are no code editors in the browser
+#[cfg(target_arch = "wasm32")]
+fn main() {}
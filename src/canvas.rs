@@ -0,0 +1,317 @@
+use std::{fmt::Write, sync::Arc};
+
+use egui::{
+    epaint::Tessellator, Align2, Color32, Context, FontId, Mesh, Pos2, Rect, Shape, Stroke, Vec2,
+};
+
+use crate::{
+    path::{LineCap, LineJoin, PathStore, PenDash, PenMode},
+    turtle::Turtle,
+};
+
+// Returns the color that the path is painted with, based on the pen's mode
+// (the path of penreverse has the inverse color of the background, with the opacity of the pen)
+pub fn stroke_color(color: Color32, mode: PenMode, background: Color32) -> Color32 {
+    match mode {
+        PenMode::Paint => color,
+        PenMode::Erase => background,
+        PenMode::InverseBackground => Color32::from_rgba_unmultiplied(
+            255 - background.r(),
+            255 - background.g(),
+            255 - background.b(),
+            color.a(),
+        ),
+    }
+}
+
+// A path is split to this many dashes at most
+const MAX_DASHES: usize = 10_000;
+
+// Chops up the path to the dashes of the dash pattern
+// e.g. input:  [(0, 0), (10, 0)] with 3 long dashes and 2 long gaps
+//      output: [[(0, 0), (3, 0)], [(5, 0), (8, 0)]]
+pub fn split_dashes(points: &[Pos2], dash: PenDash) -> Vec<Vec<Pos2>> {
+    let mut dashes: Vec<Vec<Pos2>> = vec![];
+    let mut current_dash: Vec<Pos2> = points.first().into_iter().copied().collect();
+    let mut drawing = true;
+    let mut remaining = dash.on;
+    for (index, segment) in points.windows(2).enumerate() {
+        let mut start = segment[0];
+        let end = segment[1];
+        let mut length = start.distance(end);
+        while length > remaining {
+            // The rest of the path is drawn as a solid line
+            // (the very long lines could be split forever, when the dashes are too short to change their length)
+            if dashes.len() >= MAX_DASHES {
+                let mut rest = if drawing { current_dash } else { vec![] };
+                if rest.last() != Some(&start) {
+                    rest.push(start);
+                }
+                rest.extend_from_slice(&points[index + 1..]);
+                dashes.push(rest);
+                return dashes;
+            }
+            let point = start + (end - start).normalized() * remaining;
+            length -= remaining;
+            start = point;
+            if drawing {
+                current_dash.push(point);
+                dashes.push(std::mem::take(&mut current_dash));
+            } else {
+                current_dash = vec![point];
+            }
+            drawing = !drawing;
+            remaining = if drawing { dash.on } else { dash.off };
+        }
+        remaining -= length;
+        if drawing {
+            current_dash.push(end);
+        }
+    }
+    if drawing && current_dash.len() > 1 {
+        dashes.push(current_dash);
+    }
+    dashes
+}
+
+// Creates the shapes of a single line, with its caps and joins
+fn line_shapes(
+    mut points: Vec<Pos2>,
+    stroke: Stroke,
+    cap: LineCap,
+    join: LineJoin,
+    shapes: &mut Vec<Shape>,
+) {
+    if points.len() < 2 {
+        return;
+    }
+    let radius = stroke.width / 2_f32;
+    if cap == LineCap::Square {
+        let first_direction = (points[0] - points[1]).normalized();
+        let last_direction = (points[points.len() - 1] - points[points.len() - 2]).normalized();
+        points[0] += first_direction * radius;
+        let last_index = points.len() - 1;
+        points[last_index] += last_direction * radius;
+    }
+    match join {
+        LineJoin::Miter | LineJoin::Round => {
+            shapes.push(Shape::line(points.clone(), stroke));
+            if join == LineJoin::Round {
+                points[1..points.len() - 1].iter().for_each(|point| {
+                    shapes.push(Shape::circle_filled(*point, radius, stroke.color))
+                });
+            }
+        }
+        LineJoin::Bevel => {
+            points.windows(2).for_each(|segment| {
+                shapes.push(Shape::line_segment([segment[0], segment[1]], stroke))
+            });
+            // Filling the gap on the outer side of the corners
+            points.windows(3).for_each(|corner| {
+                let incoming = (corner[1] - corner[0]).normalized();
+                let outgoing = (corner[2] - corner[1]).normalized();
+                let turn = incoming.x * outgoing.y - incoming.y * outgoing.x;
+                if turn == 0_f32 {
+                    return;
+                }
+                let side = -turn.signum() * radius;
+                shapes.push(Shape::convex_polygon(
+                    vec![
+                        corner[1],
+                        corner[1] + incoming.rot90() * side,
+                        corner[1] + outgoing.rot90() * side,
+                    ],
+                    stroke.color,
+                    Stroke::NONE,
+                ));
+            });
+        }
+    }
+    if cap == LineCap::Round {
+        shapes.push(Shape::circle_filled(points[0], radius, stroke.color));
+        shapes.push(Shape::circle_filled(
+            points[points.len() - 1],
+            radius,
+            stroke.color,
+        ));
+    }
+}
+
+// Creates the shapes of the drawing, that can be painted on the canvas
+pub fn drawing_shapes(path: &PathStore, background: Color32) -> Vec<Shape> {
+    let mut shapes: Vec<Shape> = vec![];
+    for line in &path.lines {
+        let style = path.style(line);
+        let stroke = Stroke::new(
+            style.width,
+            stroke_color(style.color, style.mode, background),
+        );
+        let dashes = match style.dash {
+            Some(dash) => split_dashes(&line.points, dash),
+            None => vec![line.points.clone()],
+        };
+        dashes
+            .into_iter()
+            .for_each(|dash| line_shapes(dash, stroke, style.cap, style.join, &mut shapes));
+    }
+    shapes
+}
+
+// Creates the shapes of the labels, they are laid out every time (the fonts cache their layouts)
+pub fn label_shapes(ctx: &Context, path: &PathStore) -> Vec<Shape> {
+    ctx.fonts_mut(|fonts| {
+        path.labels
+            .iter()
+            .map(|label| {
+                Shape::text(
+                    fonts,
+                    label.position,
+                    Align2::LEFT_BOTTOM,
+                    &label.text,
+                    FontId::proportional(label.size),
+                    label.color,
+                )
+            })
+            .collect()
+    })
+}
+
+// Keeps the tessellated mesh of the drawing, so it's only rebuilt when the drawing changes
+#[derive(Default)]
+pub struct CanvasCache {
+    key: Option<(u64, Color32, u32)>,
+    mesh: Arc<Mesh>,
+}
+
+impl CanvasCache {
+    pub fn shape(&mut self, ctx: &Context, path: &PathStore, background: Color32) -> Shape {
+        let pixels_per_point = ctx.pixels_per_point();
+        let key = (path.revision(), background, pixels_per_point.to_bits());
+        if self.key != Some(key) {
+            let mut tessellator = Tessellator::new(
+                pixels_per_point,
+                ctx.tessellation_options(|options| *options),
+                ctx.fonts(|fonts| fonts.font_image_size()),
+                vec![],
+            );
+            let mut mesh = Mesh::default();
+            drawing_shapes(path, background)
+                .into_iter()
+                .for_each(|shape| tessellator.tessellate_shape(shape, &mut mesh));
+            self.mesh = Arc::new(mesh);
+            self.key = Some(key);
+        }
+        Shape::mesh(self.mesh.clone())
+    }
+}
+
+fn svg_color(color: Color32) -> String {
+    let [r, g, b, a] = color.to_srgba_unmultiplied();
+    format!(
+        "stroke=\"rgb({}, {}, {})\" stroke-opacity=\"{}\"",
+        r,
+        g,
+        b,
+        a as f32 / 255_f32
+    )
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// Exports the turtle's drawing as an SVG image
+pub fn to_svg(turtle: &Turtle, background: Color32) -> String {
+    let mut bounds = Rect::NOTHING;
+    let mut margin = 0_f32;
+    for line in &turtle.path.lines {
+        line.points
+            .iter()
+            .for_each(|point| bounds.extend_with(*point));
+        margin = margin.max(turtle.path.style(line).width);
+    }
+    // The width of the labels is only estimated, because the SVG viewer lays out the text
+    for label in &turtle.path.labels {
+        bounds.extend_with(label.position - Vec2::new(0_f32, label.size));
+        bounds.extend_with(
+            label.position + Vec2::new(label.text.chars().count() as f32 * label.size, 0_f32),
+        );
+    }
+    // The straight lines have zero width or height, only the empty drawing has no bounds
+    if !bounds.is_finite() {
+        bounds = Rect::from_center_size(turtle.position, Vec2::ZERO);
+    }
+    bounds = bounds.expand(margin.max(1_f32));
+    let [r, g, b, _] = background.to_srgba_unmultiplied();
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"{} {} {} {}\">",
+        bounds.width(),
+        bounds.height(),
+        bounds.min.x,
+        bounds.min.y,
+        bounds.width(),
+        bounds.height()
+    );
+    let _ = writeln!(
+        svg,
+        "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"rgb({}, {}, {})\"/>",
+        bounds.min.x,
+        bounds.min.y,
+        bounds.width(),
+        bounds.height(),
+        r,
+        g,
+        b
+    );
+    for polyline in &turtle.path.lines {
+        let style = turtle.path.style(polyline);
+        let mut line = String::from("<polyline fill=\"none\" points=\"");
+        polyline.points.iter().for_each(|point| {
+            let _ = write!(line, "{},{} ", point.x, point.y);
+        });
+        let _ = write!(line, "\" stroke-width=\"{}\"", style.width);
+        // The lines have the same colors as on the canvas
+        let _ = write!(
+            line,
+            " {}",
+            svg_color(stroke_color(style.color, style.mode, background))
+        );
+        if let Some(dash) = style.dash {
+            let _ = write!(line, " stroke-dasharray=\"{} {}\"", dash.on, dash.off);
+        }
+        let _ = match style.cap {
+            LineCap::Butt => write!(line, " stroke-linecap=\"butt\""),
+            LineCap::Round => write!(line, " stroke-linecap=\"round\""),
+            LineCap::Square => write!(line, " stroke-linecap=\"square\""),
+        };
+        let _ = match style.join {
+            LineJoin::Miter => write!(line, " stroke-linejoin=\"miter\""),
+            LineJoin::Round => write!(line, " stroke-linejoin=\"round\""),
+            LineJoin::Bevel => write!(line, " stroke-linejoin=\"bevel\""),
+        };
+        line.push_str("/>");
+        let _ = writeln!(svg, "{}", line);
+    }
+    for label in &turtle.path.labels {
+        let [r, g, b, a] = label.color.to_srgba_unmultiplied();
+        let _ = writeln!(
+            svg,
+            "<text x=\"{}\" y=\"{}\" font-size=\"{}\" fill=\"rgb({}, {}, {})\" fill-opacity=\"{}\">{}</text>",
+            label.position.x,
+            label.position.y,
+            label.size,
+            r,
+            g,
+            b,
+            a as f32 / 255_f32,
+            escape_xml(&label.text)
+        );
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
@@ -0,0 +1,384 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use egui::Color32;
+
+use crate::{
+    analysis::lint_script,
+    arithmetic::whole_number,
+    canvas::to_svg,
+    commands::execute_command,
+    documentation::Manual,
+    formatting::format_script,
+    history::history_lines,
+    keywords::{KeywordTable, Keywords},
+    locale::{check_locale, import_locales, Locale},
+    message::Argument,
+    random::set_default_seed,
+    turtle::Turtle,
+};
+
+// What the application does, when it's started
+#[derive(Debug, PartialEq)]
+pub enum Action {
+    // Starts the window of the application
+    App,
+    // Executes the script without a window
+    Run {
+        script: PathBuf,
+        svg: Option<PathBuf>,
+    },
+    // Compares the locale files with the default (English) locale
+    LocaleCheck {
+        files: Vec<PathBuf>,
+    },
+    // Formats and lints the scripts
+    Format {
+        scripts: Vec<PathBuf>,
+        normalize: bool,
+        check: bool,
+    },
+}
+
+// The wrong command line arguments, they are printed with the usage of the program
+#[derive(Debug, PartialEq)]
+pub enum ArgumentError {
+    // The seed isn't a whole number, e.g. "--seed 1.5"
+    InvalidSeed(String),
+    // The option is the last argument, e.g. "--svg"
+    MissingValue(String),
+    UnknownArgument(String),
+    // The option isn't used by the command, e.g. "fmt square.txt --svg square.svg"
+    OptionWithoutCommand { option: String, command: String },
+    // The command has no files, e.g. "run"
+    MissingFiles,
+    // The language of the "--language" option isn't loaded
+    UnknownLanguage(String),
+}
+
+impl ArgumentError {
+    pub fn message(&self, locale: &Locale) -> String {
+        match self {
+            Self::InvalidSeed(value) => locale.format(
+                &locale.cli_invalid_seed_error,
+                &[("value", Argument::from(value.as_str()))],
+            ),
+            Self::MissingValue(option) => locale.format(
+                &locale.cli_missing_value_error,
+                &[("option", Argument::from(option.as_str()))],
+            ),
+            Self::UnknownArgument(argument) => locale.format(
+                &locale.cli_unknown_argument_error,
+                &[("argument", Argument::from(argument.as_str()))],
+            ),
+            Self::OptionWithoutCommand { option, command } => locale.format(
+                &locale.cli_option_without_command_error,
+                &[
+                    ("option", Argument::from(option.as_str())),
+                    ("command", Argument::from(command.as_str())),
+                ],
+            ),
+            Self::MissingFiles => locale.cli_missing_files_error.to_string(),
+            Self::UnknownLanguage(language) => locale.format(
+                &locale.cli_unknown_language_error,
+                &[("language", Argument::from(language.as_str()))],
+            ),
+        }
+    }
+}
+
+// The value of the "--language" option, the messages of the commands are printed in this language
+// (the arguments are searched for it before they are processed, so their errors can be translated)
+pub fn language_argument(arguments: &[String]) -> Option<&str> {
+    arguments
+        .iter()
+        .position(|argument| argument == "--language")
+        .and_then(|index| arguments.get(index + 1))
+        .map(String::as_str)
+}
+
+// The locale of the language (case-insensitive, e.g. "hu") and the other loaded locales,
+// or the default (English) locale and every loaded locale without a language
+pub fn select_locale(language: Option<&str>) -> Result<(Locale, Vec<Locale>), ArgumentError> {
+    let (mut locales, _) = import_locales();
+    let Some(language) = language else {
+        return Ok((Locale::default(), locales));
+    };
+    match locales
+        .iter()
+        .position(|locale| locale.language_id.eq_ignore_ascii_case(language))
+    {
+        Some(index) => Ok((locales.remove(index), locales)),
+        None => Err(ArgumentError::UnknownLanguage(language.to_string())),
+    }
+}
+
+// Processes the command line arguments (without the name of the program)
+// e.g. input:  ["run", "square.txt", "--seed", "42"]
+//      output: Run { script: "square.txt", svg: None } (and the default seed is 42)
+pub fn parse_arguments(
+    arguments: impl IntoIterator<Item = String>,
+) -> Result<Action, ArgumentError> {
+    let mut arguments = arguments.into_iter();
+    let mut run = false;
+    let mut locale_check = false;
+    let mut format = false;
+    let mut language = false;
+    let mut normalize = false;
+    let mut check = false;
+    let mut files: Vec<PathBuf> = vec![];
+    let mut script: Option<PathBuf> = None;
+    let mut svg: Option<PathBuf> = None;
+    while let Some(argument) = arguments.next() {
+        match argument.as_str() {
+            "--seed" => {
+                let value = arguments.next().unwrap_or_default();
+                let Some(seed) = value.parse().ok().and_then(whole_number) else {
+                    return Err(ArgumentError::InvalidSeed(value));
+                };
+                set_default_seed(seed as u64);
+            }
+            "--svg" => match arguments.next() {
+                Some(path) => svg = Some(PathBuf::from(path)),
+                None => return Err(ArgumentError::MissingValue(argument)),
+            },
+            // The language is selected by language_argument
+            "--language" => match arguments.next() {
+                Some(_) => language = true,
+                None => return Err(ArgumentError::MissingValue(argument)),
+            },
+            "--normalize" => normalize = true,
+            "--check" => check = true,
+            "run" if !run && !locale_check && !format => run = true,
+            "locale-check" if !run && !locale_check && !format => locale_check = true,
+            "fmt" if !run && !locale_check && !format => format = true,
+            _ if run && script.is_none() && !argument.starts_with("--") => {
+                script = Some(PathBuf::from(argument))
+            }
+            _ if (locale_check || format) && !argument.starts_with("--") => {
+                files.push(PathBuf::from(argument))
+            }
+            _ => return Err(ArgumentError::UnknownArgument(argument)),
+        }
+    }
+    let without = |option: &str, command: &str| {
+        Err(ArgumentError::OptionWithoutCommand {
+            option: option.to_string(),
+            command: command.to_string(),
+        })
+    };
+    if svg.is_some() && !run {
+        return without("--svg", "run");
+    }
+    if (normalize || check) && !format {
+        return without(if normalize { "--normalize" } else { "--check" }, "fmt");
+    }
+    if language && !(run || locale_check || format) {
+        return without("--language", "run, locale-check, fmt");
+    }
+    if format {
+        return match files.is_empty() {
+            true => Err(ArgumentError::MissingFiles),
+            false => Ok(Action::Format {
+                scripts: files,
+                normalize,
+                check,
+            }),
+        };
+    }
+    if locale_check {
+        return match files.is_empty() {
+            true => Err(ArgumentError::MissingFiles),
+            false => Ok(Action::LocaleCheck { files }),
+        };
+    }
+    match (run, script) {
+        (false, _) => Ok(Action::App),
+        (true, Some(script)) => Ok(Action::Run { script, svg }),
+        (true, None) => Err(ArgumentError::MissingFiles),
+    }
+}
+
+// The file and the error in the messages of the commands
+fn file_error(message: &str, file: &Path, error: impl ToString, locale: &Locale) -> String {
+    locale.format(
+        message,
+        &[
+            ("file", Argument::from(file.display().to_string())),
+            ("error", Argument::from(error.to_string())),
+        ],
+    )
+}
+
+// Executes the script with a black pen, and returns the exit code of the program
+// (0 - the script was executed, 1 - the script failed, 2 - the files couldn't be read or written)
+// The keywords of the language and the English keywords can be used, like in the application
+pub fn run_script(script: &Path, svg: Option<&Path>, locale: &Locale) -> i32 {
+    let source = match fs::read_to_string(script) {
+        Ok(source) => source,
+        Err(error) => {
+            eprintln!(
+                "{}",
+                file_error(&locale.cli_read_error, script, error, locale)
+            );
+            return 2;
+        }
+    };
+    let mut turtle = Turtle::default();
+    turtle.pen.color = Color32::BLACK;
+    turtle.pen.width = 1_f32;
+    turtle.keywords = KeywordTable::new(&[&locale.keywords, &Keywords::default()])
+        .documented(&[&locale.documentation, &Manual::default()]);
+    let result = execute_command(source, &mut turtle);
+    for line in history_lines(&turtle.command_history, locale, &turtle.keywords) {
+        println!("{}", line);
+    }
+    if let Some(svg) = svg {
+        if let Err(error) = fs::write(svg, to_svg(&turtle, Color32::WHITE)) {
+            eprintln!(
+                "{}",
+                file_error(&locale.cli_write_error, svg, error, locale)
+            );
+            return 2;
+        }
+    }
+    match result {
+        Ok(()) => 0,
+        Err(error) => {
+            eprintln!("{}", error.message(locale));
+            1
+        }
+    }
+}
+
+// Prints the differences between the locale files and the default locale, and returns the exit code
+// (0 - the files have every key, 1 - some keys are missing or different, 2 - the files can't be loaded)
+pub fn check_locales(files: &[PathBuf], locale: &Locale) -> i32 {
+    let mut exit_code = 0;
+    for file in files {
+        let report = fs::read_to_string(file)
+            .map_err(|error| error.to_string())
+            .and_then(|contents| check_locale(&contents));
+        let report = match report {
+            Ok(report) => report,
+            Err(error) => {
+                eprintln!(
+                    "{}",
+                    file_error(&locale.cli_locale_load_error, file, error, locale)
+                );
+                exit_code = 2;
+                continue;
+            }
+        };
+        let path = Argument::from(file.display().to_string());
+        if report.is_empty() {
+            println!(
+                "{}",
+                locale.format(&locale.cli_locale_ok, &[("file", path)])
+            );
+            continue;
+        }
+        println!("{}:", file.display());
+        for key in &report.missing {
+            let key = Argument::from(key.as_str());
+            println!(
+                "  {}",
+                locale.format(&locale.cli_missing_key, &[("key", key)])
+            );
+        }
+        for key in &report.extra {
+            let key = Argument::from(key.as_str());
+            println!(
+                "  {}",
+                locale.format(&locale.cli_extra_key, &[("key", key)])
+            );
+        }
+        let list = |placeholders: &[String]| {
+            Argument::from(
+                placeholders
+                    .iter()
+                    .map(|placeholder| format!("{{{}}}", placeholder))
+                    .collect::<Vec<String>>()
+                    .join(", "),
+            )
+        };
+        for (key, translated, expected) in &report.placeholders {
+            let key = ("key", Argument::from(key.as_str()));
+            let expected = ("expected", list(expected));
+            let line = match translated {
+                Some(translated) => locale.format(
+                    &locale.cli_different_placeholders,
+                    &[key, ("translated", list(translated)), expected],
+                ),
+                None => locale.format(&locale.cli_invalid_placeholders, &[key, expected]),
+            };
+            println!("  {}", line);
+        }
+        exit_code = exit_code.max(1);
+    }
+    exit_code
+}
+
+// Formats the scripts and prints their warnings, and returns the exit code
+// (0 - the scripts are formatted, 1 - a script has a syntax error or isn't formatted (with --check),
+// 2 - the files can't be read or written)
+// The aliases of every language are recognized, they are normalized to the aliases of the language
+pub fn format_scripts(
+    scripts: &[PathBuf],
+    normalize: bool,
+    check: bool,
+    locale: &Locale,
+    other_locales: &[Locale],
+) -> i32 {
+    let mut languages: Vec<&Keywords> = vec![&locale.keywords];
+    languages.extend(other_locales.iter().map(|locale| &locale.keywords));
+    let english = Keywords::default();
+    languages.push(&english);
+    let keywords =
+        KeywordTable::new(&languages).documented(&[&locale.documentation, &Manual::default()]);
+    let mut exit_code = 0;
+    for script in scripts {
+        let source = match fs::read_to_string(script) {
+            Ok(source) => source,
+            Err(error) => {
+                eprintln!(
+                    "{}",
+                    file_error(&locale.cli_read_error, script, error, locale)
+                );
+                exit_code = 2;
+                continue;
+            }
+        };
+        let formatted = match format_script(&source, normalize.then_some(&keywords)) {
+            Ok(formatted) => formatted,
+            Err(error) => {
+                println!("{}: {}", script.display(), error.message(locale));
+                exit_code = exit_code.max(1);
+                continue;
+            }
+        };
+        for warning in lint_script(&source, &keywords) {
+            println!("{}: {}", script.display(), warning.message(locale));
+        }
+        if formatted == source {
+            continue;
+        }
+        if check {
+            let path = Argument::from(script.display().to_string());
+            println!(
+                "{}",
+                locale.format(&locale.cli_not_formatted, &[("file", path)])
+            );
+            exit_code = exit_code.max(1);
+        } else if let Err(error) = fs::write(script, formatted) {
+            eprintln!(
+                "{}",
+                file_error(&locale.cli_write_error, script, error, locale)
+            );
+            exit_code = 2;
+        }
+    }
+    exit_code
+}
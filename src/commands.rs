@@ -1,22 +1,34 @@
-use std::{f32::consts::PI, vec};
+use std::fmt::{self, Display, Formatter};
 
-use dyn_fmt::AsStrFormatExt;
-use egui::{Color32, TextBuffer};
+use egui::{ahash::HashMap, Color32};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    arithmetic::parse_number_value,
-    locale::{get_text, Locale},
-    parsing::trim_whitespace,
-    turtle::Turtle,
+    arithmetic::{evaluate, parse_number_value, whole_number, MAX_VALUE_SIZE},
+    error::{ErrorKind, Span, TurtleError},
+    history::{HelpTopic, HistoryEntry},
+    parsing::{parse, tokenize, tokens_span, tokens_text, Statement, Token},
+    path::{Label, LineCap, LineJoin, PenDash, PenMode, MIN_DASH_LENGTH},
+    turtle::{AngleUnit, BoundaryMode, Orientation, Turtle},
 };
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
 pub enum VariableTypes {
     Boolean { value: bool },
     Number { value: f64 },
+    Text { value: String },
+    List { value: Vec<VariableTypes> },
 }
 
 impl VariableTypes {
+    // The number of the values and the characters, with the elements of the nested lists
+    // e.g. [1, "ab", []] -> 5 (the list, 1, the 2 characters and the empty list)
+    pub fn size(&self) -> usize {
+        match self {
+            VariableTypes::Text { value } => value.chars().count(),
+            VariableTypes::List { value } => 1 + value.iter().map(Self::size).sum::<usize>(),
+            _ => 1,
+        }
+    }
     pub fn get_value(&self) -> f64 {
         let mut val = 0_f64;
         if let VariableTypes::Number { value } = self {
@@ -33,6 +45,31 @@ impl VariableTypes {
     }
 }
 
+// The value as it's printed (the texts are printed without the quotation marks,
+// except inside of the lists, e.g. [1, "a", [2, 3]])
+impl Display for VariableTypes {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            VariableTypes::Boolean { value } => write!(f, "{}", value),
+            VariableTypes::Number { value } => write!(f, "{}", value),
+            VariableTypes::Text { value } => write!(f, "{}", value),
+            VariableTypes::List { value } => {
+                write!(f, "[")?;
+                for (index, element) in value.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    match element {
+                        VariableTypes::Text { value } => write!(f, "\"{}\"", value)?,
+                        element => write!(f, "{}", element)?,
+                    }
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
 pub struct Variable {
     pub raw_value: String,
@@ -40,498 +77,830 @@ pub struct Variable {
     pub writable: bool,
 }
 
+// Procedure defined by the user, it's called like the commands
+#[derive(Default, Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct Procedure {
+    pub parameters: Vec<String>,
+    pub block: Vec<Statement>,
+}
+
+// The aliases and the documentation of the commands are declared in the locale files
+// by the keywords of the commands
 #[derive(Default, Debug, PartialEq, Serialize, Deserialize, Clone)]
 pub struct Command {
-    aliases: &'static str,
-    //documentation: todo!(),
+    keyword: &'static str,
 }
 
-const FORWARD: Command = Command {
-    aliases: "e elore f fd forward",
-    //documentation:todo!(),
-};
+impl Command {
+    fn has_alias(&self, name: &str, turtle: &Turtle) -> bool {
+        turtle.keywords.command(name) == Some(self.keyword)
+    }
+}
 
-const ROTATE_RIGHT: Command = Command {
-    aliases: "j jobb jobbra r rt right",
-    //documentation:todo!(),
-};
+const FORWARD: Command = Command { keyword: "forward" };
+
+const ROTATE_RIGHT: Command = Command { keyword: "right" };
 
-const ROTATE_LEFT: Command = Command {
-    aliases: "b bal balra l lt left",
-    //documentation:todo!(),
+const ROTATE_LEFT: Command = Command { keyword: "left" };
+
+// setheading(<angle>) - turns the turtle to the direction (in the angle unit and the orientation)
+const SETHEADING: Command = Command {
+    keyword: "setheading",
 };
 
+// The unit of the angles
+const DEGREES: Command = Command { keyword: "degrees" };
+
+const RADIANS: Command = Command { keyword: "radians" };
+
+// The orientation of the heading
+// compass - 0 is north, and the heading grows clockwise (this is the default)
+// math - 0 is east, and the heading grows counter-clockwise
+const COMPASS: Command = Command { keyword: "compass" };
+
+const MATH: Command = Command { keyword: "math" };
+
 const PENCOLOR: Command = Command {
-    aliases: "tsz tollszin szin pc pencolor color",
-    //documentation:todo!(),
+    keyword: "pencolor",
 };
 
 const PENWIDTH: Command = Command {
-    aliases: "tv tollvastagsag vastagsag pw penwidth width",
-    //documentation:todo!(),
+    keyword: "penwidth",
 };
 
-const PENUP: Command = Command {
-    aliases: "tf tollfel pu penup up",
-    //documentation:todo!(),
-};
+const PENUP: Command = Command { keyword: "penup" };
+
+const PENDOWN: Command = Command { keyword: "pendown" };
 
-const PENDOWN: Command = Command {
-    aliases: "tl tollle pd pendown down",
-    //documentation:todo!(),
+const PENPAINT: Command = Command {
+    keyword: "penpaint",
 };
 
-const PRINTVAL: Command = Command {
-    aliases: "kier kiertekeles kiszamolas eval calc calculate evaluate",
-    //documentation:todo!(),
+const PENERASE: Command = Command {
+    keyword: "penerase",
 };
 
-const PRINTRAW: Command = Command {
-    aliases: "ki kiir kiiratas print",
-    //documentation:todo!(),
+const PENREVERSE: Command = Command {
+    keyword: "penreverse",
 };
 
-const CLEAR: Command = Command {
-    aliases: "trl torol clr clear",
-    //documentation:todo!(),
+const PENDASH: Command = Command { keyword: "pendash" };
+
+const LINECAP: Command = Command { keyword: "linecap" };
+
+const LINEJOIN: Command = Command {
+    keyword: "linejoin",
 };
 
-const RESET: Command = Command {
-    aliases: "alaphelyzet reset default",
-    //documentation:todo!(),
+const WRAP: Command = Command { keyword: "wrap" };
+
+const WINDOW: Command = Command { keyword: "window" };
+
+const FENCE: Command = Command { keyword: "fence" };
+
+// Accepted values of the linecap and linejoin commands (their aliases are in the locale files)
+const BUTT_VALUE: &str = "butt";
+const ROUND_VALUE: &str = "round";
+const SQUARE_VALUE: &str = "square";
+const MITER_VALUE: &str = "miter";
+const BEVEL_VALUE: &str = "bevel";
+
+const PRINTVAL: Command = Command {
+    keyword: "evaluate",
 };
 
-const REPEAT: Command = Command {
-    aliases: "i ism ismetles r rep repeat for",
-    //documentation:todo!(),
+const PRINTRAW: Command = Command { keyword: "print" };
+
+const LABEL: Command = Command { keyword: "label" };
+
+// append(<list>, <value>) - adds the value to the end of the list variable
+const APPEND: Command = Command { keyword: "append" };
+
+// The default size of the labels' font
+const LABEL_SIZE: f64 = 16_f64;
+
+// seed(<number>) - the random numbers are the same after the same seed
+const SEED: Command = Command { keyword: "seed" };
+
+const CLEAR: Command = Command { keyword: "clear" };
+
+const RESET: Command = Command { keyword: "reset" };
+
+const REPEAT: Command = Command { keyword: "repeat" };
+
+// foreach(<variable>, <list>) {commands} - the variable goes through the elements of the list
+const FOREACH: Command = Command { keyword: "foreach" };
+
+const BREAK: Command = Command { keyword: "break" };
+
+const CONTINUE: Command = Command {
+    keyword: "continue",
 };
 
-const HELP: Command = Command {
-    aliases: "? segitseg help",
-    //documentation:todo!(),
+// stop - leaves the procedure, or stops the program outside of the procedures
+const STOP: Command = Command { keyword: "stop" };
+
+const HELP: Command = Command { keyword: "help" };
+
+// Declarations of the variables
+// local <var> = <value> - the variable only exists in the block, where it's declared
+// global <var> = <value> - the variable exists everywhere
+// const <var> = <value> - the variable can't be updated (it's local)
+const LOCAL: Command = Command { keyword: "local" };
+
+const GLOBAL: Command = Command { keyword: "global" };
+
+const CONST: Command = Command { keyword: "const" };
+
+// to <name>(<parameters>) {commands} - defines a procedure
+const PROCEDURE: Command = Command {
+    keyword: "procedure",
 };
 
-pub fn execute_command(
-    commandstring: String,
+// The blocks and the procedure calls can be nested this deep
+const MAX_DEPTH: usize = 100;
+
+// A program can execute this many blocks (loop iterations and procedure calls),
+// so an endless loop (e.g. repeat(1e15)) stops with an error instead of freezing the application
+const MAX_STEPS: usize = 1_000_000;
+
+pub fn execute_command(commandstring: String, turtle: &mut Turtle) -> Result<(), TurtleError> {
+    // The whole input is parsed before it's executed, so a syntax error doesn't leave a half-drawn picture
+    let statements = parse(&tokenize(&commandstring))?;
+    turtle.scopes.clear();
+    turtle.depth = 0;
+    turtle.steps = 0;
+    execute_block(&statements, false, turtle).map(|_| ())
+}
+
+// How the execution continues after a command
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Flow {
+    Next,
+    // The loop is exited
+    Break,
+    // The loop jumps to its next iteration
+    Continue,
+    // The procedure (or the program) is left
+    Stop,
+}
+
+// Executes the statements, the execution stops at the first statement that fails
+// or at the first break/continue (<in_loop> tells whether they are allowed) or stop
+fn execute_block(
+    statements: &[Statement],
+    in_loop: bool,
     turtle: &mut Turtle,
-    locale: &[Locale],
-    selected_locale: usize,
-) {
-    // Removing whitespaces
-    let trimmed_commandstring: String = trim_whitespace(&commandstring);
-    // This splits off the input
-    // e.g. initial input:      "forward(100);right(90);forward(10)"
-    //      processed output:   ["forward(100)", "right(90)", "forward(10)"]
-    let mut splitted_command_tokens: Vec<&str> = trimmed_commandstring.split(";").collect();
-    let mut command_tokens: Vec<String> = splitted_command_tokens
-        .iter_mut()
-        .map(|f| f.to_string())
-        .collect();
-    let mut command_block_counter: i32 = 0;
-    let mut chained_commands: Vec<String> = vec![];
-    //
-    //  Printing out the command tokens before further processing
-    //
-    //println!("Raw cmd tokens: {:?}", command_tokens);
-
-    // We chain the command together by specifying how deep the nested function goes
-    // command_block_counter specifies the depth of the nested function
-    (0..command_tokens.clone().len()).for_each(|i| {
-        if command_tokens[i].contains("{")
-            || command_tokens[i].contains("}")
-            || command_block_counter > 0
+) -> Result<Flow, TurtleError> {
+    for statement in statements {
+        let flow = match statement {
+            Statement::Assignment {
+                declaration,
+                name,
+                value,
+            } => {
+                assign_variable(declaration.as_ref(), name, value, turtle)?;
+                Flow::Next
+            }
+            Statement::Procedure {
+                keyword,
+                name,
+                parameters,
+                block,
+                span,
+            } => {
+                define_procedure(keyword, name, parameters, block.as_deref(), *span, turtle)?;
+                Flow::Next
+            }
+            Statement::Command {
+                name,
+                arguments,
+                block,
+                span,
+            } => {
+                let call = CommandCall {
+                    name,
+                    args: arguments,
+                    span: *span,
+                };
+                let block = block.as_deref().unwrap_or_default();
+                // The loops and the procedures are executed from here,
+                // so the nested blocks use less of the stack
+                if REPEAT.has_alias(&name.text, turtle) {
+                    execute_loop(&call, block, turtle)?
+                } else if FOREACH.has_alias(&name.text, turtle) {
+                    execute_foreach(&call, block, turtle)?
+                } else if let Some(procedure) = turtle.procedures.get(&name.text).cloned() {
+                    call_procedure(&call, &procedure, turtle)?
+                } else {
+                    execute_single_command(&call, in_loop, turtle)?
+                }
+            }
+        };
+        if flow != Flow::Next {
+            return Ok(flow);
+        }
+    }
+    Ok(Flow::Next)
+}
+
+// Executes the statements in a new scope, the variables declared in it are dropped at its end
+// <call> is the loop or the procedure call, that executes the statements
+fn execute_scoped(
+    call: &CommandCall,
+    statements: &[Statement],
+    in_loop: bool,
+    scope: HashMap<String, Variable>,
+    turtle: &mut Turtle,
+) -> Result<Flow, TurtleError> {
+    // Too deep nesting (e.g. endless recursion) would overflow the stack
+    if turtle.depth >= MAX_DEPTH {
+        return Err(TurtleError::new(
+            ErrorKind::NestingLimit,
+            &call.name.text,
+            call.span,
+        ));
+    }
+    if turtle.steps >= MAX_STEPS {
+        return Err(TurtleError::new(
+            ErrorKind::StepLimit { max: MAX_STEPS },
+            &call.name.text,
+            call.span,
+        ));
+    }
+    turtle.steps += 1;
+    turtle.scopes.push(scope);
+    turtle.depth += 1;
+    let result = execute_block(statements, in_loop, turtle);
+    turtle.depth -= 1;
+    turtle.scopes.pop();
+    result
+}
+
+// Variable names can contain letters, digits and underscores, but they can't be numbers
+pub(crate) fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.parse::<f64>().is_err()
+        && name
+            .chars()
+            .all(|char| char.is_alphanumeric() || char == '_')
+}
+
+// This is where we declare the variable
+// <var>=<value> - value can be a boolean or a number
+// Without a declaration the variable is updated where it's declared, or it's declared in the block
+fn assign_variable(
+    declaration: Option<&Token>,
+    name: &Token,
+    value: &[Token],
+    turtle: &mut Turtle,
+) -> Result<(), TurtleError> {
+    if let Some(declaration) = declaration {
+        if ![&LOCAL, &GLOBAL, &CONST]
+            .iter()
+            .any(|command| command.has_alias(&declaration.text, turtle))
         {
-            command_block_counter += command_tokens[i].matches("{").count() as i32
-                - command_tokens[i].matches("}").count() as i32;
-            chained_commands.push(command_tokens[i].to_string());
-            command_tokens[i] = "".to_string();
+            // Two words after each other, which don't declare a variable
+            return Err(TurtleError::new(
+                ErrorKind::UnexpectedToken,
+                &name.text,
+                name.span,
+            ));
         }
-        if command_block_counter == 0 {
-            chained_commands.push(command_tokens[i].to_string());
-            chained_commands.retain(|x| !x.is_empty());
-            command_tokens[i] = chained_commands.join(";");
-            chained_commands = vec![];
+    }
+    if !is_valid_name(&name.text) {
+        return Err(TurtleError::new(
+            ErrorKind::InvalidVariableName,
+            &name.text,
+            name.span,
+        ));
+    }
+    let writable =
+        !declaration.is_some_and(|declaration| CONST.has_alias(&declaration.text, turtle));
+    let new_var = Variable {
+        raw_value: tokens_text(value),
+        variable_type: evaluate(value, turtle)?,
+        writable,
+    };
+    let scope = match declaration {
+        Some(declaration) if GLOBAL.has_alias(&declaration.text, turtle) => &mut turtle.variables,
+        Some(_) => turtle.local_scope(),
+        None => turtle.variable_scope(&name.text),
+    };
+    if scope
+        .get(&name.text)
+        .is_some_and(|variable| !variable.writable)
+    {
+        return Err(TurtleError::new(
+            ErrorKind::ImmutableVariable,
+            &name.text,
+            name.span,
+        ));
+    }
+    scope.insert(name.text.clone(), new_var);
+    Ok(())
+}
+
+// to <name>(<parameters>) {commands}
+// The procedure is stored, and it can be called later like the commands: <name>(<arguments>)
+fn define_procedure(
+    keyword: &Token,
+    name: &Token,
+    parameters: &[Vec<Token>],
+    block: Option<&[Statement]>,
+    span: Span,
+    turtle: &mut Turtle,
+) -> Result<(), TurtleError> {
+    if !PROCEDURE.has_alias(&keyword.text, turtle) {
+        // Two words after each other, which don't define a procedure
+        return Err(TurtleError::new(
+            ErrorKind::UnexpectedToken,
+            &name.text,
+            name.span,
+        ));
+    }
+    if !is_valid_name(&name.text) || turtle.keywords.command(&name.text).is_some() {
+        return Err(TurtleError::new(
+            ErrorKind::InvalidProcedureName,
+            &name.text,
+            name.span,
+        ));
+    }
+    let mut parameter_names: Vec<String> = vec![];
+    for parameter in parameters {
+        let parameter_name = tokens_text(parameter);
+        if parameter.len() != 1
+            || !is_valid_name(&parameter_name)
+            || parameter_names.contains(&parameter_name)
+        {
+            return Err(TurtleError::new(
+                ErrorKind::InvalidVariableName,
+                &parameter_name,
+                tokens_span(parameter),
+            ));
+        }
+        parameter_names.push(parameter_name);
+    }
+    let Some(block) = block else {
+        return Err(TurtleError::new(ErrorKind::MissingBlock, &name.text, span));
+    };
+    turtle.procedures.insert(
+        name.text.clone(),
+        Procedure {
+            parameters: parameter_names,
+            block: block.to_vec(),
+        },
+    );
+    Ok(())
+}
+
+// Calls the procedure, the arguments are evaluated in the scope of the caller
+// The procedure can only see its parameters, its own variables and the global variables
+fn call_procedure(
+    call: &CommandCall,
+    procedure: &Procedure,
+    turtle: &mut Turtle,
+) -> Result<Flow, TurtleError> {
+    if call.args.len() != procedure.parameters.len() {
+        return Err(TurtleError::new(
+            ErrorKind::WrongArgumentCount {
+                expected: procedure.parameters.len(),
+            },
+            &call.name.text,
+            call.span,
+        ));
+    }
+    let mut parameters: HashMap<String, Variable> = HashMap::default();
+    for (index, parameter) in procedure.parameters.iter().enumerate() {
+        let value = evaluate(call.argument(index)?, turtle)?;
+        let variable = Variable {
+            raw_value: value.to_string(),
+            variable_type: value,
+            writable: true,
+        };
+        parameters.insert(parameter.clone(), variable);
+    }
+    let caller_scopes = std::mem::take(&mut turtle.scopes);
+    let result = execute_scoped(call, &procedure.block, false, parameters, turtle);
+    turtle.scopes = caller_scopes;
+    // The stop command only leaves the procedure
+    result.map(|_| Flow::Next)
+}
+
+// A command with its arguments
+struct CommandCall<'a> {
+    name: &'a Token,
+    args: &'a [Vec<Token>],
+    // The span of the command (without its command block)
+    span: Span,
+}
+
+impl<'a> CommandCall<'a> {
+    // Returns the tokens of the <index>th argument of the command
+    fn argument(&self, index: usize) -> Result<&'a [Token], TurtleError> {
+        match self.args.get(index) {
+            Some(argument) => Ok(argument),
+            None => Err(TurtleError::new(
+                ErrorKind::MissingArgument,
+                &self.name.text,
+                self.span,
+            )),
+        }
+    }
+    fn evaluate(&self, index: usize, turtle: &mut Turtle) -> Result<f64, TurtleError> {
+        parse_number_value(self.argument(index)?, turtle)
+    }
+    // Creates an error, which points to the <index>th argument of the command
+    fn error(&self, kind: ErrorKind, index: usize) -> TurtleError {
+        match self.args.get(index) {
+            Some(argument) => TurtleError::new(kind, &tokens_text(argument), tokens_span(argument)),
+            None => TurtleError::new(kind, &self.name.text, self.span),
         }
-    });
-    command_tokens.retain(|x| !x.is_empty());
+    }
+}
+
+// Executes a single command (the loops and the procedures are executed by execute_block)
+// e.g  input:  "pencolor(255, 0, 0, 255)"
+//      name: "pencolor", args: [["255"], ["0"], ["0"], ["255"]]
+fn execute_single_command(
+    call: &CommandCall,
+    in_loop: bool,
+    turtle: &mut Turtle,
+) -> Result<Flow, TurtleError> {
+    let name = call.name.text.as_str();
+    // Finding the command by its alias in the language of the application
+    let keyword = turtle.keywords.command(name).map(str::to_string);
+    let is = |command: &Command| keyword.as_deref() == Some(command.keyword);
     //
     //  Printing out the chopped up input (command block for the execution controls are not chopped up)
     //
-    //println!("Cmd tokens: {:?}", command_tokens);
-    command_tokens.iter().enumerate().for_each(|command| {
-        let mut args: Vec<&str> = vec![];
-        let mut command_blocks: &str = "";
-        // Structure:
-        // e.g  input:  "forward(100)"
-        //      output: ["forward", "100)"]
-        // Structure of the <structure> variable
-        // structure = ["<command>", "(<arguments>)", "{<command_block>}"]
-        let mut structure: Vec<&str> = vec![];
-        let mut bracket_begin: (&str, &str) = ("", "");
-        let mut bracket_begin_remainder: String = "".into();
-        let mut bracket_end: (&str, &str) = ("", "");
-        let mut bracket_end_remainder: String = "".into();
-        if command.1.contains("{") || command.1.contains("}") {
-            let bracket_begin_index: Vec<_> = command.1.match_indices("{").collect();
-            bracket_begin = command.1.split_at(bracket_begin_index.first().unwrap().0);
-            bracket_begin_remainder = bracket_begin.1.replacen('{', "", 1);
-            structure.push(bracket_begin.0);
-            let bracket_end_index: Vec<_> = bracket_begin_remainder.match_indices("}").collect();
-            bracket_end = bracket_begin_remainder.split_at(bracket_end_index.last().unwrap().0);
-            bracket_end_remainder = bracket_end.1.replacen('}', "", 1);
-            structure.push(bracket_end.0);
-            structure.push(&bracket_end_remainder);
-            structure.retain(|&x| !x.is_empty());
-            command_blocks = structure.last().unwrap();
+    //println!("Name: {:?}, args: {:?}", name, args);
+    if is(&FORWARD) {
+        let dist = call.evaluate(0, turtle)?;
+        if !(dist as f32).is_finite() {
+            return Err(call.error(ErrorKind::InvalidDistance, 0));
         }
-        if command.1.contains(&['(', ')'][..]) {
-            structure = command.1.split(&['(', ')'][..]).collect();
-            structure.retain(|&x| !x.is_empty());
-            args = structure.get(1).unwrap().split(",").collect();
-        } else if !command.1.contains(&['(', ')'][..]) {
-            structure = command.1.split('=').collect();
-            structure.retain(|&x| !x.is_empty());
-            args = structure.first().unwrap().split(",").collect();
+        if !turtle.forward(dist as f32) {
+            return Err(TurtleError::new(ErrorKind::Fence, name, call.span));
         }
-        // This is where we declare the variable
-        // <var>=<value> - value can be a boolean or a number
-        if command.1.contains('=') && !command.1.contains(";") {
-            let variable: Vec<&str> = command.1.split('=').collect();
-            let variable_name_result: Result<f64, _> = variable.first().unwrap().parse();
-            match variable_name_result {
-                Ok(_val) => {
-                    turtle.command_history.push(
-                        get_text(&locale.to_vec(), selected_locale)
-                            .invalid_var_name_error
-                            .to_string(),
-                    );
-                    return;
-                }
-                Err(_e) => {
-                    let new_var = Variable {
-                        raw_value: variable.get(1).unwrap().to_string(),
-                        variable_type: VariableTypes::Number {
-                            value: parse_number_value(
-                                variable.get(1).unwrap().to_string(),
-                                turtle,
-                                locale,
-                                selected_locale,
-                            ),
-                        },
-                        writable: true,
-                    };
-                    if !turtle
-                        .variables
-                        .contains_key(variable.first().unwrap().as_str())
-                    {
-                        turtle
-                            .variables
-                            .insert(variable.first().unwrap().to_string(), new_var);
-                    } else if turtle
-                        .variables
-                        .contains_key(variable.first().unwrap().as_str())
-                    {
-                        if !turtle
-                            .variables
-                            .get(variable.first().unwrap().as_str())
-                            .unwrap()
-                            .writable
-                        {
-                            turtle.command_history.push(
-                                get_text(&locale.to_vec(), selected_locale)
-                                    .var_immutable_error
-                                    .format(&[variable.first().unwrap().to_string()]),
-                            );
-                        } else {
-                            turtle
-                                .variables
-                                .insert(variable.first().unwrap().to_string(), new_var);
-                        }
-                    }
-                }
-            }
-            return;
+    } else if is(&ROTATE_RIGHT) {
+        let angle: f64 = call.evaluate(0, turtle)?;
+        if !angle.is_finite() {
+            return Err(call.error(ErrorKind::InvalidAngle, 0));
         }
-        // Splitting the commands and their aliases for easier matching
-        let forward_commands: Vec<&str> = FORWARD.aliases.split(" ").collect();
-        let rotate_right_commands: Vec<&str> = ROTATE_RIGHT.aliases.split(" ").collect();
-        let rotate_left_commands: Vec<&str> = ROTATE_LEFT.aliases.split(" ").collect();
-        let pencolor_commands: Vec<&str> = PENCOLOR.aliases.split(" ").collect();
-        let penwidth_commands: Vec<&str> = PENWIDTH.aliases.split(" ").collect();
-        let penup_commands: Vec<&str> = PENUP.aliases.split(" ").collect();
-        let pendown_commands: Vec<&str> = PENDOWN.aliases.split(" ").collect();
-        let printval_commands: Vec<&str> = PRINTVAL.aliases.split(" ").collect();
-        let printraw_commands: Vec<&str> = PRINTRAW.aliases.split(" ").collect();
-        let clear_commands: Vec<&str> = CLEAR.aliases.split(" ").collect();
-        let reset_commands: Vec<&str> = RESET.aliases.split(" ").collect();
-        let repeat_commands: Vec<&str> = REPEAT.aliases.split(" ").collect();
-        let help_commands: Vec<&str> = HELP.aliases.split(" ").collect();
-        //
-        //  Printing out the chopped up input (command block for the execution controls are not chopped up)
-        //
-        //println!("Structure: {:?}", structure);
-        if forward_commands.contains(structure.first().unwrap()) {
-            let dist = parse_number_value(
-                args.first().unwrap().to_string(),
-                turtle,
-                locale,
-                selected_locale,
-            );
-            if dist.is_nan() || dist.is_infinite() {
-                turtle.command_history.push(
-                    get_text(&locale.to_vec(), selected_locale)
-                        .invalid_distance_error
-                        .format(&[dist]),
-                );
-                return;
+        turtle.rotate(-angle);
+    } else if is(&ROTATE_LEFT) {
+        let angle: f64 = call.evaluate(0, turtle)?;
+        if !angle.is_finite() {
+            return Err(call.error(ErrorKind::InvalidAngle, 0));
+        }
+        turtle.rotate(angle);
+    } else if is(&SETHEADING) {
+        let heading: f64 = call.evaluate(0, turtle)?;
+        if !heading.is_finite() {
+            return Err(call.error(ErrorKind::InvalidAngle, 0));
+        }
+        turtle.set_heading(heading);
+    } else if is(&DEGREES) {
+        turtle.angle_unit = AngleUnit::Degrees;
+    } else if is(&RADIANS) {
+        turtle.angle_unit = AngleUnit::Radians;
+    } else if is(&COMPASS) {
+        turtle.orientation = Orientation::Compass;
+    } else if is(&MATH) {
+        turtle.orientation = Orientation::Math;
+    } else if is(&PENCOLOR) {
+        let mut colors = [0_u8; 4];
+        for (color, value) in colors.iter_mut().enumerate() {
+            let color_value = call.evaluate(color, turtle)?;
+            if color_value.is_nan() {
+                return Err(call.error(ErrorKind::InvalidColorValue, color));
+            } else if !(0_f64..=255_f64).contains(&color_value) || (color_value % 1_f64 != 0_f64) {
+                return Err(call.error(ErrorKind::InvalidColorInterval, color));
             }
-            let x_offset = dist as f32 * turtle.angle.sin();
-            let y_offset = dist as f32 * turtle.angle.cos();
-            if !turtle.pen_up {
-                turtle.path[turtle.path_color.len() - 1].push(turtle.position);
-                turtle.set_position(turtle.position.x - x_offset, turtle.position.y - y_offset);
-                turtle.path[turtle.path_color.len() - 1].push(turtle.position);
-            } else {
-                turtle.set_position(turtle.position.x - x_offset, turtle.position.y - y_offset);
+            *value = color_value as u8;
+        }
+        turtle.pen.color =
+            Color32::from_rgba_unmultiplied(colors[0], colors[1], colors[2], colors[3]);
+    } else if is(&PENWIDTH) {
+        let width: f64 = call.evaluate(0, turtle)?;
+        if !(width as f32).is_finite() {
+            return Err(call.error(ErrorKind::InvalidPenSize, 0));
+        }
+        turtle.pen.width = width as f32;
+    } else if is(&PENUP) {
+        turtle.pen_up = true;
+    } else if is(&PENDOWN) {
+        turtle.pen_up = false;
+    } else if is(&PENPAINT) || is(&PENERASE) || is(&PENREVERSE) {
+        // Changing the pen's mode also puts down the pen
+        turtle.pen_up = false;
+        turtle.pen.mode = if is(&PENERASE) {
+            PenMode::Erase
+        } else if is(&PENREVERSE) {
+            PenMode::InverseBackground
+        } else {
+            PenMode::Paint
+        };
+    } else if is(&PENDASH) {
+        let on: f64 = call.evaluate(0, turtle)?;
+        // If the length of the gaps isn't specified, it equals to the length of the dashes
+        let off: f64 = if call.args.len() > 1 {
+            call.evaluate(1, turtle)?
+        } else {
+            on
+        };
+        for (index, length) in [on, off].into_iter().enumerate() {
+            if !(length as f32).is_finite() || length < 0_f64 {
+                return Err(call.error(ErrorKind::InvalidPenDash, index));
             }
-        } else if rotate_right_commands.contains(structure.first().unwrap()) {
-            let angle: f64 = parse_number_value(
-                args.first().unwrap().to_string(),
-                turtle,
-                locale,
-                selected_locale,
-            );
-            if angle.is_nan() || angle.is_infinite() {
-                turtle.command_history.push(
-                    get_text(&locale.to_vec(), selected_locale)
-                        .invalid_angle_error
-                        .format(&[angle]),
-                );
-            } else {
-                let corrected_angle = angle as f32 * ((2_f32 * PI) / 360_f32);
-                turtle.angle -= corrected_angle;
+            // The dashes and the gaps can't be too short (only the solid line has 0 long dashes)
+            if on != 0_f64 && (length as f32) < MIN_DASH_LENGTH {
+                return Err(call.error(ErrorKind::InvalidPenDash, index.min(call.args.len() - 1)));
             }
-        } else if rotate_left_commands.contains(structure.first().unwrap()) {
-            let angle: f64 = parse_number_value(
-                args.first().unwrap().to_string(),
-                turtle,
-                locale,
-                selected_locale,
-            );
-            if angle.is_nan() || angle.is_infinite() {
-                turtle.command_history.push(
-                    get_text(&locale.to_vec(), selected_locale)
-                        .invalid_angle_error
-                        .format(&[angle]),
-                );
-            } else {
-                let corrected_angle = angle as f32 * ((2_f32 * PI) / 360_f32);
-                turtle.angle -= (2_f32 * PI) - corrected_angle;
+        }
+        // pendash(0) switches back to solid lines
+        turtle.pen.dash = if on == 0_f64 {
+            None
+        } else {
+            Some(PenDash {
+                on: on as f32,
+                off: off as f32,
+            })
+        };
+    } else if is(&LINECAP) {
+        let value = tokens_text(call.argument(0)?);
+        let value = turtle.keywords.value(&value).map(str::to_string);
+        if value.as_deref() == Some(BUTT_VALUE) {
+            turtle.pen.cap = LineCap::Butt;
+        } else if value.as_deref() == Some(ROUND_VALUE) {
+            turtle.pen.cap = LineCap::Round;
+        } else if value.as_deref() == Some(SQUARE_VALUE) {
+            turtle.pen.cap = LineCap::Square;
+        } else {
+            return Err(call.error(ErrorKind::InvalidLineCap, 0));
+        }
+    } else if is(&LINEJOIN) {
+        let value = tokens_text(call.argument(0)?);
+        let value = turtle.keywords.value(&value).map(str::to_string);
+        if value.as_deref() == Some(MITER_VALUE) {
+            turtle.pen.join = LineJoin::Miter;
+        } else if value.as_deref() == Some(ROUND_VALUE) {
+            turtle.pen.join = LineJoin::Round;
+        } else if value.as_deref() == Some(BEVEL_VALUE) {
+            turtle.pen.join = LineJoin::Bevel;
+        } else {
+            return Err(call.error(ErrorKind::InvalidLineJoin, 0));
+        }
+    } else if is(&WRAP) {
+        turtle.boundary_mode = BoundaryMode::Wrap;
+    } else if is(&WINDOW) {
+        turtle.boundary_mode = BoundaryMode::Window;
+    } else if is(&FENCE) {
+        turtle.boundary_mode = BoundaryMode::Fence;
+    } else if is(&PRINTVAL) || is(&PRINTRAW) {
+        // Command for printing out the value of a variable (e.g. "x = 5"),
+        // or the value of an expression (e.g. print("x = {x}"))
+        let argument = call.argument(0)?;
+        let variable_name = tokens_text(argument);
+        let line = match turtle.get_variable(&variable_name) {
+            Some(searched_var) if argument.len() == 1 => {
+                format!("{} = {}", variable_name, searched_var.variable_type)
             }
-        } else if pencolor_commands.contains(structure.first().unwrap()) {
-            let r: f64 = parse_number_value(
-                args.first().unwrap().to_string(),
-                turtle,
-                locale,
-                selected_locale,
-            );
-            let g: f64 = parse_number_value(
-                args.get(1).unwrap().to_string(),
-                turtle,
-                locale,
-                selected_locale,
-            );
-            let b: f64 = parse_number_value(
-                args.get(2).unwrap().to_string(),
-                turtle,
-                locale,
-                selected_locale,
-            );
-            let a: f64 = parse_number_value(
-                args.get(3).unwrap().to_string(),
-                turtle,
-                locale,
-                selected_locale,
-            );
-            let mut colors = [r, g, b, a];
-            (0..colors.len()).for_each(|color| {
-                if colors[color].is_nan() {
-                    turtle.command_history.push(
-                        get_text(&locale.to_vec(), selected_locale)
-                            .invalid_color_value_error
-                            .format(&[colors[color]]),
-                    );
-                    colors = [
-                        turtle.pencolor.r() as f64,
-                        turtle.pencolor.g() as f64,
-                        turtle.pencolor.b() as f64,
-                        turtle.pencolor.a() as f64,
-                    ];
-                } else if colors[color] < 0_f64
-                    || colors[color] > 255_f64
-                    || (colors[color] % 1_f64 != 0_f64)
-                {
-                    turtle.command_history.push(
-                        get_text(&locale.to_vec(), selected_locale)
-                            .invalid_color_interval_error
-                            .format(&[colors[color]]),
-                    );
-                    colors = [
-                        turtle.pencolor.r() as f64,
-                        turtle.pencolor.g() as f64,
-                        turtle.pencolor.b() as f64,
-                        turtle.pencolor.a() as f64,
-                    ];
+            _ => {
+                let value = evaluate(argument, turtle)?;
+                if is(&PRINTVAL) {
+                    format!("{} = {}", variable_name, value)
+                } else {
+                    value.to_string()
                 }
-            });
-            turtle.pencolor = Color32::from_rgba_unmultiplied(
-                colors[0] as u8,
-                colors[1] as u8,
-                colors[2] as u8,
-                colors[3] as u8,
-            );
-            turtle.path.push(vec![]);
-            turtle.path_color.push(turtle.pencolor);
-            turtle.path_width.push(turtle.penwidth);
-        } else if penwidth_commands.contains(structure.first().unwrap()) {
-            let width: f64 = parse_number_value(
-                args.first().unwrap().to_string(),
-                turtle,
-                locale,
-                selected_locale,
-            );
-            if width.is_nan() || width.is_infinite() {
-                turtle.command_history.push(
-                    get_text(&locale.to_vec(), selected_locale)
-                        .invalid_pen_size_error
-                        .format(&[width]),
-                );
-            } else {
-                turtle.penwidth = width as f32;
-                turtle.path.push(vec![]);
-                turtle.path_color.push(turtle.pencolor);
-                turtle.path_width.push(turtle.penwidth);
             }
-        } else if penup_commands.contains(structure.first().unwrap()) {
-            turtle.pen_up = true;
-        } else if pendown_commands.contains(structure.first().unwrap()) {
-            turtle.pen_up = false;
-            if turtle.path.last().is_some() {
-                turtle.path.push(vec![]);
-                turtle.path_color.push(turtle.pencolor);
-                turtle.path_width.push(turtle.penwidth);
-            }
-        } else if printval_commands.contains(structure.first().unwrap()) {
-            // Command for printing out the variables numerical or booleanic value
-            let searched_var_result: Option<&Variable> =
-                turtle.variables.get(*args.first().unwrap());
-            let searched_var: &Variable = match searched_var_result {
-                Some(result) => result,
-                None => {
-                    turtle.command_history.push(
-                        get_text(&locale.to_vec(), selected_locale)
-                            .invalid_variable_error
-                            .format(&[args.first().unwrap()]),
-                    );
-                    return;
-                }
-            };
-            turtle.command_history.push(format!(
-                "{} = {}",
-                args.first().unwrap(),
-                searched_var.variable_type.get_value()
+        };
+        turtle.command_history.push(HistoryEntry::Text(line));
+        // Printing out all the variables
+        //println!("{:?}", turtle.variables.iter());
+    } else if is(&LABEL) {
+        // label(<text>, <size>) - writes the text to the turtle's position, the size is optional
+        let text = evaluate(call.argument(0)?, turtle)?.to_string();
+        let size = if call.args.len() > 1 {
+            call.evaluate(1, turtle)?
+        } else {
+            LABEL_SIZE
+        };
+        if !(size as f32).is_finite() || size <= 0_f64 {
+            return Err(call.error(ErrorKind::InvalidLabelSize, 1));
+        }
+        turtle.path.add_label(Label {
+            position: turtle.position,
+            text,
+            color: turtle.pen.color,
+            size: size as f32,
+        });
+    } else if is(&APPEND) {
+        // append(<list>, <value>) - the list has to be a variable, which can be updated
+        let list_name = tokens_text(call.argument(0)?);
+        let value = evaluate(call.argument(1)?, turtle)?;
+        let Some(variable) = turtle.variable_scope(&list_name).get_mut(&list_name) else {
+            return Err(call.error(ErrorKind::UndefinedVariable, 0));
+        };
+        if !variable.writable {
+            return Err(call.error(ErrorKind::ImmutableVariable, 0));
+        }
+        let VariableTypes::List { value: list } = &mut variable.variable_type else {
+            return Err(call.error(ErrorKind::ExpectedList, 0));
+        };
+        if 1 + list.iter().map(VariableTypes::size).sum::<usize>() + value.size() > MAX_VALUE_SIZE {
+            return Err(TurtleError::new(
+                ErrorKind::ValueSize {
+                    max: MAX_VALUE_SIZE,
+                },
+                name,
+                call.span,
             ));
-            // Printing out all the variables
-            //println!("{:?}", turtle.variables.iter());
-        } else if printraw_commands.contains(structure.first().unwrap()) {
-            // Command for printing out the variables raw value
-            let searched_var_result: Option<&Variable> =
-                turtle.variables.get(*args.first().unwrap());
-            let searched_var: &Variable = match searched_var_result {
-                Some(result) => result,
-                None => {
-                    turtle.command_history.push(
-                        get_text(&locale.to_vec(), selected_locale)
-                            .invalid_variable_error
-                            .format(&[args.first().unwrap()]),
-                    );
-                    return;
-                }
-            };
-            turtle.command_history.push(format!(
-                "{} = {}",
-                args.first().unwrap(),
-                searched_var.raw_value
+        }
+        list.push(value);
+        variable.raw_value = variable.variable_type.to_string();
+    } else if is(&SEED) {
+        let Some(seed) = whole_number(call.evaluate(0, turtle)?) else {
+            return Err(call.error(ErrorKind::InvalidSeed, 0));
+        };
+        turtle.random.seed(seed as u64);
+    } else if is(&CLEAR) {
+        turtle.command_history.clear();
+    } else if is(&RESET) {
+        // The language of the keywords doesn't change, and the blocks, which are being executed
+        // (e.g. repeat(1) { reset }), keep their scopes and their depth
+        let keywords = std::mem::take(&mut turtle.keywords);
+        let scopes = std::mem::take(&mut turtle.scopes);
+        let (depth, steps) = (turtle.depth, turtle.steps);
+        *turtle = Turtle {
+            keywords,
+            scopes,
+            depth,
+            steps,
+            ..Default::default()
+        };
+    } else if is(&BREAK) || is(&CONTINUE) {
+        if !in_loop {
+            return Err(TurtleError::new(
+                ErrorKind::LoopControlOutsideLoop,
+                name,
+                call.span,
             ));
-        } else if clear_commands.contains(structure.first().unwrap()) {
-            turtle.command_history.clear();
-        } else if reset_commands.contains(structure.first().unwrap()) {
-            *turtle = Turtle::default();
-        } else if repeat_commands.contains(structure.first().unwrap()) {
-            let from_result: Result<isize, _> = args.get(1).unwrap().parse();
-            let to_result: Result<isize, _> = args.get(2).unwrap().parse();
-
-            let from: isize = match from_result {
-                Ok(val) => val,
-                Err(_e) => {
-                    turtle.command_history.push(
-                        get_text(&locale.to_vec(), selected_locale)
-                            .invalid_loop_start_error
-                            .format(&[args.get(1).unwrap()]),
-                    );
-                    return;
-                }
-            };
-            let to: isize = match to_result {
-                Ok(val) => val,
-                Err(_e) => {
-                    turtle.command_history.push(
-                        get_text(&locale.to_vec(), selected_locale)
-                            .invalid_loop_end_error
-                            .format(&[args.get(1).unwrap()]),
-                    );
-                    return;
+        }
+        return Ok(if is(&BREAK) {
+            Flow::Break
+        } else {
+            Flow::Continue
+        });
+    } else if is(&STOP) {
+        return Ok(Flow::Stop);
+    } else if is(&HELP) {
+        // help - prints the commands and the functions
+        // help(<command>) - prints the documentation of the command (or the function)
+        // The documentation is printed in the language, which is selected when it's displayed
+        let topic = match call.args.first().filter(|argument| !argument.is_empty()) {
+            None => HelpTopic::All,
+            Some(argument) => {
+                let alias = tokens_text(argument);
+                if turtle.keywords.documentation(&alias).is_none() {
+                    return Err(call.error(ErrorKind::UnknownCommand, 0));
                 }
-            };
-            if from <= to {
-                let var = Variable {
-                    raw_value: from.to_string(),
-                    variable_type: VariableTypes::Number { value: from as f64 },
-                    writable: false,
-                };
-                turtle
-                    .variables
-                    .insert(args.first().unwrap().to_string(), var);
-                //
-                //  Printing the command block that is passed to the next iteration
-                //
-                //println!("Command block: {}", command_blocks);
-                for var in from..to {
-                    let variable = Variable {
-                        raw_value: var.to_string(),
-                        variable_type: VariableTypes::Number { value: var as f64 },
-                        writable: false,
-                    };
-                    turtle
-                        .variables
-                        .insert(args.first().unwrap().to_string(), variable);
-                    execute_command(command_blocks.to_string(), turtle, locale, selected_locale);
+                match turtle.keywords.command(&alias) {
+                    Some(name) => HelpTopic::Command(name.to_string()),
+                    None => HelpTopic::Function(
+                        turtle
+                            .keywords
+                            .function(&alias)
+                            .unwrap_or_default()
+                            .to_string(),
+                    ),
                 }
-            } else {
-                turtle.command_history.push(
-                    get_text(&locale.to_vec(), selected_locale)
-                        .invalid_loop_start_error
-                        .format(&[from, to]),
-                );
             }
-        } else if help_commands.contains(structure.first().unwrap()) {
-            turtle.command_history.push(String::new());
-        } else {
-            turtle.command_history.push(
-                get_text(&locale.to_vec(), selected_locale)
-                    .terminal_help_message
-                    .to_string(),
-            );
+        };
+        turtle.command_history.push(HistoryEntry::Help { topic });
+    } else {
+        return Err(TurtleError::new(
+            ErrorKind::UnknownCommand,
+            name,
+            call.name.span,
+        ));
+    }
+    Ok(Flow::Next)
+}
+
+// Executes the loops
+// repeat(<count>) {commands} - it executes the commands <count> times
+// for(<variable>, <from>, <to>, <step>) {commands} - the variable goes from <from> by <step>
+// until it reaches <to> (exclusive boundary), the step is 1 by default and it can be negative
+fn execute_loop(
+    call: &CommandCall,
+    command_blocks: &[Statement],
+    turtle: &mut Turtle,
+) -> Result<Flow, TurtleError> {
+    if call.args.len() <= 1 {
+        let count = call.evaluate(0, turtle)?;
+        if !count.is_finite() || count < 0_f64 || count % 1_f64 != 0_f64 {
+            return Err(call.error(ErrorKind::InvalidLoopCount, 0));
         }
-    });
+        for _ in 0..count as u64 {
+            match execute_scoped(call, command_blocks, true, HashMap::default(), turtle)? {
+                Flow::Break => break,
+                Flow::Stop => return Ok(Flow::Stop),
+                Flow::Next | Flow::Continue => {}
+            }
+        }
+        return Ok(Flow::Next);
+    }
+    let loop_variable = tokens_text(call.argument(0)?);
+    if !is_valid_name(&loop_variable) {
+        return Err(call.error(ErrorKind::InvalidVariableName, 0));
+    }
+    let from = call.evaluate(1, turtle)?;
+    if !from.is_finite() {
+        return Err(call.error(ErrorKind::InvalidLoopStart, 1));
+    }
+    let to = call.evaluate(2, turtle)?;
+    if !to.is_finite() {
+        return Err(call.error(ErrorKind::InvalidLoopEnd, 2));
+    }
+    let step = if call.args.len() > 3 {
+        call.evaluate(3, turtle)?
+    } else {
+        1_f64
+    };
+    if !step.is_finite() || step == 0_f64 {
+        return Err(call.error(ErrorKind::InvalidLoopStep, 3));
+    }
+    // The loop has to get closer to its end with every step
+    if (to - from) * step < 0_f64 {
+        let (start, end) = (call.argument(1)?, call.argument(2)?);
+        return Err(TurtleError::new(
+            ErrorKind::InvalidLoopInterval {
+                start: from,
+                end: to,
+            },
+            &format!("{},{}", tokens_text(start), tokens_text(end)),
+            tokens_span(start).to(tokens_span(end)),
+        ));
+    }
+    // The loop variable only exists inside of the loop (in the scope of the loop's block)
+    let mut iteration = 0_u64;
+    loop {
+        // Multiplying the step instead of adding it up, so the rounding errors don't pile up
+        let value = from + iteration as f64 * step;
+        if (step > 0_f64 && value >= to) || (step < 0_f64 && value <= to) {
+            break;
+        }
+        let variable = Variable {
+            raw_value: value.to_string(),
+            variable_type: VariableTypes::Number { value },
+            writable: false,
+        };
+        let scope = HashMap::from_iter([(loop_variable.clone(), variable)]);
+        match execute_scoped(call, command_blocks, true, scope, turtle)? {
+            Flow::Break => break,
+            Flow::Stop => return Ok(Flow::Stop),
+            Flow::Next | Flow::Continue => {}
+        }
+        iteration += 1;
+    }
+    Ok(Flow::Next)
+}
+
+// foreach(<variable>, <list>) {commands} - the variable goes through the elements of the list
+// (or through the characters of the text), the list is evaluated before the loop
+fn execute_foreach(
+    call: &CommandCall,
+    command_blocks: &[Statement],
+    turtle: &mut Turtle,
+) -> Result<Flow, TurtleError> {
+    let loop_variable = tokens_text(call.argument(0)?);
+    if !is_valid_name(&loop_variable) {
+        return Err(call.error(ErrorKind::InvalidVariableName, 0));
+    }
+    let elements = match evaluate(call.argument(1)?, turtle)? {
+        VariableTypes::List { value } => value,
+        VariableTypes::Text { value } => value
+            .chars()
+            .map(|char| VariableTypes::Text {
+                value: char.to_string(),
+            })
+            .collect(),
+        _ => return Err(call.error(ErrorKind::ExpectedList, 1)),
+    };
+    for element in elements {
+        let variable = Variable {
+            raw_value: element.to_string(),
+            variable_type: element,
+            writable: false,
+        };
+        let scope = HashMap::from_iter([(loop_variable.clone(), variable)]);
+        match execute_scoped(call, command_blocks, true, scope, turtle)? {
+            Flow::Break => break,
+            Flow::Stop => return Ok(Flow::Stop),
+            Flow::Next | Flow::Continue => {}
+        }
+    }
+    Ok(Flow::Next)
 }
@@ -0,0 +1,113 @@
+use crate::{
+    documentation::Documentation,
+    keywords::KeywordTable,
+    parsing::{tokenize, TokenKind},
+    turtle::Turtle,
+};
+
+// The input of the console after the completion
+#[derive(Debug, PartialEq, Clone)]
+pub struct Completion {
+    pub input: String,
+    // The position of the cursor in characters (after the completed word)
+    pub cursor: usize,
+    // The names, which start with the word (more than one if the completion is ambiguous)
+    pub candidates: Vec<String>,
+}
+
+// The byte index of the cursor, which is counted in characters
+fn byte_index(input: &str, cursor: usize) -> usize {
+    input
+        .char_indices()
+        .nth(cursor)
+        .map_or(input.len(), |(index, _)| index)
+}
+
+// The names, which can be completed: the aliases of the commands, the functions and the values,
+// the procedures and the variables (sorted, without repetitions)
+pub fn names(keywords: &KeywordTable, turtle: &Turtle) -> Vec<String> {
+    let mut names: Vec<String> = keywords
+        .aliases()
+        .map(str::to_string)
+        .chain(turtle.procedures.keys().cloned())
+        .chain(turtle.variables.keys().cloned())
+        .chain(turtle.scopes.iter().flat_map(|scope| scope.keys().cloned()))
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+// Completes the word before the cursor to the longest common beginning of the names
+// e.g. "pen" -> "pencolor" if it's the only name, which starts with "pen"
+// It's None if the cursor isn't after a word or no name starts with the word
+pub fn complete(
+    input: &str,
+    cursor: usize,
+    keywords: &KeywordTable,
+    turtle: &Turtle,
+) -> Option<Completion> {
+    let end = byte_index(input, cursor);
+    // The word has to end at the cursor (the words in strings and comments aren't completed)
+    let word = tokenize(&input[..end])
+        .pop()
+        .filter(|token| token.kind == TokenKind::Word && token.span.end == end)?;
+    let candidates: Vec<String> = names(keywords, turtle)
+        .into_iter()
+        .filter(|name| name.starts_with(&word.text))
+        .collect();
+    let mut prefix = candidates.first()?.clone();
+    for candidate in &candidates[1..] {
+        let length = prefix
+            .char_indices()
+            .zip(candidate.chars())
+            .find(|((_, a), b)| a != b)
+            .map_or(prefix.len().min(candidate.len()), |((index, _), _)| index);
+        prefix.truncate(length);
+    }
+    Some(Completion {
+        input: format!("{}{}{}", &input[..word.span.start], prefix, &input[end..]),
+        cursor: cursor + prefix.chars().count() - word.text.chars().count(),
+        candidates,
+    })
+}
+
+// The documentation of the command (or function), whose arguments are typed before the cursor,
+// or whose alias is before the cursor
+// e.g. "repeat(4) { forward(10 * (2" -> the documentation of forward
+pub fn usage_hint<'a>(
+    input: &str,
+    cursor: usize,
+    keywords: &'a KeywordTable,
+) -> Option<&'a Documentation> {
+    let tokens = tokenize(&input[..byte_index(input, cursor)]);
+    if let Some(documentation) = tokens
+        .last()
+        .filter(|token| token.kind == TokenKind::Word)
+        .and_then(|token| keywords.documentation(&token.text))
+    {
+        return Some(documentation);
+    }
+    // The words before the parentheses, which aren't closed yet
+    let mut open: Vec<Option<&str>> = vec![];
+    // The parentheses outside of the blocks, which are being typed
+    // (the commands in the blocks aren't the arguments of the outer commands)
+    let mut blocks: Vec<Vec<Option<&str>>> = vec![];
+    let mut previous: Option<&str> = None;
+    for token in &tokens {
+        match token.kind {
+            TokenKind::OpenParenthesis => open.push(previous),
+            TokenKind::CloseParenthesis => {
+                open.pop();
+            }
+            TokenKind::OpenBrace => blocks.push(std::mem::take(&mut open)),
+            TokenKind::CloseBrace => open = blocks.pop().unwrap_or_default(),
+            _ => {}
+        }
+        previous = (token.kind == TokenKind::Word).then_some(token.text.as_str());
+    }
+    open.into_iter()
+        .rev()
+        .flatten()
+        .find_map(|alias| keywords.documentation(alias))
+}
@@ -1,6 +1,431 @@
-#[derive(Default, Debug)]
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+// The documentation of a command or a function, it's declared in the locale files
+// The aliases come from the keywords of the languages (they aren't in the documentation)
+#[derive(Default, Debug, PartialEq, Serialize, Deserialize, Clone)]
 pub struct Documentation {
-    aliases: Vec<String>,
-    usage: String,
-    description: String,
+    #[serde(skip)]
+    pub aliases: Vec<String>,
+    // e.g. "forward(<distance>)"
+    pub usage: String,
+    pub description: String,
+    // Scripts, which show how the command is used (they can be inserted into the script editor)
+    #[serde(default)]
+    pub examples: Vec<String>,
+}
+
+// The documentation of every command and function in a language
+// The missing labels and sections of a translation are taken from the English documentation
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct Manual {
+    pub aliases_label: String,
+    pub examples_label: String,
+    pub commands: BTreeMap<String, Documentation>,
+    pub functions: BTreeMap<String, Documentation>,
+}
+
+// The English documentation
+// (name, usage, description, examples)
+const COMMAND_DOCUMENTATION: [(&str, &str, &str, &[&str]); 38] = [
+    (
+        "forward",
+        "forward(<distance>)",
+        "moves the turtle forward by the distance (in pixels), its pen draws the path",
+        &["forward(100)"],
+    ),
+    (
+        "right",
+        "right(<angle>)",
+        "turns the turtle to the right by the angle (in degrees or radians)",
+        &["right(90)", "repeat(3) { forward(80); right(120) }"],
+    ),
+    (
+        "left",
+        "left(<angle>)",
+        "turns the turtle to the left by the angle (in degrees or radians)",
+        &["left(45)"],
+    ),
+    (
+        "setheading",
+        "setheading(<angle>)",
+        "turns the turtle to the direction, 0 is upwards with the compass orientation",
+        &["setheading(90)"],
+    ),
+    (
+        "degrees",
+        "degrees",
+        "the angles are measured in degrees (this is the default)",
+        &["degrees; right(90)"],
+    ),
+    (
+        "radians",
+        "radians",
+        "the angles are measured in radians",
+        &["radians; right(3.14159 / 2); degrees"],
+    ),
+    (
+        "compass",
+        "compass",
+        "the heading is 0 upwards and it grows clockwise (this is the default)",
+        &["compass; print(heading())"],
+    ),
+    (
+        "math",
+        "math",
+        "the heading is 0 to the right and it grows counter-clockwise",
+        &["math; setheading(90); forward(50); compass"],
+    ),
+    (
+        "pencolor",
+        "pencolor(<red>, <green>, <blue>, <alpha>)",
+        "the color of the pen, every channel is from 0 to 255",
+        &["pencolor(255, 0, 0, 255)"],
+    ),
+    (
+        "penwidth",
+        "penwidth(<width>)",
+        "the width of the pen in pixels",
+        &["penwidth(5); forward(50)"],
+    ),
+    (
+        "penup",
+        "penup",
+        "the turtle lifts up its pen, so it doesn't draw its path",
+        &["penup; forward(50); pendown"],
+    ),
+    (
+        "pendown",
+        "pendown",
+        "the turtle puts down its pen, so it draws its path",
+        &["pendown; forward(50)"],
+    ),
+    (
+        "penpaint",
+        "penpaint",
+        "the turtle paints its path with the color of the pen (this is the default)",
+        &["penerase; forward(20); penpaint; forward(20)"],
+    ),
+    (
+        "penerase",
+        "penerase",
+        "the path of the turtle erases the drawing underneath it",
+        &["penerase; forward(50)"],
+    ),
+    (
+        "penreverse",
+        "penreverse",
+        "the path of the turtle has the inverse color of the background (the lines underneath it are covered with this color, they aren't inverted)",
+        &["penreverse; forward(50)"],
+    ),
+    (
+        "pendash",
+        "pendash(<dash>, <gap>)",
+        "the turtle draws dashed lines, pendash(0) switches back to solid lines",
+        &["pendash(10, 5); forward(100); pendash(0)"],
+    ),
+    (
+        "linecap",
+        "linecap(<butt/round/square>)",
+        "the shape of the ends of the lines",
+        &["penwidth(10); linecap(round); forward(50)"],
+    ),
+    (
+        "linejoin",
+        "linejoin(<miter/round/bevel>)",
+        "the shape of the corners of the lines",
+        &["penwidth(10); linejoin(bevel); forward(50); right(90); forward(50)"],
+    ),
+    (
+        "wrap",
+        "wrap",
+        "when the turtle leaves the canvas, it appears on the opposite side of it",
+        &["wrap; forward(1000)"],
+    ),
+    (
+        "window",
+        "window",
+        "the turtle can leave the canvas (this is the default)",
+        &["window; forward(1000)"],
+    ),
+    (
+        "fence",
+        "fence",
+        "the turtle can't leave the canvas, it stops at the edge of it",
+        &["fence; forward(50)"],
+    ),
+    (
+        "evaluate",
+        "evaluate(<expression>)",
+        "prints the expression and its value",
+        &["evaluate(2 + 3 * 4)"],
+    ),
+    (
+        "print",
+        "print(<expression>)",
+        "prints the value of the expression, the expressions in braces are replaced in the texts",
+        &["x = 5; print(\"x = {x}\")"],
+    ),
+    (
+        "label",
+        "label(<text>, <size>)",
+        "writes the text next to the turtle with the color of the pen, the size is optional (16 by default)",
+        &["label(\"Hello!\", 24)"],
+    ),
+    (
+        "append",
+        "append(<list>, <value>)",
+        "adds the value to the end of the list variable",
+        &["xs = [1, 2]; append(xs, 3); print(xs)"],
+    ),
+    (
+        "seed",
+        "seed(<whole number>)",
+        "the random numbers are repeated after the same seed",
+        &["seed(42); print(random(100))"],
+    ),
+    (
+        "clear",
+        "clear",
+        "clears the console",
+        &["clear"],
+    ),
+    (
+        "reset",
+        "reset",
+        "resets the turtle and clears its drawing",
+        &["reset"],
+    ),
+    (
+        "repeat",
+        "repeat(<count>) {commands}",
+        "repeats the commands, for(<variable>, <from>, <to>, <step>) {commands} goes from <from> by <step> until <to> (the step is optional)",
+        &["repeat(4) { forward(50); right(90) }", "for(i, 0, 100, 10) { forward(i); right(90) }"],
+    ),
+    (
+        "foreach",
+        "foreach(<variable>, <list>) {commands}",
+        "the variable goes through the elements of the list (or the characters of the text)",
+        &["foreach(side, [20, 40, 60]) { forward(side); right(90) }"],
+    ),
+    (
+        "break",
+        "break",
+        "exits the loop",
+        &["repeat(10) { forward(10); break }"],
+    ),
+    (
+        "continue",
+        "continue",
+        "jumps to the next iteration of the loop",
+        &["for(i, 0, 4) { right(90); continue; forward(100) }"],
+    ),
+    (
+        "stop",
+        "stop",
+        "leaves the procedure, or stops the program outside of the procedures",
+        &["to square(size) { repeat(4) { forward(size); right(90) }; stop; forward(size) }; square(50)"],
+    ),
+    (
+        "help",
+        "help(<command>)",
+        "prints the commands, or the documentation of the command",
+        &["help", "help(forward)"],
+    ),
+    (
+        "local",
+        "local <variable> = <value>",
+        "declares a variable, which only exists in its block",
+        &["repeat(2) { local side = 30; forward(side) }"],
+    ),
+    (
+        "global",
+        "global <variable> = <value>",
+        "declares a global variable, which can be used everywhere",
+        &["to grow() { global size = 20 }; grow(); forward(size)"],
+    ),
+    (
+        "const",
+        "const <variable> = <value>",
+        "declares a variable, which can't be changed",
+        &["const side = 50; forward(side)"],
+    ),
+    (
+        "procedure",
+        "to <name>(<parameters>) {commands}",
+        "defines a procedure, which can be called like the commands",
+        &["to square(size) { repeat(4) { forward(size); right(90) } }; square(50)"],
+    ),
+];
+
+const FUNCTION_DOCUMENTATION: [(&str, &str, &str, &[&str]); 15] = [
+    (
+        "length",
+        "length(<text or list>)",
+        "the number of characters in the text, or the number of elements in the list",
+        &["print(length(\"turtle\"))"],
+    ),
+    (
+        "substring",
+        "substring(<text>, <start>, <count>)",
+        "<count> characters of the text from the <start>th character (from 0)",
+        &["print(substring(\"turtle\", 0, 4))"],
+    ),
+    (
+        "text",
+        "text(<value>)",
+        "converts the value to text",
+        &["print(text(5) + \"px\")"],
+    ),
+    (
+        "number",
+        "number(<text>)",
+        "converts the text to number",
+        &["forward(number(\"50\"))"],
+    ),
+    (
+        "range",
+        "range(<from>, <to>, <step>)",
+        "list of the numbers from <from> by <step> until <to> (the step is optional)",
+        &["print(range(0, 10, 2))"],
+    ),
+    (
+        "random",
+        "random(<min>, <max>)",
+        "random whole number from <min> to <max>, random(<max>) is from 0 to <max>-1",
+        &["forward(random(10, 50))"],
+    ),
+    (
+        "randomfloat",
+        "randomfloat()",
+        "random number from 0 (included) to 1 (excluded)",
+        &["print(randomfloat())"],
+    ),
+    (
+        "pick",
+        "pick(<list>)",
+        "random element of the list (or random character of the text)",
+        &["right(pick([90, -90]))"],
+    ),
+    (
+        "heading",
+        "heading()",
+        "the direction of the turtle, between 0 and a full turn",
+        &["right(30); print(heading())"],
+    ),
+    (
+        "sin",
+        "sin(<angle>)",
+        "sine of the angle",
+        &["print(sin(30))"],
+    ),
+    (
+        "cos",
+        "cos(<angle>)",
+        "cosine of the angle",
+        &["print(cos(60))"],
+    ),
+    (
+        "tan",
+        "tan(<angle>)",
+        "tangent of the angle",
+        &["print(tan(45))"],
+    ),
+    (
+        "asin",
+        "asin(<number>)",
+        "the angle, whose sine is the number",
+        &["print(asin(0.5))"],
+    ),
+    (
+        "acos",
+        "acos(<number>)",
+        "the angle, whose cosine is the number",
+        &["print(acos(0.5))"],
+    ),
+    (
+        "atan",
+        "atan(<number>)",
+        "the angle, whose tangent is the number",
+        &["print(atan(1))"],
+    ),
+];
+
+impl Default for Manual {
+    fn default() -> Self {
+        let to_map = |records: &[(&str, &str, &str, &[&str])]| {
+            records
+                .iter()
+                .map(|(name, usage, description, examples)| {
+                    (
+                        name.to_string(),
+                        Documentation {
+                            aliases: vec![],
+                            usage: usage.to_string(),
+                            description: description.to_string(),
+                            examples: examples.iter().map(|example| example.to_string()).collect(),
+                        },
+                    )
+                })
+                .collect()
+        };
+        Self {
+            aliases_label: String::from("Aliases:"),
+            examples_label: String::from("Examples:"),
+            commands: to_map(&COMMAND_DOCUMENTATION),
+            functions: to_map(&FUNCTION_DOCUMENTATION),
+        }
+    }
+}
+
+impl Documentation {
+    // e.g. "forward(<distance>) - moves the turtle forward..."
+    pub fn summary(&self) -> String {
+        format!("{} - {}", self.usage, self.description)
+    }
+    // The lines printed by help(<command>)
+    pub fn lines(&self, manual: &Manual) -> Vec<String> {
+        let mut lines = vec![
+            self.summary(),
+            format!("{} {}", manual.aliases_label, self.aliases.join(", ")),
+        ];
+        if !self.examples.is_empty() {
+            lines.push(manual.examples_label.clone());
+            lines.extend(
+                self.examples
+                    .iter()
+                    .map(|example| format!("    {}", example)),
+            );
+        }
+        lines
+    }
+}
+
+impl Manual {
+    // The commands and the functions ordered by their usage in the language
+    pub fn commands(&self) -> Vec<&Documentation> {
+        sorted(&self.commands)
+    }
+    pub fn functions(&self) -> Vec<&Documentation> {
+        sorted(&self.functions)
+    }
+    // The lines printed by help (the commands, then the functions)
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines: Vec<String> = self
+            .commands()
+            .iter()
+            .map(|command| command.summary())
+            .collect();
+        lines.push(String::new());
+        lines.extend(self.functions().iter().map(|function| function.summary()));
+        lines
+    }
+}
+
+fn sorted(records: &BTreeMap<String, Documentation>) -> Vec<&Documentation> {
+    let mut records: Vec<&Documentation> = records.values().collect();
+    records.sort_by_key(|documentation| documentation.usage.to_lowercase());
+    records
 }
@@ -0,0 +1,275 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{locale::Locale, message::Argument};
+
+// Location of a part of the source code
+// <start> and <end> are byte offsets, <line> and <column> (of the start) are counted from 1
+#[derive(Default, Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Copy)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Span {
+    // The span from the start of this span to the end of the <other> span
+    pub fn to(self, other: Span) -> Span {
+        Span {
+            end: other.end.max(self.start),
+            ..self
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub enum ErrorKind {
+    InvalidVariableName,
+    ImmutableVariable,
+    InvalidDistance,
+    InvalidAngle,
+    Fence,
+    InvalidColorValue,
+    InvalidColorInterval,
+    InvalidPenSize,
+    InvalidPenDash,
+    InvalidLineCap,
+    InvalidLineJoin,
+    UndefinedVariable,
+    InvalidLoopStart,
+    InvalidLoopEnd,
+    InvalidLoopInterval { start: f64, end: f64 },
+    InvalidExpression,
+    UnknownCommand,
+    MissingArgument,
+    UnbalancedBrackets,
+    UnexpectedToken,
+    UnterminatedComment,
+    InvalidLoopStep,
+    InvalidLoopCount,
+    LoopControlOutsideLoop,
+    MissingBlock,
+    InvalidProcedureName,
+    WrongArgumentCount { expected: usize },
+    NestingLimit,
+    ExpectedNumber,
+    UnterminatedString,
+    UnknownFunction,
+    InvalidLabelSize,
+    InvalidIndex { length: usize },
+    ExpectedList,
+    InvalidRandomRange,
+    InvalidSeed,
+    ExpressionNestingLimit,
+    RangeLength { max: usize },
+    StepLimit { max: usize },
+    ValueSize { max: usize },
+}
+
+impl ErrorKind {
+    // The error codes never change, so they can be looked up in the documentation
+    pub fn code(&self) -> u16 {
+        match self {
+            ErrorKind::InvalidVariableName => 1,
+            ErrorKind::ImmutableVariable => 2,
+            ErrorKind::InvalidDistance => 3,
+            ErrorKind::InvalidAngle => 4,
+            ErrorKind::Fence => 5,
+            ErrorKind::InvalidColorValue => 6,
+            ErrorKind::InvalidColorInterval => 7,
+            ErrorKind::InvalidPenSize => 8,
+            ErrorKind::InvalidPenDash => 9,
+            ErrorKind::InvalidLineCap => 10,
+            ErrorKind::InvalidLineJoin => 11,
+            ErrorKind::UndefinedVariable => 12,
+            ErrorKind::InvalidLoopStart => 13,
+            ErrorKind::InvalidLoopEnd => 14,
+            ErrorKind::InvalidLoopInterval { .. } => 15,
+            ErrorKind::InvalidExpression => 16,
+            ErrorKind::UnknownCommand => 17,
+            ErrorKind::MissingArgument => 18,
+            ErrorKind::UnbalancedBrackets => 19,
+            ErrorKind::UnexpectedToken => 20,
+            ErrorKind::UnterminatedComment => 21,
+            ErrorKind::InvalidLoopStep => 22,
+            ErrorKind::InvalidLoopCount => 23,
+            ErrorKind::LoopControlOutsideLoop => 24,
+            ErrorKind::MissingBlock => 25,
+            ErrorKind::InvalidProcedureName => 26,
+            ErrorKind::WrongArgumentCount { .. } => 27,
+            ErrorKind::NestingLimit => 28,
+            ErrorKind::ExpectedNumber => 29,
+            ErrorKind::UnterminatedString => 30,
+            ErrorKind::UnknownFunction => 31,
+            ErrorKind::InvalidLabelSize => 32,
+            ErrorKind::InvalidIndex { .. } => 33,
+            ErrorKind::ExpectedList => 34,
+            ErrorKind::InvalidRandomRange => 35,
+            ErrorKind::InvalidSeed => 36,
+            ErrorKind::ExpressionNestingLimit => 37,
+            ErrorKind::RangeLength { .. } => 38,
+            ErrorKind::StepLimit { .. } => 39,
+            ErrorKind::ValueSize { .. } => 40,
+        }
+    }
+}
+
+// Error of the interpreter, which stops the execution of the program
+// It's only turned into a message (in the selected language) when it's displayed
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct TurtleError {
+    pub kind: ErrorKind,
+    // The part of the source code, which caused the error
+    pub token: String,
+    pub span: Span,
+}
+
+impl TurtleError {
+    pub fn new(kind: ErrorKind, token: &str, span: Span) -> Self {
+        Self {
+            kind,
+            token: token.to_string(),
+            span,
+        }
+    }
+    // The description of the error in the language of the locale (without its location)
+    pub fn text(&self, locale: &Locale) -> String {
+        let token = Argument::from(self.token.as_str());
+        match &self.kind {
+            ErrorKind::InvalidVariableName => locale.invalid_var_name_error.to_string(),
+            ErrorKind::ImmutableVariable => {
+                locale.format(&locale.var_immutable_error, &[("name", token.clone())])
+            }
+            ErrorKind::InvalidDistance => locale.format(
+                &locale.invalid_distance_error,
+                &[("distance", token.clone())],
+            ),
+            ErrorKind::InvalidAngle => {
+                locale.format(&locale.invalid_angle_error, &[("angle", token.clone())])
+            }
+            ErrorKind::Fence => locale.fence_error.to_string(),
+            ErrorKind::InvalidColorValue => locale.format(
+                &locale.invalid_color_value_error,
+                &[("value", token.clone())],
+            ),
+            ErrorKind::InvalidColorInterval => locale.format(
+                &locale.invalid_color_interval_error,
+                &[("value", token.clone())],
+            ),
+            ErrorKind::InvalidPenSize => {
+                locale.format(&locale.invalid_pen_size_error, &[("size", token.clone())])
+            }
+            ErrorKind::InvalidPenDash => {
+                locale.format(&locale.invalid_pen_dash_error, &[("length", token.clone())])
+            }
+            ErrorKind::InvalidLineCap => {
+                locale.format(&locale.invalid_line_cap_error, &[("value", token.clone())])
+            }
+            ErrorKind::InvalidLineJoin => {
+                locale.format(&locale.invalid_line_join_error, &[("value", token.clone())])
+            }
+            ErrorKind::UndefinedVariable => {
+                locale.format(&locale.invalid_variable_error, &[("name", token.clone())])
+            }
+            ErrorKind::InvalidLoopStart => locale.format(
+                &locale.invalid_loop_start_error,
+                &[("value", token.clone())],
+            ),
+            ErrorKind::InvalidLoopEnd => {
+                locale.format(&locale.invalid_loop_end_error, &[("value", token.clone())])
+            }
+            ErrorKind::InvalidLoopInterval { start, end } => locale.format(
+                &locale.invalid_loop_interval_error,
+                &[("start", (*start).into()), ("end", (*end).into())],
+            ),
+            ErrorKind::InvalidExpression => {
+                locale.format(&locale.invalid_expression, &[("expression", token.clone())])
+            }
+            ErrorKind::UnknownCommand => {
+                locale.format(&locale.unknown_command_error, &[("name", token.clone())])
+            }
+            ErrorKind::MissingArgument => locale.format(
+                &locale.missing_argument_error,
+                &[("command", token.clone())],
+            ),
+            ErrorKind::UnbalancedBrackets => locale.format(
+                &locale.unbalanced_brackets_error,
+                &[("bracket", token.clone())],
+            ),
+            ErrorKind::UnexpectedToken => {
+                locale.format(&locale.unexpected_token_error, &[("token", token.clone())])
+            }
+            ErrorKind::UnterminatedComment => locale.unterminated_comment_error.to_string(),
+            ErrorKind::InvalidLoopStep => {
+                locale.format(&locale.invalid_loop_step_error, &[("value", token.clone())])
+            }
+            ErrorKind::InvalidLoopCount => locale.format(
+                &locale.invalid_loop_count_error,
+                &[("count", token.clone())],
+            ),
+            ErrorKind::LoopControlOutsideLoop => {
+                locale.format(&locale.loop_control_error, &[("command", token.clone())])
+            }
+            ErrorKind::MissingBlock => {
+                locale.format(&locale.missing_block_error, &[("name", token.clone())])
+            }
+            ErrorKind::InvalidProcedureName => locale.format(
+                &locale.invalid_procedure_name_error,
+                &[("name", token.clone())],
+            ),
+            ErrorKind::WrongArgumentCount { expected } => locale.format(
+                &locale.wrong_argument_count_error,
+                &[("name", token), ("count", (*expected).into())],
+            ),
+            ErrorKind::NestingLimit => {
+                locale.format(&locale.nesting_limit_error, &[("command", token.clone())])
+            }
+            ErrorKind::ExpectedNumber => {
+                locale.format(&locale.expected_number_error, &[("value", token.clone())])
+            }
+            ErrorKind::UnterminatedString => locale.unterminated_string_error.to_string(),
+            ErrorKind::UnknownFunction => {
+                locale.format(&locale.unknown_function_error, &[("name", token.clone())])
+            }
+            ErrorKind::InvalidLabelSize => {
+                locale.format(&locale.invalid_label_size_error, &[("size", token.clone())])
+            }
+            ErrorKind::InvalidIndex { length } => locale.format(
+                &locale.invalid_index_error,
+                &[("index", token), ("length", (*length).into())],
+            ),
+            ErrorKind::ExpectedList => {
+                locale.format(&locale.expected_list_error, &[("value", token.clone())])
+            }
+            ErrorKind::InvalidRandomRange => locale.format(
+                &locale.invalid_random_range_error,
+                &[("value", token.clone())],
+            ),
+            ErrorKind::InvalidSeed => {
+                locale.format(&locale.invalid_seed_error, &[("value", token.clone())])
+            }
+            ErrorKind::ExpressionNestingLimit => locale.expression_nesting_limit_error.to_string(),
+            ErrorKind::RangeLength { max } => locale.format(
+                &locale.range_length_error,
+                &[("arguments", token), ("max", (*max).into())],
+            ),
+            ErrorKind::StepLimit { max } => locale.format(
+                &locale.step_limit_error,
+                &[("command", token), ("max", (*max).into())],
+            ),
+            ErrorKind::ValueSize { max } => {
+                locale.format(&locale.value_size_error, &[("max", (*max).into())])
+            }
+        }
+    }
+    // e.g. "1:9 E003: The turtle can't travel the specified distance (1/0)!"
+    pub fn message(&self, locale: &Locale) -> String {
+        format!(
+            "{}:{} E{:03}: {}",
+            self.span.line,
+            self.span.column,
+            self.kind.code(),
+            self.text(locale)
+        )
+    }
+}
@@ -0,0 +1,267 @@
+use std::collections::HashMap;
+
+use crate::{
+    error::TurtleError,
+    keywords::KeywordTable,
+    parsing::{parse, tokenize, Statement, Token, TokenKind},
+};
+
+// The blocks are indented by 4 spaces
+const INDENTATION: &str = "    ";
+
+fn is_line_comment(token: &Token) -> bool {
+    token.kind == TokenKind::Comment && !token.text.starts_with("/*")
+}
+
+// Writes the lines of the formatted script
+#[derive(Default)]
+struct Formatter<'a> {
+    lines: Vec<String>,
+    // The line, which is being written (without its indentation)
+    line: String,
+    // The number of blocks around the line, which is being written
+    depth: usize,
+    // The indentation of the line, which is being written
+    line_depth: usize,
+    // The line ends with the "{" of a block, only a comment can follow it in the line
+    block_opened: bool,
+    // The last token of the line
+    previous: Option<&'a Token>,
+    // The last written token (it can be on an earlier line)
+    last: Option<&'a Token>,
+    // The last token is followed by a ";"
+    separated: bool,
+    // The number of line breaks since the last token
+    line_breaks: usize,
+    // The last token was a sign, e.g. "-" in "x = -1"
+    sign: bool,
+    // The canonical aliases of the keywords by the positions of their tokens
+    aliases: HashMap<usize, &'a str>,
+}
+
+impl<'a> Formatter<'a> {
+    fn end_line(&mut self) {
+        if !self.line.is_empty() {
+            self.lines.push(format!(
+                "{}{}",
+                INDENTATION.repeat(self.line_depth),
+                std::mem::take(&mut self.line)
+            ));
+        }
+        self.previous = None;
+        self.block_opened = false;
+    }
+    // Whether the token is separated from the previous token of the line by a space
+    fn space_before(&self, token: &Token) -> bool {
+        let Some(previous) = self.previous else {
+            return false;
+        };
+        if self.sign {
+            return false;
+        }
+        if token.kind == TokenKind::Comment || previous.kind == TokenKind::Comment {
+            return true;
+        }
+        match (previous.kind, token.kind) {
+            (TokenKind::OpenParenthesis | TokenKind::OpenBracket, _) => false,
+            (_, TokenKind::CloseParenthesis | TokenKind::CloseBracket | TokenKind::Comma) => false,
+            // The calls and the indexes, e.g. "forward(10)", "points[0]"
+            (TokenKind::Word, TokenKind::OpenParenthesis) => false,
+            (
+                TokenKind::Word
+                | TokenKind::String
+                | TokenKind::CloseParenthesis
+                | TokenKind::CloseBracket,
+                TokenKind::OpenBracket,
+            ) => false,
+            _ => true,
+        }
+    }
+    fn write(&mut self, token: &'a Token) {
+        if self.block_opened && !is_line_comment(token) {
+            self.end_line();
+        }
+        if self.line.is_empty() {
+            self.line_depth = self.depth;
+            // One empty line is kept between the parts of the script
+            if self.line_breaks > 1
+                && self
+                    .lines
+                    .last()
+                    .is_some_and(|line| !line.trim_end().ends_with('{'))
+            {
+                self.lines.push(String::new());
+            }
+        } else if self.space_before(token) {
+            self.line.push(' ');
+        }
+        // The operators are signs after the other operators, e.g. "-" in "x = 2 * -1"
+        self.sign = token.kind == TokenKind::Operator
+            && matches!(token.text.as_str(), "-" | "+")
+            && self.previous.is_none_or(|previous| {
+                matches!(
+                    previous.kind,
+                    TokenKind::Operator
+                        | TokenKind::OpenParenthesis
+                        | TokenKind::OpenBracket
+                        | TokenKind::Comma
+                        | TokenKind::Equals
+                )
+            });
+        match self.aliases.get(&token.span.start) {
+            Some(alias) => self.line.push_str(alias),
+            None => self.line.push_str(&token.text),
+        }
+        self.previous = Some(token);
+        self.last = Some(token);
+        self.separated = false;
+        self.line_breaks = 0;
+    }
+}
+
+// The canonical aliases of the functions called in the expression, e.g. "rnd(1, 6)" -> "random(1, 6)"
+fn expression_aliases<'a>(
+    tokens: &[Token],
+    keywords: &'a KeywordTable,
+    aliases: &mut HashMap<usize, &'a str>,
+) {
+    for pair in tokens.windows(2) {
+        if pair[0].kind == TokenKind::Word && pair[1].kind == TokenKind::OpenParenthesis {
+            if let Some(alias) = keywords.canonical_function(&pair[0].text) {
+                aliases.insert(pair[0].span.start, alias);
+            }
+        }
+    }
+}
+
+// The canonical aliases of the keywords in the statements (by the positions of their tokens)
+// The commands, the declarations, the functions and the values are replaced,
+// the procedures and the variables keep their names
+fn canonical_aliases<'a>(
+    statements: &[Statement],
+    keywords: &'a KeywordTable,
+    aliases: &mut HashMap<usize, &'a str>,
+) {
+    for statement in statements {
+        // The keywords of the statement with their canonical aliases
+        let mut replaced: Vec<(&Token, Option<&str>)> = vec![];
+        let block = match statement {
+            Statement::Assignment {
+                declaration, value, ..
+            } => {
+                if let Some(declaration) = declaration {
+                    replaced.push((declaration, keywords.canonical_command(&declaration.text)));
+                }
+                expression_aliases(value, keywords, aliases);
+                None
+            }
+            Statement::Procedure { keyword, block, .. } => {
+                if keywords.command(&keyword.text) == Some("procedure") {
+                    replaced.push((keyword, keywords.canonical_command(&keyword.text)));
+                }
+                block.as_deref()
+            }
+            Statement::Command {
+                name,
+                arguments,
+                block,
+                ..
+            } => {
+                replaced.push((name, keywords.canonical_command(&name.text)));
+                let is_help = keywords.command(&name.text) == Some("help");
+                for argument in arguments {
+                    match argument.as_slice() {
+                        // e.g. "linecap(round)"
+                        [word]
+                            if word.kind == TokenKind::Word
+                                && keywords.value(&word.text).is_some() =>
+                        {
+                            replaced.push((word, keywords.canonical_value(&word.text)))
+                        }
+                        // e.g. "help(fd)"
+                        [word] if word.kind == TokenKind::Word && is_help => replaced.push((
+                            word,
+                            keywords
+                                .canonical_command(&word.text)
+                                .or_else(|| keywords.canonical_function(&word.text)),
+                        )),
+                        argument => expression_aliases(argument, keywords, aliases),
+                    }
+                }
+                block.as_deref()
+            }
+        };
+        for (token, alias) in replaced {
+            if let Some(alias) = alias {
+                aliases.insert(token.span.start, alias);
+            }
+        }
+        if let Some(block) = block {
+            canonical_aliases(block, keywords, aliases);
+        }
+    }
+}
+
+// Formats the script with one statement in every line, and indents the blocks
+// The comments are kept, the scripts with syntax errors aren't formatted
+// With a keyword table the aliases of the keywords are normalized to their canonical aliases
+// in the first language of the table (e.g. "e(10)" -> "forward(10)" in English)
+// e.g. input:  "repeat(4){fd( 10 );rt(90)}"
+//      output: "repeat(4) {\n    fd(10)\n    rt(90)\n}\n"
+pub fn format_script(source: &str, keywords: Option<&KeywordTable>) -> Result<String, TurtleError> {
+    let tokens = tokenize(source);
+    let statements = parse(&tokens)?;
+    let mut formatter = Formatter::default();
+    if let Some(keywords) = keywords {
+        canonical_aliases(&statements, keywords, &mut formatter.aliases);
+    }
+    for token in &tokens {
+        match token.kind {
+            TokenKind::Separator => {
+                formatter.end_line();
+                if token.text == "\n" {
+                    formatter.line_breaks += 1;
+                } else {
+                    formatter.separated = true;
+                }
+            }
+            TokenKind::OpenBrace => {
+                // The block can start in the next line after its command
+                if formatter.line.is_empty()
+                    && !formatter.separated
+                    && formatter.last.is_some_and(|last| {
+                        !is_line_comment(last) && last.kind != TokenKind::OpenBrace
+                    })
+                {
+                    formatter.line = formatter.lines.pop().unwrap_or_default();
+                    formatter.line = formatter.line.trim_start().to_string();
+                    formatter.line_depth = formatter.depth;
+                    formatter.previous = formatter.last;
+                }
+                formatter.line_breaks = 0;
+                formatter.write(token);
+                formatter.depth += 1;
+                formatter.block_opened = true;
+            }
+            TokenKind::CloseBrace => {
+                formatter.end_line();
+                formatter.depth = formatter.depth.saturating_sub(1);
+                formatter.line_breaks = 0;
+                formatter.write(token);
+            }
+            _ => {
+                formatter.write(token);
+                // The rest of the line belongs to the comment
+                if is_line_comment(token) {
+                    formatter.end_line();
+                }
+            }
+        }
+    }
+    formatter.end_line();
+    let mut script = formatter.lines.join("\n");
+    if !script.is_empty() {
+        script.push('\n');
+    }
+    Ok(script)
+}
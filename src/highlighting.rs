@@ -0,0 +1,199 @@
+use std::{collections::HashSet, ops::Range};
+
+use egui::{text::TextFormat, Color32, FontId, Visuals};
+
+use crate::{
+    keywords::KeywordTable,
+    parsing::{tokenize, TokenKind},
+    turtle::Turtle,
+};
+
+// The kinds of the highlighted parts of the source code
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Highlight {
+    // Whitespaces, operators, commas...
+    Plain,
+    // The aliases of the commands, the functions and the values
+    Keyword,
+    Number,
+    String,
+    Comment,
+    Variable,
+    Procedure,
+    // ( ) { } [ ]
+    Bracket,
+    // The bracket at the cursor and its pair
+    MatchingBracket,
+    // The brackets without pairs
+    UnbalancedBracket,
+}
+
+impl Highlight {
+    // The format of the highlighted part in the light and the dark theme
+    pub fn format(self, font_id: FontId, visuals: &Visuals) -> TextFormat {
+        let (light, dark) = match self {
+            Highlight::Keyword => (
+                Color32::from_rgb(0, 0, 200),
+                Color32::from_rgb(86, 156, 214),
+            ),
+            Highlight::Number => (
+                Color32::from_rgb(9, 134, 88),
+                Color32::from_rgb(181, 206, 168),
+            ),
+            Highlight::String => (
+                Color32::from_rgb(163, 21, 21),
+                Color32::from_rgb(206, 145, 120),
+            ),
+            Highlight::Comment => (
+                Color32::from_rgb(0, 128, 0),
+                Color32::from_rgb(106, 153, 85),
+            ),
+            Highlight::Variable => (
+                Color32::from_rgb(0, 16, 128),
+                Color32::from_rgb(156, 220, 254),
+            ),
+            Highlight::Procedure => (
+                Color32::from_rgb(121, 94, 38),
+                Color32::from_rgb(220, 220, 170),
+            ),
+            Highlight::UnbalancedBracket => (visuals.error_fg_color, visuals.error_fg_color),
+            Highlight::Plain | Highlight::Bracket | Highlight::MatchingBracket => {
+                (visuals.text_color(), visuals.text_color())
+            }
+        };
+        let mut format = TextFormat::simple(font_id, if visuals.dark_mode { dark } else { light });
+        if self == Highlight::MatchingBracket {
+            format.background = visuals.selection.bg_fill.gamma_multiply(0.5);
+        }
+        format
+    }
+}
+
+// The pairs of the opening and the closing brackets
+fn is_pair(open: TokenKind, close: TokenKind) -> bool {
+    matches!(
+        (open, close),
+        (TokenKind::OpenParenthesis, TokenKind::CloseParenthesis)
+            | (TokenKind::OpenBrace, TokenKind::CloseBrace)
+            | (TokenKind::OpenBracket, TokenKind::CloseBracket)
+    )
+}
+
+// Splits up the source code to highlighted parts (with the same tokens as the interpreter)
+// The parts cover the whole source code, the byte ranges follow each other
+// The cursor is counted in characters, the bracket before (or after) it is matched with its pair
+// e.g. "forward(size)" -> [("forward", Keyword), ("(", Bracket), ("size", Variable), (")", Bracket)]
+pub fn highlight(
+    source: &str,
+    keywords: &KeywordTable,
+    turtle: &Turtle,
+    cursor: Option<usize>,
+) -> Vec<(Range<usize>, Highlight)> {
+    let tokens = tokenize(source);
+    let is_procedure_keyword = |text: &str| keywords.command(text) == Some("procedure");
+    // The procedures defined by the source code, e.g. "to square(size) {...}"
+    let defined: HashSet<&str> = tokens
+        .windows(2)
+        .filter(|pair| {
+            pair[0].kind == TokenKind::Word
+                && is_procedure_keyword(&pair[0].text)
+                && pair[1].kind == TokenKind::Word
+        })
+        .map(|pair| pair[1].text.as_str())
+        .collect();
+    let mut highlights: Vec<Highlight> = tokens
+        .iter()
+        .enumerate()
+        .map(|(index, token)| match token.kind {
+            TokenKind::Word => {
+                let previous = index.checked_sub(1).and_then(|index| tokens.get(index));
+                let next = tokens.get(index + 1);
+                let is_keyword = keywords.command(&token.text).is_some()
+                    || keywords.function(&token.text).is_some();
+                if previous.is_some_and(|previous| {
+                    previous.kind == TokenKind::Word && is_procedure_keyword(&previous.text)
+                }) || (!is_keyword
+                    && next.is_some_and(|next| next.kind == TokenKind::OpenParenthesis))
+                {
+                    Highlight::Procedure
+                } else if is_keyword || keywords.value(&token.text).is_some() {
+                    Highlight::Keyword
+                } else if defined.contains(token.text.as_str())
+                    || turtle.procedures.contains_key(&token.text)
+                {
+                    Highlight::Procedure
+                } else {
+                    Highlight::Variable
+                }
+            }
+            TokenKind::Number => Highlight::Number,
+            TokenKind::String => Highlight::String,
+            TokenKind::Comment => Highlight::Comment,
+            TokenKind::OpenParenthesis
+            | TokenKind::CloseParenthesis
+            | TokenKind::OpenBrace
+            | TokenKind::CloseBrace
+            | TokenKind::OpenBracket
+            | TokenKind::CloseBracket => Highlight::Bracket,
+            _ => Highlight::Plain,
+        })
+        .collect();
+    // The indexes of the bracket tokens, which belong together
+    let mut pairs: Vec<(usize, usize)> = vec![];
+    let mut open: Vec<usize> = vec![];
+    for (index, token) in tokens.iter().enumerate() {
+        match token.kind {
+            TokenKind::OpenParenthesis | TokenKind::OpenBrace | TokenKind::OpenBracket => {
+                open.push(index)
+            }
+            TokenKind::CloseParenthesis | TokenKind::CloseBrace | TokenKind::CloseBracket => {
+                match open.last() {
+                    Some(&last) if is_pair(tokens[last].kind, token.kind) => {
+                        open.pop();
+                        pairs.push((last, index));
+                    }
+                    _ => highlights[index] = Highlight::UnbalancedBracket,
+                }
+            }
+            _ => {}
+        }
+    }
+    for index in open {
+        highlights[index] = Highlight::UnbalancedBracket;
+    }
+    if let Some(cursor) = cursor {
+        let cursor = source
+            .char_indices()
+            .nth(cursor)
+            .map_or(source.len(), |(index, _)| index);
+        let at_cursor = |index: usize| {
+            pairs
+                .iter()
+                .find(|(open, close)| *open == index || *close == index)
+                .copied()
+        };
+        let before = tokens.iter().position(|token| token.span.end == cursor);
+        let after = tokens.iter().position(|token| token.span.start == cursor);
+        if let Some((open, close)) = before
+            .and_then(at_cursor)
+            .or_else(|| after.and_then(at_cursor))
+        {
+            highlights[open] = Highlight::MatchingBracket;
+            highlights[close] = Highlight::MatchingBracket;
+        }
+    }
+    // The whitespaces between the tokens are plain
+    let mut parts: Vec<(Range<usize>, Highlight)> = vec![];
+    let mut end = 0;
+    for (token, highlight) in tokens.iter().zip(highlights) {
+        if token.span.start > end {
+            parts.push((end..token.span.start, Highlight::Plain));
+        }
+        parts.push((token.span.start..token.span.end, highlight));
+        end = token.span.end;
+    }
+    if end < source.len() {
+        parts.push((end..source.len(), Highlight::Plain));
+    }
+    parts
+}
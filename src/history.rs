@@ -0,0 +1,164 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{error::TurtleError, keywords::KeywordTable, locale::Locale, message::Argument};
+
+// The messages of the locale, which are written to the console
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, Copy)]
+pub enum MessageId {
+    TerminalHelp,
+    AmbiguousKeyword,
+    ProjectSaved,
+    SessionDrawingTooLarge,
+}
+
+impl MessageId {
+    pub fn text(self, locale: &Locale) -> &str {
+        match self {
+            MessageId::TerminalHelp => &locale.terminal_help_message,
+            MessageId::AmbiguousKeyword => &locale.ambiguous_keyword_error,
+            MessageId::ProjectSaved => &locale.project_saved,
+            MessageId::SessionDrawingTooLarge => &locale.session_drawing_too_large,
+        }
+    }
+}
+
+// The documentation printed by the help command
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub enum HelpTopic {
+    // Every command and function
+    All,
+    // The name of the command or the function, e.g. "forward"
+    Command(String),
+    Function(String),
+}
+
+// An entry of the console's history
+// The messages are stored with their ids and arguments, so they are displayed in the selected language,
+// even if the language is changed after they were written
+// The entries are stored without tags, so the texts are saved as they were before
+// (e.g. ["square", {"id": "ProjectSaved", "arguments": []}])
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum HistoryEntry {
+    // Printed by the script, it isn't translated
+    Text(String),
+    Error(TurtleError),
+    Message {
+        id: MessageId,
+        arguments: Vec<(String, Argument)>,
+    },
+    Help {
+        topic: HelpTopic,
+    },
+}
+
+impl HistoryEntry {
+    pub fn message(id: MessageId, arguments: &[(&str, Argument)]) -> Self {
+        HistoryEntry::Message {
+            id,
+            arguments: arguments
+                .iter()
+                .map(|(name, argument)| (name.to_string(), argument.clone()))
+                .collect(),
+        }
+    }
+    // The lines of the entry in the language of the locale and the keywords
+    pub fn lines(&self, locale: &Locale, keywords: &KeywordTable) -> Vec<String> {
+        match self {
+            // The line breaks of the texts start new lines in the console
+            HistoryEntry::Text(text) => text.split('\n').map(str::to_string).collect(),
+            HistoryEntry::Error(error) => vec![error.message(locale)],
+            HistoryEntry::Message { id, arguments } => {
+                let arguments: Vec<(&str, Argument)> = arguments
+                    .iter()
+                    .map(|(name, argument)| (name.as_str(), argument.clone()))
+                    .collect();
+                vec![locale.format(id.text(locale), &arguments)]
+            }
+            HistoryEntry::Help { topic } => {
+                let Some(manual) = keywords.manual() else {
+                    return vec![];
+                };
+                let documentation = match topic {
+                    HelpTopic::All => return manual.lines(),
+                    HelpTopic::Command(name) => manual.commands.get(name),
+                    HelpTopic::Function(name) => manual.functions.get(name),
+                };
+                documentation
+                    .map(|documentation| documentation.lines(manual))
+                    .unwrap_or_default()
+            }
+        }
+    }
+}
+
+// The lines of the console
+pub fn history_lines(
+    history: &[HistoryEntry],
+    locale: &Locale,
+    keywords: &KeywordTable,
+) -> Vec<String> {
+    history
+        .iter()
+        .flat_map(|entry| entry.lines(locale, keywords))
+        .collect()
+}
+
+// The number of inputs, which are kept in the input history
+const MAX_INPUTS: usize = 100;
+
+// The inputs of the console, which can be recalled by the Up and Down keys
+// They are kept separately from the lines of the console
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct InputHistory {
+    inputs: Vec<String>,
+    // The index of the recalled input (None while a new input is typed)
+    #[serde(skip)]
+    position: Option<usize>,
+    // The input, which was typed before the older inputs were recalled
+    #[serde(skip)]
+    draft: String,
+}
+
+impl InputHistory {
+    // The same input isn't added twice in a row, and the empty inputs aren't added
+    pub fn push(&mut self, input: &str) {
+        self.position = None;
+        self.draft.clear();
+        if input.trim().is_empty() || self.inputs.last().is_some_and(|last| last == input) {
+            return;
+        }
+        self.inputs.push(input.to_string());
+        if self.inputs.len() > MAX_INPUTS {
+            self.inputs.remove(0);
+        }
+    }
+    // The input before the recalled one (Up key), the current input is restored by recall_next
+    pub fn recall_previous(&mut self, current: &str) -> Option<&str> {
+        let position = match self.position {
+            None => {
+                let last = self.inputs.len().checked_sub(1)?;
+                self.draft = current.to_string();
+                last
+            }
+            Some(position) => position.saturating_sub(1),
+        };
+        self.position = Some(position);
+        Some(&self.inputs[position])
+    }
+    // The input after the recalled one (Down key), or the input that was typed before
+    pub fn recall_next(&mut self) -> Option<&str> {
+        let position = self.position?;
+        if position + 1 < self.inputs.len() {
+            self.position = Some(position + 1);
+            Some(&self.inputs[position + 1])
+        } else {
+            self.position = None;
+            Some(&self.draft)
+        }
+    }
+    pub fn inputs(&self) -> &[String] {
+        &self.inputs
+    }
+}
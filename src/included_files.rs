@@ -3,6 +3,16 @@
     pub locale_hu: &'a str,
 }
 
+impl LocaleFolder<'_> {
+    // The names and the contents of the included locale files
+    pub fn files(&self) -> [(&str, &str); 2] {
+        [
+            ("locale_en.yml", self.locale_en),
+            ("locale_hu.yml", self.locale_hu),
+        ]
+    }
+}
+
 pub struct IncludedFiles<'a> {
     pub locales: LocaleFolder<'a>,
 }
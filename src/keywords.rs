@@ -0,0 +1,276 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, OnceLock},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    documentation::{Documentation, Manual},
+    included_files::FILES,
+};
+
+// The keywords of a language, they are declared in the locale files
+// e.g. "forward: f fd forward" - the aliases of the forward command are separated by spaces
+// The names of the commands, functions and values are the same in every language
+// The missing sections of a translation are taken from the English keywords
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct Keywords {
+    pub commands: BTreeMap<String, String>,
+    pub functions: BTreeMap<String, String>,
+    // The named arguments of the commands (e.g. linecap(round))
+    pub values: BTreeMap<String, String>,
+}
+
+// The English keywords are only declared in the English locale file
+// (they can't be read as Keywords, because its missing sections are filled by the default)
+#[derive(Deserialize)]
+struct EnglishLocale {
+    keywords: EnglishKeywords,
+}
+
+#[derive(Deserialize)]
+struct EnglishKeywords {
+    commands: BTreeMap<String, String>,
+    functions: BTreeMap<String, String>,
+    values: BTreeMap<String, String>,
+}
+
+// The English locale file is read only once
+static ENGLISH_KEYWORDS: OnceLock<Keywords> = OnceLock::new();
+
+impl Default for Keywords {
+    fn default() -> Self {
+        ENGLISH_KEYWORDS
+            .get_or_init(|| {
+                let EnglishKeywords {
+                    commands,
+                    functions,
+                    values,
+                } = yaml_serde::from_str::<EnglishLocale>(FILES.locales.locale_en)
+                    .expect("the English locale file declares the keywords")
+                    .keywords;
+                Self {
+                    commands,
+                    functions,
+                    values,
+                }
+            })
+            .clone()
+    }
+}
+
+// Alias that belongs to more than one command (or function or value) in the same language
+// e.g. "r" - ["repeat", "right"]
+#[derive(Debug, PartialEq, Clone)]
+pub struct Ambiguity {
+    pub alias: String,
+    pub names: Vec<String>,
+}
+
+// The names of the commands (or functions or values) by their aliases
+fn names_by_alias(keywords: &BTreeMap<String, String>) -> BTreeMap<&str, Vec<&str>> {
+    let mut names: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for (name, aliases) in keywords {
+        for alias in aliases.split_whitespace() {
+            let names = names.entry(alias).or_default();
+            if !names.contains(&name.as_str()) {
+                names.push(name);
+            }
+        }
+    }
+    names
+}
+
+impl Keywords {
+    fn tables(&self) -> [&BTreeMap<String, String>; 3] {
+        [&self.commands, &self.functions, &self.values]
+    }
+    // The ambiguous aliases can't be used in this language, because it isn't known,
+    // which command they mean
+    pub fn ambiguities(&self) -> Vec<Ambiguity> {
+        self.tables()
+            .into_iter()
+            .flat_map(|keywords| {
+                names_by_alias(keywords)
+                    .into_iter()
+                    .filter(|(_, names)| names.len() > 1)
+                    .map(|(alias, names)| Ambiguity {
+                        alias: alias.to_string(),
+                        names: names.into_iter().map(str::to_string).collect(),
+                    })
+                    .collect::<Vec<Ambiguity>>()
+            })
+            .collect()
+    }
+}
+
+// The aliases and the names of the commands, functions and values, which they mean
+#[derive(Debug, Default, Clone)]
+struct KeywordMap {
+    commands: HashMap<String, String>,
+    functions: HashMap<String, String>,
+    values: HashMap<String, String>,
+    // The aliases of the commands, the functions and the values by their names
+    // (the aliases of the first language are the first ones)
+    aliases: HashMap<String, Vec<String>>,
+    // The documentation of the commands and the functions with their aliases
+    manual: Option<Manual>,
+}
+
+// Finds the commands, the functions and the values by their aliases, and their documentation
+// The keywords of the selected language and the fallback language (English) can be used
+// The table isn't saved with the turtle, because it depends on the language of the application
+#[derive(Debug, Clone)]
+pub struct KeywordTable {
+    map: Arc<KeywordMap>,
+}
+
+// The default table is created only once (the default turtle is created often)
+static DEFAULT_TABLE: OnceLock<KeywordTable> = OnceLock::new();
+
+impl Default for KeywordTable {
+    fn default() -> Self {
+        DEFAULT_TABLE
+            .get_or_init(|| {
+                KeywordTable::new(&[&Keywords::default()]).documented(&[&Manual::default()])
+            })
+            .clone()
+    }
+}
+
+impl KeywordTable {
+    // The first languages are preferred, if an alias means different commands in them
+    // e.g. [selected language, fallback language]
+    pub fn new(languages: &[&Keywords]) -> Self {
+        let mut map = KeywordMap::default();
+        for keywords in languages.iter().rev() {
+            for (table, keywords) in [&mut map.commands, &mut map.functions, &mut map.values]
+                .into_iter()
+                .zip(keywords.tables())
+            {
+                for (alias, names) in names_by_alias(keywords) {
+                    if let [name] = names[..] {
+                        table.insert(alias.to_string(), name.to_string());
+                    }
+                }
+            }
+        }
+        for keywords in languages {
+            for (table, keywords) in [&map.commands, &map.functions, &map.values]
+                .into_iter()
+                .zip(keywords.tables())
+            {
+                for (name, aliases) in keywords {
+                    for alias in aliases.split_whitespace() {
+                        let known = map.aliases.entry(name.clone()).or_default();
+                        if table.get(alias) == Some(name)
+                            && !known.iter().any(|known| known == alias)
+                        {
+                            known.push(alias.to_string());
+                        }
+                    }
+                }
+            }
+        }
+        Self { map: Arc::new(map) }
+    }
+    // Adds the documentation of the languages to the table (in the same order as their keywords)
+    pub fn documented(mut self, manuals: &[&Manual]) -> Self {
+        let Some(first) = manuals.first() else {
+            return self;
+        };
+        let mut manual = Manual {
+            aliases_label: first.aliases_label.clone(),
+            examples_label: first.examples_label.clone(),
+            commands: BTreeMap::new(),
+            functions: BTreeMap::new(),
+        };
+        for other in manuals.iter().rev() {
+            manual.commands.extend(other.commands.clone());
+            manual.functions.extend(other.functions.clone());
+        }
+        for (name, documentation) in manual
+            .commands
+            .iter_mut()
+            .chain(manual.functions.iter_mut())
+        {
+            documentation.aliases = self.map.aliases.get(name).cloned().unwrap_or_default();
+        }
+        Arc::make_mut(&mut self.map).manual = Some(manual);
+        self
+    }
+    // The name of the command, e.g. "fd" -> "forward"
+    pub fn command(&self, alias: &str) -> Option<&str> {
+        self.map.commands.get(alias).map(String::as_str)
+    }
+    pub fn function(&self, alias: &str) -> Option<&str> {
+        self.map.functions.get(alias).map(String::as_str)
+    }
+    pub fn value(&self, alias: &str) -> Option<&str> {
+        self.map.values.get(alias).map(String::as_str)
+    }
+    // Every alias of the commands, the functions and the values (in no particular order)
+    pub fn aliases(&self) -> impl Iterator<Item = &str> {
+        self.map
+            .commands
+            .keys()
+            .chain(self.map.functions.keys())
+            .chain(self.map.values.keys())
+            .map(String::as_str)
+    }
+    // The alias, which stands for the other aliases of the same keyword (e.g. in the formatted scripts)
+    // It's the alias in the usage of the documentation (e.g. "fd" -> "forward", "e" -> "elore"),
+    // or the first alias of the first language, e.g. for the values
+    fn canonical<'a>(
+        &'a self,
+        alias: &str,
+        table: &'a HashMap<String, String>,
+        manual: Option<&'a BTreeMap<String, Documentation>>,
+    ) -> Option<&'a str> {
+        let name = table.get(alias)?;
+        let usage = manual
+            .and_then(|manual| manual.get(name))
+            .and_then(|documentation| {
+                documentation
+                    .usage
+                    .split(|char: char| !(char.is_alphanumeric() || char == '_'))
+                    .next()
+            });
+        match usage {
+            Some(usage) if table.get(usage) == Some(name) => Some(usage),
+            _ => self
+                .map
+                .aliases
+                .get(name)
+                .and_then(|aliases| aliases.first())
+                .map(String::as_str),
+        }
+    }
+    // e.g. "fd" -> "forward"
+    pub fn canonical_command(&self, alias: &str) -> Option<&str> {
+        let manual = self.manual().map(|manual| &manual.commands);
+        self.canonical(alias, &self.map.commands, manual)
+    }
+    // e.g. "rnd" -> "random"
+    pub fn canonical_function(&self, alias: &str) -> Option<&str> {
+        let manual = self.manual().map(|manual| &manual.functions);
+        self.canonical(alias, &self.map.functions, manual)
+    }
+    pub fn canonical_value(&self, alias: &str) -> Option<&str> {
+        self.canonical(alias, &self.map.values, None)
+    }
+    pub fn manual(&self) -> Option<&Manual> {
+        self.map.manual.as_ref()
+    }
+    // The documentation of the command or the function, e.g. "fd" -> the documentation of forward
+    pub fn documentation(&self, alias: &str) -> Option<&Documentation> {
+        let manual = self.manual()?;
+        match (self.command(alias), self.function(alias)) {
+            (Some(name), _) => manual.commands.get(name),
+            (None, Some(name)) => manual.functions.get(name),
+            (None, None) => None,
+        }
+    }
+}
@@ -1,10 +1,30 @@
+pub mod analysis;
 mod app;
-mod arithmetic;
+pub mod arithmetic;
+pub mod canvas;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod cli;
+pub mod completion;
+pub mod history;
 mod included_files;
-mod locale;
+pub mod keywords;
+pub mod locale;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod lsp;
+pub mod message;
 //mod boolean;
-mod commands;
-//mod documentation;
-mod parsing;
-mod turtle;
+pub mod commands;
+pub mod documentation;
+pub mod error;
+pub mod formatting;
+pub mod highlighting;
+pub mod parsing;
+pub mod path;
+pub mod project;
+pub mod random;
+pub mod turtle;
 pub use app::RuggedTurtleApp;
+pub use canvas::to_svg;
+
+// The name of the application, it's also the name of its data directory
+pub const APP_NAME: &str = "Rugged turtle";
@@ -1,93 +1,412 @@
+use std::{fs, path::PathBuf};
+
 use serde::{Deserialize, Serialize};
+use yaml_serde::Value;
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::APP_NAME;
+use crate::{
+    documentation::Manual,
+    included_files::FILES,
+    keywords::Keywords,
+    message::{format_message, placeholders, Argument, PluralRule},
+};
+
+// The directories of the locale files, which can add new languages or override the included ones
+// (without rebuilding the application), the later ones are preferred:
+// - the "locale" directory next to the executable file
+// - the "locale" directory in the data directory of the application
+//   (e.g. ~/.local/share/ruggedturtle/locale on Linux, %APPDATA%\Rugged turtle\data\locale on Windows)
+#[cfg(not(target_arch = "wasm32"))]
+pub fn locale_directories() -> Vec<PathBuf> {
+    let executable_directory = std::env::current_exe()
+        .ok()
+        .and_then(|path| path.parent().map(|directory| directory.to_path_buf()));
+    [executable_directory, eframe::storage_dir(APP_NAME)]
+        .into_iter()
+        .flatten()
+        .map(|directory| directory.join("locale"))
+        .collect()
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum LocaleErrorKind {
+    // The file couldn't be read or it isn't a valid locale file
+    Invalid { error: String },
+    // A text of the file contains other placeholders than the default text,
+    // it's replaced by the default text
+    InvalidPlaceholders { key: String, expected: Vec<String> },
+}
+
+// A problem of a locale file, which is reported when the application is started
+#[derive(Debug, PartialEq, Clone)]
+pub struct LocaleError {
+    pub path: String,
+    pub kind: LocaleErrorKind,
+}
+
+impl LocaleError {
+    pub fn message(&self, locale: &Locale) -> String {
+        match &self.kind {
+            LocaleErrorKind::Invalid { error } => locale.format(
+                &locale.locale_invalid_error,
+                &[
+                    ("path", self.path.as_str().into()),
+                    ("error", error.as_str().into()),
+                ],
+            ),
+            LocaleErrorKind::InvalidPlaceholders { key, expected } => {
+                let expected: Vec<String> = expected
+                    .iter()
+                    .map(|placeholder| format!("{{{}}}", placeholder))
+                    .collect();
+                locale.format(
+                    &locale.locale_placeholder_error,
+                    &[
+                        ("path", self.path.as_str().into()),
+                        ("key", key.as_str().into()),
+                        ("placeholders", expected.join(", ").into()),
+                    ],
+                )
+            }
+        }
+    }
+}
+
+// Loads the languages of the application from the included files and the locale directories
+pub fn import_locales() -> (Vec<Locale>, Vec<LocaleError>) {
+    #[cfg(not(target_arch = "wasm32"))]
+    let directories = locale_directories();
+    // There are no locale directories in the browser
+    #[cfg(target_arch = "wasm32")]
+    let directories = vec![];
+    load_locales(&directories)
+}
 
-pub fn import_locales(locales: &mut Vec<Locale>) -> Vec<Locale> {
-    locales.clear();
-    #[cfg(not(any(
-        target_os = "android",
-        target_arch = "wasm32",
-        target_os = "ios",
-        target_os = "macos"
-    )))]
-    {
-        // Materials - PC version (loads them from the src/materials folder)
-
-        use std::fs;
-        let paths = fs::read_dir("src/locale").unwrap();
+// The locale files included in the executable are loaded first, then the locale files
+// of the directories replace the languages with the same language_id or add new languages
+// The files, that can't be loaded, are returned as errors
+pub fn load_locales(directories: &[PathBuf]) -> (Vec<Locale>, Vec<LocaleError>) {
+    let mut locales: Vec<Locale> = vec![];
+    let mut errors: Vec<LocaleError> = vec![];
+    for (path, contents) in FILES.locales.files() {
+        add_locale(&mut locales, &mut errors, path, contents);
+    }
+    for directory in directories {
+        let Ok(entries) = fs::read_dir(directory) else {
+            // The directories are optional
+            continue;
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|extension| extension == "yml" || extension == "yaml")
+            })
+            .collect();
+        paths.sort();
         for path in paths {
-            if path
-                .as_ref()
-                .is_ok_and(|path| path.file_name() != "default_locale.yml")
+            let display = path.display().to_string();
+            match fs::read_to_string(&path) {
+                Ok(contents) => add_locale(&mut locales, &mut errors, &display, &contents),
+                Err(error) => errors.push(LocaleError {
+                    path: display,
+                    kind: LocaleErrorKind::Invalid {
+                        error: error.to_string(),
+                    },
+                }),
+            }
+        }
+    }
+    // The application always has a language
+    if locales.is_empty() {
+        locales.push(Locale::default());
+    }
+    (locales, errors)
+}
+
+// Only the language_id is required in the locale files, the other keys can be missing
+#[derive(Deserialize)]
+struct LanguageId {
+    #[allow(dead_code)]
+    language_id: String,
+}
+
+fn add_locale(
+    locales: &mut Vec<Locale>,
+    errors: &mut Vec<LocaleError>,
+    path: &str,
+    contents: &str,
+) {
+    let locale = yaml_serde::from_str::<LanguageId>(contents)
+        .and_then(|_| yaml_serde::from_str::<Locale>(contents));
+    match locale {
+        Ok(locale) => {
+            let (locale, invalid) = validate_placeholders(locale);
+            errors.extend(invalid.into_iter().map(|(key, expected)| LocaleError {
+                path: path.to_string(),
+                kind: LocaleErrorKind::InvalidPlaceholders { key, expected },
+            }));
+            match locales
+                .iter_mut()
+                .find(|loaded| loaded.language_id == locale.language_id)
             {
-                let locale: Result<Vec<u8>, std::io::Error> =
-                    fs::read(path.as_ref().unwrap().path().display().to_string().as_str());
-                let serialized_locale: Locale = yaml_serde::from_reader(locale.unwrap().as_slice())
-                    .unwrap_or(Locale::default());
-                locales.push(serialized_locale);
+                Some(loaded) => *loaded = locale,
+                None => locales.push(locale),
             }
         }
+        Err(error) => errors.push(LocaleError {
+            path: path.to_string(),
+            kind: LocaleErrorKind::Invalid {
+                error: error.to_string(),
+            },
+        }),
     }
-    #[cfg(any(
-        target_os = "android",
-        target_arch = "wasm32",
-        target_os = "ios",
-        target_os = "macos"
-    ))]
-    {
-        // Materials - Portable version (includes the files in src/materials in the executable file)
-
-        use yaml_serde::from_str;
-
-        use crate::included_files::FILES;
-        // Locale
-        locales.push(from_str(&FILES.locales.locale_en).unwrap());
-        locales.push(from_str(&FILES.locales.locale_hu).unwrap());
+}
+
+// The messages of the translation can only contain the placeholders of the default messages (in any order)
+// The messages with other or invalid placeholders are replaced by the default messages,
+// and they are returned with the placeholders of the default messages
+fn validate_placeholders(locale: Locale) -> (Locale, Vec<(String, Vec<String>)>) {
+    let (Ok(Value::Mapping(mut translation)), Ok(Value::Mapping(default))) = (
+        yaml_serde::to_value(&locale),
+        yaml_serde::to_value(Locale::default()),
+    ) else {
+        return (locale, vec![]);
+    };
+    let mut invalid: Vec<(String, Vec<String>)> = vec![];
+    for (key, value) in default {
+        let (Some(key), Value::String(default)) = (key.as_str(), &value) else {
+            continue;
+        };
+        let Some(Value::String(message)) = translation.get(key) else {
+            continue;
+        };
+        let expected = placeholders(default).unwrap_or_default();
+        let valid = placeholders(message)
+            .is_some_and(|names| names.iter().all(|name| expected.contains(name)));
+        if !valid {
+            translation.insert(Value::from(key), value.clone());
+            invalid.push((key.to_string(), expected));
+        }
+    }
+    match yaml_serde::from_value(Value::Mapping(translation)) {
+        Ok(validated) if !invalid.is_empty() => (validated, invalid),
+        _ => (locale, invalid),
+    }
+}
+
+// The differences between a translation and the default (English) locale
+// The keys of the sections are joined by dots, e.g. "keywords.commands.forward"
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct LocaleReport {
+    // The keys, which are taken from the default locale
+    pub missing: Vec<String>,
+    // The keys, which aren't used by the application
+    pub extra: Vec<String>,
+    // The messages with other placeholders than the default ones
+    // (key, placeholders of the translation - None if it's invalid, placeholders of the default locale)
+    pub placeholders: Vec<(String, Option<Vec<String>>, Vec<String>)>,
+}
+
+impl LocaleReport {
+    pub fn is_empty(&self) -> bool {
+        self.missing.is_empty() && self.extra.is_empty() && self.placeholders.is_empty()
     }
-    locales.clone()
 }
 
+// Compares the contents of a locale file with the default locale
+// The file has to be a valid locale, otherwise the error of the file is returned
+pub fn check_locale(contents: &str) -> Result<LocaleReport, String> {
+    yaml_serde::from_str::<LanguageId>(contents)
+        .and_then(|_| yaml_serde::from_str::<Locale>(contents))
+        .map_err(|error| error.to_string())?;
+    let translation: Value = yaml_serde::from_str(contents).map_err(|error| error.to_string())?;
+    let default = yaml_serde::to_value(Locale::default()).map_err(|error| error.to_string())?;
+    let mut report = LocaleReport::default();
+    compare_keys(&mut report, "", &translation, &default);
+    Ok(report)
+}
+
+fn compare_keys(report: &mut LocaleReport, prefix: &str, translation: &Value, default: &Value) {
+    let key_name = |key: &Value| {
+        let key = key
+            .as_str()
+            .map(str::to_string)
+            .unwrap_or(format!("{:?}", key));
+        match prefix {
+            "" => key,
+            _ => format!("{}.{}", prefix, key),
+        }
+    };
+    match (translation, default) {
+        (Value::Mapping(translation), Value::Mapping(default)) => {
+            for (key, value) in default.iter() {
+                match translation.get(key) {
+                    Some(translated) => compare_keys(report, &key_name(key), translated, value),
+                    None => report.missing.push(key_name(key)),
+                }
+            }
+            for key in translation.keys() {
+                if !default.contains_key(key) {
+                    report.extra.push(key_name(key));
+                }
+            }
+        }
+        // Only the messages have placeholders, the documentation can contain braces
+        (Value::String(translation), Value::String(default)) if !prefix.contains('.') => {
+            let translated = placeholders(translation);
+            let expected = placeholders(default).unwrap_or_default();
+            if translated.as_ref() != Some(&expected) {
+                report
+                    .placeholders
+                    .push((prefix.to_string(), translated, expected));
+            }
+        }
+        // The lists (e.g. the examples of the documentation) can be different in the translations
+        _ => {}
+    }
+}
+
+// The texts of a language, the missing keys of a translation are taken from the default (English) locale
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
 pub struct Locale {
     pub language_id: String,
     pub language_name: String,
+    // The separator of the fractions in the numbers of the messages, e.g. "." - 0.5, "," - 0,5
+    pub decimal_separator: String,
+    // The forms of the words, which belong to the numbers, are chosen by the plural rule
+    pub plural_rule: PluralRule,
     pub terminal_help_message: String,
     pub run_button: String,
+    pub format_button: String,
+    pub normalize_keywords_checkbox: String,
     pub pencolor_button: String,
     pub pen_width_button: String,
     pub reset_menu: String,
+    pub copy_svg_menu: String,
+    pub open_project_menu: String,
+    pub save_project_menu: String,
+    pub include_drawing_menu: String,
     pub file_menu: String,
     pub settings_menu: String,
     pub languages_menu: String,
     pub dark_theme_menu: String,
     pub light_theme_menu: String,
+    pub restore_session_menu: String,
+    pub keep_variables_menu: String,
     pub terminal_title: String,
+    pub script_editor_title: String,
     pub colorpicker_dialog_title: String,
     pub colorpicker_dialog_text: String,
     pub done_button: String,
+    pub ok_button: String,
     pub cancel_button: String,
+    pub yes_button: String,
+    pub no_button: String,
     pub copy_color_button: String,
     pub pen_width_dialog_title: String,
     pub pen_width_dialog_text: String,
     pub new_canvas_dialog_title: String,
     pub new_canvas_dialog_text: String,
+    pub session_drawing_too_large: String,
+    pub project_path_dialog_title: String,
+    pub project_path_dialog_text: String,
+    pub project_error_title: String,
+    pub project_invalid_error: String,
+    pub project_version_error: String,
+    pub project_io_error: String,
+    pub project_saved: String,
+    pub locale_error_title: String,
+    pub locale_invalid_error: String,
+    pub locale_placeholder_error: String,
     pub help_menu: String,
+    pub help_search_hint: String,
+    pub help_example_hint: String,
+    pub help_commands_title: String,
+    pub help_functions_title: String,
     pub invalid_var_name_error: String,
     pub var_immutable_error: String,
     pub invalid_distance_error: String,
     pub invalid_angle_error: String,
+    pub fence_error: String,
     pub invalid_color_value_error: String,
     pub invalid_color_interval_error: String,
     pub invalid_pen_size_error: String,
+    pub invalid_pen_dash_error: String,
+    pub invalid_line_cap_error: String,
+    pub invalid_line_join_error: String,
     pub invalid_variable_error: String,
     pub invalid_loop_start_error: String,
     pub invalid_loop_end_error: String,
     pub invalid_loop_interval_error: String,
+    pub invalid_loop_step_error: String,
+    pub invalid_loop_count_error: String,
+    pub loop_control_error: String,
+    pub missing_block_error: String,
+    pub invalid_procedure_name_error: String,
+    pub wrong_argument_count_error: String,
+    pub nesting_limit_error: String,
+    pub expected_number_error: String,
+    pub unterminated_string_error: String,
+    pub unknown_function_error: String,
+    pub invalid_label_size_error: String,
+    pub invalid_index_error: String,
+    pub expected_list_error: String,
+    pub invalid_random_range_error: String,
+    pub invalid_seed_error: String,
+    pub expression_nesting_limit_error: String,
+    pub range_length_error: String,
+    pub step_limit_error: String,
+    pub value_size_error: String,
     pub invalid_expression: String,
+    pub unknown_command_error: String,
+    pub missing_argument_error: String,
+    pub unbalanced_brackets_error: String,
+    pub unexpected_token_error: String,
+    pub unterminated_comment_error: String,
+    pub ambiguous_keyword_error: String,
+    pub unused_variable_warning: String,
+    pub shadowed_loop_variable_warning: String,
+    pub unreachable_code_warning: String,
+    pub loop_variable_write_warning: String,
+    pub cli_invalid_seed_error: String,
+    pub cli_missing_value_error: String,
+    pub cli_unknown_argument_error: String,
+    pub cli_option_without_command_error: String,
+    pub cli_missing_files_error: String,
+    pub cli_unknown_language_error: String,
+    pub cli_read_error: String,
+    pub cli_write_error: String,
+    pub cli_locale_load_error: String,
+    pub cli_locale_ok: String,
+    pub cli_missing_key: String,
+    pub cli_extra_key: String,
+    pub cli_different_placeholders: String,
+    pub cli_invalid_placeholders: String,
+    pub cli_not_formatted: String,
+    pub cli_usage: String,
+    // The aliases of the commands, functions and values in this language
+    pub keywords: Keywords,
+    // The documentation of the commands and the functions in this language
+    pub documentation: Manual,
 }
 
 impl Locale {
     pub fn get_language_name(&self) -> String {
         String::from(&self.language_name)
     }
+    // The message of the language with the named arguments
+    // e.g. format(&locale.var_immutable_error, &[("name", "x".into())])
+    pub fn format(&self, message: &str, arguments: &[(&str, Argument)]) -> String {
+        format_message(
+            message,
+            arguments,
+            &self.decimal_separator,
+            self.plural_rule,
+        )
+    }
 }
 
 pub fn get_text(locale: &Vec<Locale>, selected_locale: usize) -> &Locale {
@@ -99,96 +418,142 @@ impl Default for Locale {
         Self {
             language_id: String::from("EN"),
             language_name: String::from("English"),
-            terminal_help_message: String::from("Type \"help\" to display the commands! If the turtle is not visible, type \"reset\" command."),
+            decimal_separator: String::from("."),
+            plural_rule: PluralRule::OneOther,
+            terminal_help_message: String::from("Type \"help\" to display the commands! If the turtle is not visible, type \"reset\" command, or use the \"wrap\" or \"fence\" commands to keep it on the canvas."),
             run_button: String::from("Run"),
+            format_button: String::from("Format"),
+            normalize_keywords_checkbox: String::from("Normalize keywords"),
             pencolor_button: String::from("Change pen color..."),
             pen_width_button: String::from("Change pen width..."),
             reset_menu: String::from("Reset"),
+            copy_svg_menu: String::from("Copy drawing as SVG"),
+            open_project_menu: String::from("Open project..."),
+            save_project_menu: String::from("Save project..."),
+            include_drawing_menu: String::from("Save the drawing with the project"),
             file_menu: String::from("File"),
             settings_menu: String::from("Settings"),
             languages_menu: String::from("Languages"),
             dark_theme_menu: String::from("Dark theme"),
             light_theme_menu: String::from("Light theme"),
+            restore_session_menu: String::from("Restore the last session on startup"),
+            keep_variables_menu: String::from("Keep the variables between the inputs"),
             terminal_title: String::from(" - Command history - "),
+            script_editor_title: String::from("Script editor"),
             colorpicker_dialog_title: String::from("Color selection"),
             colorpicker_dialog_text: String::from("Please, select a color: "),
             done_button: String::from("Done"),
+            ok_button: String::from("OK"),
             cancel_button: String::from("Cancel"),
+            yes_button: String::from("Yes"),
+            no_button: String::from("No"),
             copy_color_button: String::from("Copy color..."),
             pen_width_dialog_title: String::from("Line width selection"),
             pen_width_dialog_text: String::from("Please, adjust the line width: "),
             new_canvas_dialog_title: String::from("New canvas creation..."),
             new_canvas_dialog_text: String::from("Please, specify the size of the new canvas:"),
-            help_menu: String::from(r#"#############
- # Variables
- #############
- -   <variable_name> = variable value (also it support arithmetic operations (+, -, *, /, :, %))
- 
- 
- #########################
- # Arithmetic operations
- #########################
- - '+' - addition (it adds two numbers or variables together)
- - '-' - subtraction (it subtracts a number or variable from another number or variable)
- - '*' - multiplication (multiplies a number or variable with another number or variable)
- - '/' - full division (divides a number or variable with another number or variable -> it can yield a decimal number)
- - ':' - integer division (divides a number or variable with another number or variable -> it yields an integer)
- - '%' - remainder (it gives the remainder from a division)
- 
- 
- #############
- # Commands
- #############
- - forward(number of pixels, that the turtle needs to move forward)
- Aliases: e(), elore(), f(), forward()
- 
- - right(the angle that the turtle needs to turn to the right in degrees - 0-360)
- Aliases: j(), jobb(), jobbra(), r(), right()
- 
- - left(the angle that the turtle needs to turn to the left in degrees - 0-360)
- Aliases: b(), bal(), balra(), l(), left()
- 
- - pencolor(red color component 0-255, green color component 0-255, blue color component 0-255, alpha channel value 0-255) - it changes the color of the line using the RGBA color model. Examples: (0,0,0,255) - black, (255,255,255,255) - white. (255,255,255,0) - transparent
- Aliases: tsz(), tollszin(), szin(), pc(), pencolor(), color()
- 
- - penwidth(width of the pen in pixels -> large value = thicker line)
- Aliases: tv(), tollvastagsag(), vastagsag(), pw(), penwidth(), width()
- 
- - penup - it picks up the pen, so the turtle doesn't leave a trail
- Aliases: tf, tollfel, pu, penup
- 
- - pendown - it puts down the pen, so the turtle leaves a trail
- Aliases: tl, tollle, pd, pendown
- 
- - evaluate(<variable>) - it evaluates an expression or variable and displays it's simplified form
- Aliases: kier(), kiertekeles(), kiszamolas(), eval(), calc(), calculate(), evaluate()
- 
- - print(<variable>) - it prints the variables' expression without simplification
- Aliases: ki(), kiir(), kiiratas(), print()
- 
- - clear - clears the terminal's command history
- Aliases: trl, torol, clr, clear
-
- - reset - resets the application
- Aliases: alaphelyzet, reset, default
- 
- - help - diplays the help menu for the commands
- Aliases: ?, segitseg, help
- 
- - for(<variable>, from, to (exclusive boundary - it goes until it hits the number before the specified boundary)) {commands}
- Aliases: i() {}, ism() {}, ismetles() {}, r() {}, rep() {}, repeat() {}, for() {}"#),
+            session_drawing_too_large: String::from("The drawing of the last session was too large to be restored!"),
+            project_path_dialog_title: String::from("Project file"),
+            project_path_dialog_text: String::from("Path of the project file:"),
+            project_error_title: String::from("Project error"),
+            project_invalid_error: String::from("The file is not a valid project: {reason}"),
+            project_version_error: String::from("The project was saved by a newer version of the application (project version: {version}), please update the application!"),
+            project_io_error: String::from("The project file could not be opened or saved: {error}"),
+            project_saved: String::from("The project was saved."),
+            locale_error_title: String::from("Language file error"),
+            locale_invalid_error: String::from("The language file \"{path}\" could not be loaded: {error}"),
+            locale_placeholder_error: String::from("The \"{key}\" text of the language file \"{path}\" can only contain these placeholders: {placeholders}! The default text is used instead."),
+            help_menu: String::from("Help"),
+            help_search_hint: String::from("Search..."),
+            help_example_hint: String::from("Click on an example to insert it into the script editor!"),
+            help_commands_title: String::from("Commands"),
+            help_functions_title: String::from("Functions"),
             invalid_var_name_error: String::from("You can't name a variable as a valid numerical value/expression."),
-            var_immutable_error: String::from("You can't update the \"{}\" variable!"),
-            invalid_distance_error: String::from("The turtle can't travel the specified distance ({})!"),
-            invalid_angle_error: String::from("The turtle can't rotate the specified angle ({})!"),
-            invalid_color_value_error: String::from("The specified color value ({}) can't be specified! The color value intervals need to be between 0 and 255."),
-            invalid_color_interval_error: String::from("The color values can be between 0 and 255. The color value ({}) is invalid!"),
-            invalid_pen_size_error: String::from("The turtle can't set it's pen's size to {}!"),
-            invalid_variable_error: String::from("The variable, named \"{}\" is not found!"),
-            invalid_loop_start_error: String::from("The specified start of the loop ({}) is not a valid number!"),
-            invalid_loop_end_error: String::from("The specified end of the loop ({}) is not a valid number!"),
-            invalid_loop_interval_error: String::from("The specified start of the loop ({}) needs to be less or equal to it's end ({})!"),
-            invalid_expression: String::from("The specified input ({}) can't be evaluated!"),
+            var_immutable_error: String::from("You can't update the \"{name}\" variable!"),
+            invalid_distance_error: String::from("The turtle can't travel the specified distance ({distance})!"),
+            invalid_angle_error: String::from("The turtle can't rotate the specified angle ({angle})!"),
+            fence_error: String::from("The turtle can't leave the canvas, it has stopped at the fence!"),
+            invalid_color_value_error: String::from("The specified color value ({value}) can't be specified! The color value intervals need to be between 0 and 255."),
+            invalid_color_interval_error: String::from("The color values can be between 0 and 255. The color value ({value}) is invalid!"),
+            invalid_pen_size_error: String::from("The turtle can't set it's pen's size to {size}!"),
+            invalid_pen_dash_error: String::from("The length of the pen's dashes and gaps can't be {length}!"),
+            invalid_line_cap_error: String::from("The \"{value}\" line cap is invalid! The line caps can be: butt, round, square."),
+            invalid_line_join_error: String::from("The \"{value}\" line join is invalid! The line joins can be: miter, round, bevel."),
+            invalid_variable_error: String::from("The variable, named \"{name}\" is not found!"),
+            invalid_loop_start_error: String::from("The specified start of the loop ({value}) is not a valid number!"),
+            invalid_loop_end_error: String::from("The specified end of the loop ({value}) is not a valid number!"),
+            invalid_loop_interval_error: String::from("The loop can't get from it's start ({start}) to it's end ({end}) with the specified step!"),
+            invalid_loop_step_error: String::from("The specified step of the loop ({value}) has to be a valid number other than 0!"),
+            invalid_loop_count_error: String::from("The loop can't be repeated {count} {count|time|times}! The number of repetitions has to be a non-negative whole number!"),
+            loop_control_error: String::from("The \"{command}\" command can only be used inside of a loop!"),
+            missing_block_error: String::from("The \"{name}\" procedure has no command block!"),
+            invalid_procedure_name_error: String::from("A procedure can't be named \"{name}\"!"),
+            wrong_argument_count_error: String::from("The \"{name}\" procedure or function needs {count} {count|argument|arguments}!"),
+            nesting_limit_error: String::from("The \"{command}\" command is nested too deep in the blocks and the procedure calls!"),
+            expected_number_error: String::from("The value of \"{value}\" needs to be a number!"),
+            unterminated_string_error: String::from("The text is not closed with a quotation mark!"),
+            unknown_function_error: String::from("The \"{name}\" function doesn't exist!"),
+            invalid_label_size_error: String::from("The size of the label can't be {size}!"),
+            invalid_index_error: String::from("The index \"{index}\" is not in the list or the text (its length is {length})!"),
+            expected_list_error: String::from("The value of \"{value}\" needs to be a list!"),
+            invalid_random_range_error: String::from("A random value can't be chosen from \"{value}\"!"),
+            invalid_seed_error: String::from("The seed of the random numbers has to be a whole number, not {value}!"),
+            expression_nesting_limit_error: String::from("The expression is nested too deep in the parentheses and the brackets!"),
+            range_length_error: String::from("The range({arguments}) list would have more than {max} numbers!"),
+            step_limit_error: String::from("The program was stopped after {max} loop iterations and procedure calls, \"{command}\" is repeated too many times!"),
+            value_size_error: String::from("The value would have more than {max} elements and characters!"),
+            invalid_expression: String::from("The specified input ({expression}) can't be evaluated!"),
+            unknown_command_error: String::from("The command, named \"{name}\" is not found! Type help to list the commands."),
+            missing_argument_error: String::from("The \"{command}\" command is missing some of its arguments!"),
+            unbalanced_brackets_error: String::from("The \"{bracket}\" bracket is not opened or closed properly!"),
+            unexpected_token_error: String::from("\"{token}\" is not expected here! The commands need to be separated by \";\" or line breaks."),
+            unterminated_comment_error: String::from("The comment is not closed by \"*/\"!"),
+            ambiguous_keyword_error: String::from("The \"{alias}\" keyword of the language means more than one command: {names}"),
+            unused_variable_warning: String::from("The \"{name}\" variable is never used!"),
+            shadowed_loop_variable_warning: String::from("The \"{name}\" loop variable hides a variable of the outer loop or procedure with the same name!"),
+            unreachable_code_warning: String::from("This command is never executed, because \"{command}\" leaves the block before it!"),
+            loop_variable_write_warning: String::from("The \"{name}\" loop variable can't be changed inside of its loop!"),
+            cli_invalid_seed_error: String::from("The seed has to be a whole number, not \"{value}\"!"),
+            cli_missing_value_error: String::from("The value of the {option} option is missing!"),
+            cli_unknown_argument_error: String::from("Unknown argument: \"{argument}\""),
+            cli_option_without_command_error: String::from("The {option} option can only be used with {command}!"),
+            cli_missing_files_error: String::from("The paths of the files are missing!"),
+            cli_unknown_language_error: String::from("Unknown language: \"{language}\""),
+            cli_read_error: String::from("{file}: the file could not be read: {error}"),
+            cli_write_error: String::from("{file}: the file could not be written: {error}"),
+            cli_locale_load_error: String::from("{file}: the file could not be loaded: {error}"),
+            cli_locale_ok: String::from("{file}: OK"),
+            cli_missing_key: String::from("missing: {key}"),
+            cli_extra_key: String::from("extra: {key}"),
+            cli_different_placeholders: String::from("placeholders: {key} has [{translated}] instead of [{expected}]"),
+            cli_invalid_placeholders: String::from("placeholders: {key} has a brace, which isn't closed or has no name, instead of [{expected}]"),
+            cli_not_formatted: String::from("{file}: not formatted"),
+            cli_usage: String::from(
+                "\
+Usage:
+  rugged_turtle [--seed <number>]
+      starts the application
+  rugged_turtle run <script> [--seed <number>] [--svg <file>] [--language <id>]
+      executes the script without a window, prints the lines of the console
+      and writes the drawing to the SVG file
+  rugged_turtle locale-check <file>... [--language <id>]
+      lists the missing and extra keys of the locale files, and the messages,
+      which have other {placeholders} than the English ones
+  rugged_turtle fmt <script>... [--language <id>] [--normalize] [--check]
+      formats the scripts (one statement in every line, indented blocks),
+      and prints the warnings of the linter
+
+  --language <id>  the language of the messages and the keywords (e.g. HU), it's English
+                   by default (the English keywords can always be used)
+  --normalize      replaces the aliases of the keywords with one alias of the language,
+                   e.g. \"e(10)\" -> \"forward(10)\"
+  --check          only lists the scripts, which aren't formatted, without changing them
+
+  --seed <number>  the random numbers start from this seed (like seed(<number>)),
+                   so the drawings with random numbers can be repeated",
+            ),
+            keywords: Keywords::default(),
+            documentation: Manual::default(),
         }
     }
 }
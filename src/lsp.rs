@@ -0,0 +1,360 @@
+// The language server of the turtle language (rugged_turtle-lsp), the code editors talk to it
+// with the Language Server Protocol over the standard input and output
+// The language of the keywords and the messages can be chosen by the "language" initialization option
+// (the language_id of a locale file, e.g. {"language": "HU"}), it's English by default
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::{self, BufRead, Write},
+};
+
+use serde_json::{json, Value};
+
+use crate::{
+    analysis::{check_script, lint_script, symbols, SymbolKind},
+    documentation::Manual,
+    error::Span,
+    formatting::format_script,
+    keywords::{KeywordTable, Keywords},
+    locale::{import_locales, Locale},
+    parsing::{parse, tokenize, Token, TokenKind},
+};
+
+// The error codes of JSON-RPC
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+
+// The severities of the diagnostics
+const ERROR_SEVERITY: u8 = 1;
+const WARNING_SEVERITY: u8 = 2;
+
+// The LSP kinds of the completion items
+const FUNCTION_ITEM: u8 = 3;
+const VARIABLE_ITEM: u8 = 6;
+const KEYWORD_ITEM: u8 = 14;
+const CONSTANT_ITEM: u8 = 21;
+
+// Reads a message of the client, it's None at the end of the input
+// e.g. "Content-Length: 52\r\n\r\n{"jsonrpc": "2.0", "method": "initialized", ...}"
+// The messages, which aren't valid JSON, are read as null
+pub fn read_message(input: &mut impl BufRead) -> io::Result<Option<Value>> {
+    let mut length: Option<usize> = None;
+    loop {
+        let mut header = String::new();
+        if input.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() && length.is_some() {
+            break;
+        }
+        if let Some(value) = header.strip_prefix("Content-Length:") {
+            length = value.trim().parse().ok();
+        }
+    }
+    let mut content = vec![0; length.unwrap_or_default()];
+    input.read_exact(&mut content)?;
+    Ok(Some(
+        serde_json::from_slice(&content).unwrap_or(Value::Null),
+    ))
+}
+
+pub fn write_message(output: &mut impl Write, message: &Value) -> io::Result<()> {
+    let content = message.to_string();
+    write!(
+        output,
+        "Content-Length: {}\r\n\r\n{}",
+        content.len(),
+        content
+    )?;
+    output.flush()
+}
+
+// Answers the messages of the client until it exits, and returns the exit code of the server
+// (0 - the client asked the server to shut down before it exited, 1 - otherwise)
+pub fn serve(mut input: impl BufRead, mut output: impl Write) -> i32 {
+    let mut server = Server::default();
+    loop {
+        let message = match read_message(&mut input) {
+            Ok(Some(message)) => message,
+            Ok(None) => return server.exit_code().unwrap_or(1),
+            Err(error) => {
+                eprintln!("The message could not be read: {}", error);
+                return 1;
+            }
+        };
+        for reply in server.handle(&message) {
+            if let Err(error) = write_message(&mut output, &reply) {
+                eprintln!("The message could not be written: {}", error);
+                return 1;
+            }
+        }
+        if let Some(exit_code) = server.exit_code() {
+            return exit_code;
+        }
+    }
+}
+
+// The position in the document, the LSP counts the lines from 0 and the characters in UTF-16 code units
+fn position(text: &str, offset: usize) -> Value {
+    let before = &text[..offset.min(text.len())];
+    let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+    json!({
+        "line": before.matches('\n').count(),
+        "character": before[line_start..].chars().map(char::len_utf16).sum::<usize>(),
+    })
+}
+
+// The byte offset of the LSP position in the document
+fn offset(text: &str, position: &Value) -> usize {
+    let line = position["line"].as_u64().unwrap_or_default() as usize;
+    let character = position["character"].as_u64().unwrap_or_default() as usize;
+    let line_start = match line {
+        0 => 0,
+        line => text
+            .match_indices('\n')
+            .nth(line - 1)
+            .map_or(text.len(), |(index, _)| index + 1),
+    };
+    let mut units = 0;
+    for (index, char) in text[line_start..].char_indices() {
+        if units >= character || char == '\n' {
+            return line_start + index;
+        }
+        units += char.len_utf16();
+    }
+    text.len()
+}
+
+fn range(text: &str, span: Span) -> Value {
+    json!({"start": position(text, span.start), "end": position(text, span.end)})
+}
+
+// The word (e.g. a command or a variable) at the LSP position
+fn word_at(text: &str, position: &Value) -> Option<Token> {
+    let offset = offset(text, position);
+    tokenize(text).into_iter().find(|token| {
+        token.kind == TokenKind::Word && token.span.start <= offset && offset <= token.span.end
+    })
+}
+
+// The documents are kept in memory while they are open in the editor
+#[derive(Default)]
+pub struct Server {
+    documents: HashMap<String, String>,
+    locale: Locale,
+    keywords: KeywordTable,
+    shutdown: bool,
+    exited: bool,
+}
+
+impl Server {
+    // The exit code of the server, if the client asked it to exit
+    pub fn exit_code(&self) -> Option<i32> {
+        self.exited.then_some(if self.shutdown { 0 } else { 1 })
+    }
+    // Returns the responses and the notifications for the client
+    pub fn handle(&mut self, message: &Value) -> Vec<Value> {
+        if !message.is_object() {
+            return vec![json!({
+                "jsonrpc": "2.0",
+                "id": null,
+                "error": {"code": PARSE_ERROR, "message": "The message is not a JSON object"},
+            })];
+        }
+        let params = &message["params"];
+        match (message["method"].as_str(), message.get("id")) {
+            (Some(method), Some(id)) => vec![match self.request(method, params) {
+                Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+                Err(error) => json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": {"code": METHOD_NOT_FOUND, "message": error},
+                }),
+            }],
+            (Some(method), None) => self.notification(method, params),
+            // The responses of the client aren't needed
+            (None, _) => vec![],
+        }
+    }
+    fn request(&mut self, method: &str, params: &Value) -> Result<Value, String> {
+        match method {
+            "initialize" => return Ok(self.initialize(params)),
+            "shutdown" => {
+                self.shutdown = true;
+                return Ok(Value::Null);
+            }
+            _ => {}
+        }
+        let document = params["textDocument"]["uri"]
+            .as_str()
+            .and_then(|uri| self.documents.get(uri).map(|text| (uri, text.as_str())));
+        let result = match (method, document) {
+            ("textDocument/completion", Some((_, text))) => self.completion(text),
+            ("textDocument/hover", Some((_, text))) => self.hover(text, &params["position"]),
+            ("textDocument/definition", Some((uri, text))) => {
+                self.definition(uri, text, &params["position"])
+            }
+            ("textDocument/formatting", Some((_, text))) => formatting(text),
+            // The documents, which aren't open, have no information
+            (
+                "textDocument/completion"
+                | "textDocument/hover"
+                | "textDocument/definition"
+                | "textDocument/formatting",
+                None,
+            ) => Value::Null,
+            _ => return Err(format!("Unknown method: {}", method)),
+        };
+        Ok(result)
+    }
+    fn notification(&mut self, method: &str, params: &Value) -> Vec<Value> {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+        let text = match method {
+            "exit" => {
+                self.exited = true;
+                return vec![];
+            }
+            "textDocument/didOpen" => params["textDocument"]["text"].as_str(),
+            // The whole document is sent with every change
+            "textDocument/didChange" => params["contentChanges"]
+                .as_array()
+                .and_then(|changes| changes.last())
+                .and_then(|change| change["text"].as_str()),
+            "textDocument/didClose" => {
+                self.documents.remove(uri);
+                return vec![diagnostics(uri, vec![])];
+            }
+            _ => return vec![],
+        };
+        let Some(text) = text else {
+            return vec![];
+        };
+        self.documents.insert(uri.to_string(), text.to_string());
+        let errors = check_script(text, &self.keywords).into_iter().map(|error| {
+            json!({
+                "range": range(text, error.span),
+                "severity": ERROR_SEVERITY,
+                "code": format!("E{:03}", error.kind.code()),
+                "source": "rugged_turtle",
+                "message": error.text(&self.locale),
+            })
+        });
+        let warnings = lint_script(text, &self.keywords)
+            .into_iter()
+            .map(|warning| {
+                json!({
+                    "range": range(text, warning.span),
+                    "severity": WARNING_SEVERITY,
+                    "code": format!("W{:03}", warning.kind.code()),
+                    "source": "rugged_turtle",
+                    "message": warning.text(&self.locale),
+                })
+            });
+        vec![diagnostics(uri, errors.chain(warnings).collect())]
+    }
+    fn initialize(&mut self, params: &Value) -> Value {
+        let language = params["initializationOptions"]["language"].as_str();
+        if let Some(language) = language {
+            let (locales, _) = import_locales();
+            if let Some(locale) = locales
+                .into_iter()
+                .find(|locale| locale.language_id.eq_ignore_ascii_case(language))
+            {
+                // The keywords of the language and the English keywords can be used (like in the application)
+                self.keywords = KeywordTable::new(&[&locale.keywords, &Keywords::default()])
+                    .documented(&[&locale.documentation, &Manual::default()]);
+                self.locale = locale;
+            }
+        }
+        json!({
+            "capabilities": {
+                // The whole document is sent with every change
+                "textDocumentSync": 1,
+                "completionProvider": {},
+                "hoverProvider": true,
+                "definitionProvider": true,
+                "documentFormattingProvider": true,
+            },
+            "serverInfo": {"name": "rugged_turtle-lsp", "version": env!("CARGO_PKG_VERSION")},
+        })
+    }
+    // The aliases of the commands, the functions and the values,
+    // and the procedures and the variables of the document
+    fn completion(&self, text: &str) -> Value {
+        let mut items: BTreeMap<String, Value> = BTreeMap::new();
+        for alias in self.keywords.aliases() {
+            let kind = match self.keywords.value(alias) {
+                Some(_) => CONSTANT_ITEM,
+                None => KEYWORD_ITEM,
+            };
+            let detail = self
+                .keywords
+                .documentation(alias)
+                .map(|documentation| documentation.summary());
+            items.insert(
+                alias.to_string(),
+                json!({"label": alias, "kind": kind, "detail": detail}),
+            );
+        }
+        let statements = parse(&tokenize(text)).unwrap_or_default();
+        for symbol in symbols(&statements, &self.keywords) {
+            let kind = match symbol.kind {
+                SymbolKind::Procedure => FUNCTION_ITEM,
+                SymbolKind::Variable => VARIABLE_ITEM,
+            };
+            items
+                .entry(symbol.name.text.clone())
+                .or_insert_with(|| json!({"label": symbol.name.text, "kind": kind}));
+        }
+        Value::Array(items.into_values().collect())
+    }
+    // The documentation of the command or the function at the position
+    fn hover(&self, text: &str, position: &Value) -> Value {
+        let Some(word) = word_at(text, position) else {
+            return Value::Null;
+        };
+        match (
+            self.keywords.documentation(&word.text),
+            self.keywords.manual(),
+        ) {
+            (Some(documentation), Some(manual)) => json!({
+                "contents": {"kind": "plaintext", "value": documentation.lines(manual).join("\n")},
+                "range": range(text, word.span),
+            }),
+            _ => Value::Null,
+        }
+    }
+    // The first definition of the procedure or the variable at the position
+    fn definition(&self, uri: &str, text: &str, position: &Value) -> Value {
+        let Some(word) = word_at(text, position) else {
+            return Value::Null;
+        };
+        let statements = parse(&tokenize(text)).unwrap_or_default();
+        symbols(&statements, &self.keywords)
+            .into_iter()
+            .find(|symbol| symbol.name.text == word.text)
+            .map_or(
+                Value::Null,
+                |symbol| json!({"uri": uri, "range": range(text, symbol.name.span)}),
+            )
+    }
+}
+
+fn diagnostics(uri: &str, diagnostics: Vec<Value>) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/publishDiagnostics",
+        "params": {"uri": uri, "diagnostics": diagnostics},
+    })
+}
+
+// The whole document is replaced by the formatted script (the scripts with syntax errors aren't changed)
+fn formatting(text: &str) -> Value {
+    match format_script(text, None) {
+        Ok(formatted) if formatted != text => json!([{
+            "range": {"start": position(text, 0), "end": position(text, text.len())},
+            "newText": formatted,
+        }]),
+        _ => json!([]),
+    }
+}
@@ -1,11 +1,70 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")] // hide console window on Windows in release
 
-use rugged_turtle::RuggedTurtleApp;
+use rugged_turtle::{RuggedTurtleApp, APP_NAME};
 
 // When compiling natively:
 #[cfg(not(target_arch = "wasm32"))]
 fn main() -> eframe::Result {
-    use std::sync::Arc;
+    use rugged_turtle::{
+        cli::{
+            check_locales, format_scripts, language_argument, parse_arguments, run_script,
+            select_locale, Action,
+        },
+        locale::Locale,
+    };
+    use std::{process, sync::Arc};
+
+    // The release build has no console on Windows, so the commands print their messages
+    // to the console of the terminal, which started them
+    #[cfg(all(windows, not(debug_assertions)))]
+    fn attach_console() {
+        #[link(name = "kernel32")]
+        extern "system" {
+            fn AttachConsole(process_id: u32) -> i32;
+        }
+        const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+        unsafe {
+            AttachConsole(ATTACH_PARENT_PROCESS);
+        }
+    }
+    #[cfg(not(all(windows, not(debug_assertions))))]
+    fn attach_console() {}
+
+    let arguments: Vec<String> = std::env::args().skip(1).collect();
+    // The messages of the commands are printed in the language of the "--language" option
+    let locales = || {
+        attach_console();
+        select_locale(language_argument(&arguments)).unwrap_or_else(|error| {
+            eprintln!("{}", error.message(&Locale::default()));
+            process::exit(2);
+        })
+    };
+    match parse_arguments(arguments.clone()) {
+        Ok(Action::App) => {}
+        Ok(Action::Run { script, svg }) => {
+            process::exit(run_script(&script, svg.as_deref(), &locales().0))
+        }
+        Ok(Action::LocaleCheck { files }) => process::exit(check_locales(&files, &locales().0)),
+        Ok(Action::Format {
+            scripts,
+            normalize,
+            check,
+        }) => {
+            let (locale, other_locales) = locales();
+            process::exit(format_scripts(
+                &scripts,
+                normalize,
+                check,
+                &locale,
+                &other_locales,
+            ))
+        }
+        Err(error) => {
+            let (locale, _) = locales();
+            eprintln!("{}\n\n{}", error.message(&locale), locale.cli_usage);
+            process::exit(2);
+        }
+    }
 
     env_logger::init(); // Log to stderr (if you run with `RUST_LOG=debug`).
     let icon = eframe::icon_data::from_png_bytes(include_bytes!("assets/rugged_turtle.png"))
@@ -19,7 +78,7 @@ fn main() -> eframe::Result {
     };
     native_options.viewport.icon = Some(Arc::new(icon));
     eframe::run_native(
-        "Rugged turtle",
+        APP_NAME,
         native_options,
         Box::new(|cc| Ok(Box::new(RuggedTurtleApp::new(cc)))),
     )
@@ -0,0 +1,182 @@
+use serde::{Deserialize, Serialize};
+
+// The messages of the locale files can contain named placeholders, which are replaced by the arguments:
+// - {name} - the argument, e.g. "The \"{name}\" variable is not found!"
+// - {count|time|times} - the form of the word, which belongs to the number
+//   (the forms are chosen by the plural rule of the language)
+// - {{ and }} - the braces themselves
+// The translations can use the placeholders in any order
+
+// The plural rule of a language chooses the form of the words, which belong to a number
+#[derive(Default, Debug, PartialEq, Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum PluralRule {
+    // One form for 1, another one for the other numbers (e.g. English, German)
+    #[default]
+    OneOther,
+    // The same form for every number (e.g. Hungarian, Turkish)
+    Other,
+    // 1, 21, 31... - 2-4, 22-24... - the other numbers, 11-14 included (e.g. Ukrainian, Russian)
+    // Polish isn't one of them: only 1 is singular there, 21 takes the third form
+    OneFewMany,
+}
+
+impl PluralRule {
+    // The index of the form, which belongs to the number
+    // The fractions and the texts, which aren't numbers, get the last form
+    fn form(self, number: Option<f64>) -> usize {
+        let Some(number) = number.filter(|number| number.is_finite() && number.fract() == 0.0)
+        else {
+            return usize::MAX;
+        };
+        let number = number.abs() as u64;
+        match self {
+            PluralRule::OneOther if number == 1 => 0,
+            PluralRule::OneOther => 1,
+            PluralRule::Other => 0,
+            PluralRule::OneFewMany if number % 10 == 1 && number % 100 != 11 => 0,
+            PluralRule::OneFewMany
+                if (2..=4).contains(&(number % 10)) && !(12..=14).contains(&(number % 100)) =>
+            {
+                1
+            }
+            PluralRule::OneFewMany => 2,
+        }
+    }
+}
+
+// The value of a placeholder
+// The numbers are written with the decimal separator of the language, the texts (e.g. source code) as they are
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub enum Argument {
+    Text(String),
+    Number(f64),
+}
+
+impl From<&str> for Argument {
+    fn from(value: &str) -> Self {
+        Argument::Text(value.to_string())
+    }
+}
+
+impl From<String> for Argument {
+    fn from(value: String) -> Self {
+        Argument::Text(value)
+    }
+}
+
+impl From<f64> for Argument {
+    fn from(value: f64) -> Self {
+        Argument::Number(value)
+    }
+}
+
+impl From<usize> for Argument {
+    fn from(value: usize) -> Self {
+        Argument::Number(value as f64)
+    }
+}
+
+// A part of a message
+#[derive(Debug, PartialEq)]
+enum Part<'a> {
+    Text(String),
+    Placeholder { name: &'a str, forms: Vec<&'a str> },
+}
+
+// Splits the message into texts and placeholders, it's None if a brace isn't closed or a placeholder has no name
+fn parse(message: &str) -> Option<Vec<Part<'_>>> {
+    let mut parts: Vec<Part> = vec![];
+    let mut text = String::new();
+    let mut rest = message;
+    while let Some(index) = rest.find(['{', '}']) {
+        text.push_str(&rest[..index]);
+        let brace = &rest[index..index + 1];
+        rest = &rest[index + 1..];
+        // {{ or }}
+        if let Some(after) = rest.strip_prefix(brace) {
+            text.push_str(brace);
+            rest = after;
+            continue;
+        }
+        let end = rest.find(['{', '}'])?;
+        if brace == "}" || &rest[end..end + 1] == "{" {
+            return None;
+        }
+        let mut fields = rest[..end].split('|');
+        let name = fields.next().unwrap_or_default().trim();
+        if name.is_empty() {
+            return None;
+        }
+        parts.push(Part::Text(std::mem::take(&mut text)));
+        parts.push(Part::Placeholder {
+            name,
+            forms: fields.collect(),
+        });
+        rest = &rest[end + 1..];
+    }
+    text.push_str(rest);
+    parts.push(Part::Text(text));
+    Some(parts)
+}
+
+// The names of the placeholders in the message (sorted, without repetitions),
+// it's None if the message isn't valid
+pub fn placeholders(message: &str) -> Option<Vec<String>> {
+    let mut names: Vec<String> = parse(message)?
+        .into_iter()
+        .filter_map(|part| match part {
+            Part::Placeholder { name, .. } => Some(name.to_string()),
+            Part::Text(_) => None,
+        })
+        .collect();
+    names.sort();
+    names.dedup();
+    Some(names)
+}
+
+// Replaces the placeholders of the message by the arguments
+// e.g. "The loop can't be repeated {count} {count|time|times}!" with count = 1
+//      -> "The loop can't be repeated 1 time!"
+// The placeholders without arguments are kept, and the invalid messages are returned as they are
+pub fn format_message(
+    message: &str,
+    arguments: &[(&str, Argument)],
+    decimal_separator: &str,
+    plural_rule: PluralRule,
+) -> String {
+    let Some(parts) = parse(message) else {
+        return message.to_string();
+    };
+    let mut text = String::new();
+    for part in parts {
+        let (name, forms) = match part {
+            Part::Text(value) => {
+                text.push_str(&value);
+                continue;
+            }
+            Part::Placeholder { name, forms } => (name, forms),
+        };
+        let Some((_, argument)) = arguments.iter().find(|(argument, _)| *argument == name) else {
+            text.push('{');
+            text.push_str(&[vec![name], forms].concat().join("|"));
+            text.push('}');
+            continue;
+        };
+        match (argument, forms.last()) {
+            (Argument::Text(value), None) => text.push_str(value),
+            (Argument::Number(value), None) => {
+                text.push_str(&value.to_string().replace('.', decimal_separator))
+            }
+            (argument, Some(last)) => {
+                let number = match argument {
+                    Argument::Text(value) => value.trim().parse().ok(),
+                    Argument::Number(value) => Some(*value),
+                };
+                let form = forms.get(plural_rule.form(number)).unwrap_or(last);
+                text.push_str(form);
+            }
+        }
+    }
+    text
+}
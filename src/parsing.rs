@@ -1,7 +1,450 @@
-// Removes whitespace characters from the string
-pub fn trim_whitespace(s: &str) -> String {
-    let mut new_str: Vec<&str> = s.split_whitespace().collect();
-    new_str.retain(|&x| !x.is_empty());
+use serde::{Deserialize, Serialize};
 
-    new_str.join("").to_string()
+use crate::error::{ErrorKind, Span, TurtleError};
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Copy)]
+pub enum TokenKind {
+    // Names of the commands and the variables
+    Word,
+    Number,
+    // Text between quotation marks, e.g. "x = {x}"
+    String,
+    // + - * / : % ^
+    Operator,
+    OpenParenthesis,
+    CloseParenthesis,
+    OpenBrace,
+    CloseBrace,
+    // "[" and "]" of the lists and the indexing, e.g. [1, 2, 3], points[0]
+    OpenBracket,
+    CloseBracket,
+    Comma,
+    Equals,
+    // ";" or a line break
+    Separator,
+    // "# ...", "// ..." and "/* ... */"
+    Comment,
+    // Any other character
+    Unknown,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub text: String,
+    pub span: Span,
+}
+
+// Splits up the source code to tokens, the whitespaces only separate the tokens
+// The line breaks separate the commands (like ";"), except inside of parentheses and brackets
+// e.g. input:  "forward(10 * 2) // comment"
+//      output: ["forward", "(", "10", "*", "2", ")", "// comment"]
+pub fn tokenize(source: &str) -> Vec<Token> {
+    let mut tokens: Vec<Token> = vec![];
+    let chars: Vec<(usize, char)> = source.char_indices().collect();
+    let mut index = 0_usize;
+    let mut line = 1_usize;
+    let mut column = 1_usize;
+    let mut parenthesis_depth = 0_i32;
+    while index < chars.len() {
+        let (start, char) = chars[index];
+        let next = chars.get(index + 1).map(|(_, char)| *char);
+        // The number of characters that belong to the token
+        let (kind, length) = if char == '#' || (char == '/' && next == Some('/')) {
+            (
+                TokenKind::Comment,
+                chars[index..]
+                    .iter()
+                    .take_while(|(_, char)| *char != '\n')
+                    .count(),
+            )
+        } else if char == '/' && next == Some('*') {
+            // The block comment lasts until the first "*/" (or until the end of the source code)
+            let mut length = 2;
+            while index + length < chars.len()
+                && !(chars[index + length - 1].1 == '*'
+                    && chars[index + length].1 == '/'
+                    && length > 2)
+            {
+                length += 1;
+            }
+            (TokenKind::Comment, (length + 1).min(chars.len() - index))
+        } else if char == '"' {
+            // The string lasts until the closing quotation mark (or until the end of the line)
+            let mut length = 1;
+            while let Some((_, next)) = chars.get(index + length) {
+                match next {
+                    '\n' => break,
+                    '\\' => length += 2,
+                    '"' => {
+                        length += 1;
+                        break;
+                    }
+                    _ => length += 1,
+                }
+            }
+            (TokenKind::String, length.min(chars.len() - index))
+        } else if (char == '\n' && parenthesis_depth <= 0) || char == ';' {
+            (TokenKind::Separator, 1)
+        } else if char.is_whitespace() {
+            (TokenKind::Unknown, 0)
+        } else if char.is_ascii_digit() || char == '.' {
+            (
+                TokenKind::Number,
+                chars[index..]
+                    .iter()
+                    .take_while(|(_, char)| char.is_alphanumeric() || *char == '.')
+                    .count(),
+            )
+        } else if char.is_alphabetic() || char == '_' {
+            (
+                TokenKind::Word,
+                chars[index..]
+                    .iter()
+                    .take_while(|(_, char)| char.is_alphanumeric() || *char == '_')
+                    .count(),
+            )
+        } else {
+            let kind = match char {
+                '+' | '-' | '*' | '/' | ':' | '%' | '^' => TokenKind::Operator,
+                '(' => TokenKind::OpenParenthesis,
+                ')' => TokenKind::CloseParenthesis,
+                '{' => TokenKind::OpenBrace,
+                '}' => TokenKind::CloseBrace,
+                '[' => TokenKind::OpenBracket,
+                ']' => TokenKind::CloseBracket,
+                ',' => TokenKind::Comma,
+                '=' => TokenKind::Equals,
+                _ => TokenKind::Unknown,
+            };
+            (kind, 1)
+        };
+        match kind {
+            TokenKind::OpenParenthesis | TokenKind::OpenBracket => parenthesis_depth += 1,
+            TokenKind::CloseParenthesis | TokenKind::CloseBracket => parenthesis_depth -= 1,
+            // The parentheses can't be left open in a new command
+            TokenKind::Separator | TokenKind::OpenBrace | TokenKind::CloseBrace => {
+                parenthesis_depth = 0
+            }
+            _ => {}
+        }
+        // Whitespaces aren't tokens, but they are counted in the position
+        let length = length.max(1);
+        if !(char.is_whitespace() && kind == TokenKind::Unknown) {
+            let end = chars
+                .get(index + length)
+                .map_or(source.len(), |(end, _)| *end);
+            tokens.push(Token {
+                kind,
+                text: source[start..end].to_string(),
+                span: Span {
+                    start,
+                    end,
+                    line,
+                    column,
+                },
+            });
+        }
+        for (_, char) in &chars[index..index + length] {
+            if *char == '\n' {
+                line += 1;
+                column = 1;
+            } else {
+                column += 1;
+            }
+        }
+        index += length;
+    }
+    tokens
+}
+
+// Concatenates the texts of the tokens (without the whitespaces between them)
+pub fn tokens_text(tokens: &[Token]) -> String {
+    tokens.iter().map(|token| token.text.as_str()).collect()
+}
+
+// The span from the first token to the last token
+pub fn tokens_span(tokens: &[Token]) -> Span {
+    match (tokens.first(), tokens.last()) {
+        (Some(first), Some(last)) => first.span.to(last.span),
+        _ => Span::default(),
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub enum Statement {
+    // <declaration> <name> = <value>, the declaration (e.g. "local", "const") is optional
+    Assignment {
+        declaration: Option<Token>,
+        name: Token,
+        value: Vec<Token>,
+    },
+    // <name>(<arguments>) {<block>}, the arguments and the block are optional
+    Command {
+        name: Token,
+        arguments: Vec<Vec<Token>>,
+        block: Option<Vec<Statement>>,
+        // The span of the command without its block
+        span: Span,
+    },
+    // <keyword> <name>(<parameters>) {<block>}, e.g. "to square(size) {...}"
+    // The keyword is only checked when the statement is executed
+    Procedure {
+        keyword: Token,
+        name: Token,
+        parameters: Vec<Vec<Token>>,
+        block: Option<Vec<Statement>>,
+        // The span of the definition without its block
+        span: Span,
+    },
+}
+
+// Builds up the statements from the tokens
+// e.g. input:  "for(i, 0, 4) {forward(10); right(90)}"
+//      output: [Command { name: "for", arguments: [["i"], ["0"], ["4"]], block: Some([
+//                  Command { name: "forward", arguments: [["10"]], block: None },
+//                  Command { name: "right", arguments: [["90"]], block: None }]) }]
+pub fn parse(tokens: &[Token]) -> Result<Vec<Statement>, TurtleError> {
+    let mut code: Vec<&Token> = vec![];
+    for token in tokens {
+        if token.kind != TokenKind::Comment {
+            if token.kind == TokenKind::String && !is_terminated(&token.text) {
+                return Err(error(ErrorKind::UnterminatedString, token));
+            }
+            code.push(token);
+        } else if token.text.starts_with("/*")
+            && (token.text.len() < 4 || !token.text.ends_with("*/"))
+        {
+            return Err(error(ErrorKind::UnterminatedComment, token));
+        }
+    }
+    let mut parser = Parser {
+        tokens: code,
+        position: 0,
+        depth: 0,
+    };
+    parser.parse_block(None)
+}
+
+// Checks whether the string ends with a closing quotation mark (which isn't escaped)
+fn is_terminated(string: &str) -> bool {
+    let mut chars = string.chars().skip(1);
+    while let Some(char) = chars.next() {
+        match char {
+            '\\' => {
+                chars.next();
+            }
+            '"' => return chars.next().is_none(),
+            _ => {}
+        }
+    }
+    false
+}
+
+// The blocks and the brackets can be nested this deep (the deeper nesting would overflow the stack)
+const MAX_NESTING: usize = 100;
+
+fn error(kind: ErrorKind, token: &Token) -> TurtleError {
+    TurtleError::new(kind, &token.text, token.span)
+}
+
+struct Parser<'a> {
+    tokens: Vec<&'a Token>,
+    position: usize,
+    // The number of blocks around the statement, which is being parsed
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&'a Token> {
+        self.tokens.get(self.position).copied()
+    }
+    fn peek_kind(&self) -> Option<TokenKind> {
+        self.peek().map(|token| token.kind)
+    }
+    fn advance(&mut self) -> Option<&'a Token> {
+        let token = self.peek();
+        self.position += 1;
+        token
+    }
+    // Parses the statements until the end of the block (<opening> is the "{" of the block)
+    // or until the end of the source code
+    fn parse_block(&mut self, opening: Option<&'a Token>) -> Result<Vec<Statement>, TurtleError> {
+        let mut statements: Vec<Statement> = vec![];
+        loop {
+            match self.peek() {
+                None => {
+                    return match opening {
+                        Some(opening) => Err(error(ErrorKind::UnbalancedBrackets, opening)),
+                        None => Ok(statements),
+                    }
+                }
+                Some(token) => match token.kind {
+                    TokenKind::Separator => {
+                        self.advance();
+                        continue;
+                    }
+                    TokenKind::CloseBrace if opening.is_some() => {
+                        self.advance();
+                        return Ok(statements);
+                    }
+                    TokenKind::CloseBrace => {
+                        return Err(error(ErrorKind::UnbalancedBrackets, token))
+                    }
+                    TokenKind::Word => {
+                        self.advance();
+                        statements.push(self.parse_statement(token)?);
+                    }
+                    _ => return Err(error(ErrorKind::UnexpectedToken, token)),
+                },
+            }
+            // The statements have to be separated
+            match self.peek() {
+                Some(token)
+                    if token.kind != TokenKind::Separator
+                        && token.kind != TokenKind::CloseBrace =>
+                {
+                    return Err(error(ErrorKind::UnexpectedToken, token));
+                }
+                _ => {}
+            }
+        }
+    }
+    // Parses the statement, which starts with <name>
+    fn parse_statement(&mut self, name: &'a Token) -> Result<Statement, TurtleError> {
+        if self.peek_kind() == Some(TokenKind::Equals) {
+            return self.parse_assignment(None, name);
+        }
+        // Two words after each other start a declaration or a procedure definition
+        let Some(second) = self.peek().filter(|token| token.kind == TokenKind::Word) else {
+            return self.parse_command(name);
+        };
+        self.advance();
+        if self.peek_kind() == Some(TokenKind::Equals) {
+            return self.parse_assignment(Some(name), second);
+        }
+        match self.parse_command(second)? {
+            Statement::Command {
+                name: procedure,
+                arguments,
+                block,
+                span,
+            } => Ok(Statement::Procedure {
+                keyword: name.clone(),
+                name: procedure,
+                parameters: arguments,
+                block,
+                span: name.span.to(span),
+            }),
+            statement => Ok(statement),
+        }
+    }
+    // This is where we declare the variable
+    // <var>=<value> - value can be a boolean or a number
+    fn parse_assignment(
+        &mut self,
+        declaration: Option<&'a Token>,
+        name: &'a Token,
+    ) -> Result<Statement, TurtleError> {
+        let equals = self.advance().unwrap_or(name);
+        let value = self.parse_expression()?;
+        if value.is_empty() {
+            return Err(error(ErrorKind::InvalidExpression, equals));
+        }
+        Ok(Statement::Assignment {
+            declaration: declaration.cloned(),
+            name: name.clone(),
+            value,
+        })
+    }
+    // Parses the command, which starts with <name>
+    fn parse_command(&mut self, name: &'a Token) -> Result<Statement, TurtleError> {
+        let mut span = name.span;
+        let mut arguments: Vec<Vec<Token>> = vec![];
+        if self.peek_kind() == Some(TokenKind::OpenParenthesis) {
+            let opening = self.advance().unwrap_or(name);
+            loop {
+                let argument = self.parse_expression()?;
+                match self.advance() {
+                    Some(token) if token.kind == TokenKind::Comma => {
+                        if argument.is_empty() {
+                            return Err(error(ErrorKind::InvalidExpression, token));
+                        }
+                        arguments.push(argument);
+                    }
+                    Some(token) if token.kind == TokenKind::CloseParenthesis => {
+                        // "forward()" has no arguments, but "forward(10,)" is missing one
+                        if argument.is_empty() && !arguments.is_empty() {
+                            return Err(error(ErrorKind::InvalidExpression, token));
+                        }
+                        if !argument.is_empty() {
+                            arguments.push(argument);
+                        }
+                        span = span.to(token.span);
+                        break;
+                    }
+                    _ => return Err(error(ErrorKind::UnbalancedBrackets, opening)),
+                }
+            }
+        }
+        // The command block can start in the next line
+        let mut lookahead = self.position;
+        while self
+            .tokens
+            .get(lookahead)
+            .is_some_and(|token| token.kind == TokenKind::Separator && token.text == "\n")
+        {
+            lookahead += 1;
+        }
+        let mut block: Option<Vec<Statement>> = None;
+        if let Some(opening) = self
+            .tokens
+            .get(lookahead)
+            .filter(|token| token.kind == TokenKind::OpenBrace)
+        {
+            if self.depth >= MAX_NESTING {
+                return Err(error(ErrorKind::NestingLimit, name));
+            }
+            self.position = lookahead + 1;
+            self.depth += 1;
+            block = Some(self.parse_block(Some(opening))?);
+            self.depth -= 1;
+        }
+        Ok(Statement::Command {
+            name: name.clone(),
+            arguments,
+            block,
+            span,
+        })
+    }
+    // Collects the tokens of an expression until the end of the statement or the argument
+    fn parse_expression(&mut self) -> Result<Vec<Token>, TurtleError> {
+        let mut expression: Vec<Token> = vec![];
+        let mut depth = 0;
+        while let Some(token) = self.peek() {
+            match token.kind {
+                TokenKind::OpenParenthesis | TokenKind::OpenBracket if depth >= MAX_NESTING => {
+                    return Err(error(ErrorKind::ExpressionNestingLimit, token))
+                }
+                TokenKind::OpenParenthesis | TokenKind::OpenBracket => depth += 1,
+                TokenKind::CloseParenthesis if depth == 0 => break,
+                TokenKind::CloseParenthesis | TokenKind::CloseBracket if depth > 0 => depth -= 1,
+                TokenKind::Comma if depth == 0 => break,
+                // The commas inside of the parentheses and the brackets separate
+                // the arguments of the functions and the elements of the lists
+                TokenKind::Comma => {}
+                TokenKind::Separator | TokenKind::CloseBrace => break,
+                TokenKind::OpenBrace | TokenKind::Equals | TokenKind::CloseBracket => {
+                    return Err(error(ErrorKind::UnexpectedToken, token))
+                }
+                _ => {}
+            }
+            expression.push(token.clone());
+            self.advance();
+        }
+        if depth != 0 {
+            return Err(error(ErrorKind::UnbalancedBrackets, &expression[0]));
+        }
+        Ok(expression)
+    }
 }
@@ -0,0 +1,172 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use egui::{Color32, Pos2};
+use serde::{Deserialize, Serialize};
+
+// Specifies how the pen's trail is put on the canvas
+#[derive(Default, Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Copy)]
+pub enum PenMode {
+    // The trail is painted with the pen's color
+    #[default]
+    Paint,
+    // The trail removes everything underneath it (it is painted with the background color)
+    Erase,
+    // The trail has the inverse color of the background, even where it crosses the earlier trails
+    // (it isn't an XOR of the drawing, the trails underneath it are covered, not inverted)
+    // The projects saved with the earlier name of the mode can be opened
+    #[serde(alias = "Reverse")]
+    InverseBackground,
+}
+
+// Specifies the shape of the ends of the lines
+#[derive(Default, Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Copy)]
+pub enum LineCap {
+    #[default]
+    Butt,
+    Round,
+    Square,
+}
+
+// Specifies the shape of the corners between the line segments
+#[derive(Default, Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Copy)]
+pub enum LineJoin {
+    #[default]
+    Miter,
+    Round,
+    Bevel,
+}
+
+// Dash pattern of the pen, <on> is the length of the dashes and <off> is the length of the gaps
+#[derive(Default, Debug, PartialEq, Serialize, Deserialize, Clone, Copy)]
+pub struct PenDash {
+    pub on: f32,
+    pub off: f32,
+}
+
+// The shortest dash and gap (in pixels), the shorter ones would split the lines to too many dashes
+pub const MIN_DASH_LENGTH: f32 = 0.1;
+
+// Every setting of the pen, which affects how the trail looks like
+#[derive(Default, Debug, PartialEq, Serialize, Deserialize, Clone, Copy)]
+pub struct PenStyle {
+    pub color: Color32,
+    pub width: f32,
+    pub mode: PenMode,
+    pub dash: Option<PenDash>,
+    pub cap: LineCap,
+    pub join: LineJoin,
+}
+
+// A continuous line, which is drawn with the <style>th style of the path store
+#[derive(Default, Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct Polyline {
+    pub style: usize,
+    pub points: Vec<Pos2>,
+}
+
+// Text written on the canvas by the label command, <position> is the bottom left corner of the text
+#[derive(Default, Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct Label {
+    pub position: Pos2,
+    pub text: String,
+    pub color: Color32,
+    pub size: f32,
+}
+
+// Every store gets a new revision number when it changes or when it is loaded,
+// so the revisions of different stores never match (e.g. after the reset of the turtle)
+static NEXT_REVISION: AtomicU64 = AtomicU64::new(1);
+
+fn next_revision() -> u64 {
+    NEXT_REVISION.fetch_add(1, Ordering::Relaxed)
+}
+
+// Stores the turtle's drawing
+// Every different pen style is stored only once and the lines refer to them,
+// the lines are kept in the order they were drawn (the erased and reversed lines depend on it)
+#[derive(Default, Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct PathStore {
+    pub styles: Vec<PenStyle>,
+    pub lines: Vec<Polyline>,
+    // The labels are painted over the lines
+    pub labels: Vec<Label>,
+    #[serde(skip, default = "next_revision")]
+    revision: u64,
+}
+
+// The stores are equal if they contain the same drawing (the revision only tells when it changed)
+impl PartialEq for PathStore {
+    fn eq(&self, other: &Self) -> bool {
+        self.styles == other.styles && self.lines == other.lines && self.labels == other.labels
+    }
+}
+
+impl PathStore {
+    // The revision changes every time the drawing changes
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+    pub fn style(&self, line: &Polyline) -> PenStyle {
+        self.styles.get(line.style).copied().unwrap_or_default()
+    }
+    pub fn point_count(&self) -> usize {
+        self.lines.iter().map(|line| line.points.len()).sum()
+    }
+    fn touch(&mut self) {
+        self.revision = next_revision();
+    }
+    fn style_index(&mut self, style: PenStyle) -> usize {
+        // The style of the last line is the most likely match
+        if let Some(line) = self.lines.last() {
+            if self.styles.get(line.style) == Some(&style) {
+                return line.style;
+            }
+        }
+        match self.styles.iter().position(|stored| *stored == style) {
+            Some(index) => index,
+            None => {
+                self.styles.push(style);
+                self.styles.len() - 1
+            }
+        }
+    }
+    pub fn add_label(&mut self, label: Label) {
+        self.touch();
+        self.labels.push(label);
+    }
+    // Adds a line segment to the drawing
+    // If the segment continues the last line with the same style, the line is extended,
+    // and if the new point is collinear with the last segment, the last point is moved instead
+    pub fn add_segment(&mut self, from: Pos2, to: Pos2, style: PenStyle) {
+        if from == to {
+            return;
+        }
+        self.touch();
+        let style = self.style_index(style);
+        if let Some(line) = self.lines.last_mut() {
+            if line.style == style && line.points.last() == Some(&from) {
+                let count = line.points.len();
+                if count >= 2 {
+                    let previous = line.points[count - 1] - line.points[count - 2];
+                    let next = to - from;
+                    let cross = previous.x * next.y - previous.y * next.x;
+                    if cross.abs() <= COLLINEAR_TOLERANCE * previous.length() * next.length()
+                        && previous.dot(next) > 0_f32
+                    {
+                        line.points[count - 1] = to;
+                        return;
+                    }
+                }
+                line.points.push(to);
+                return;
+            }
+        }
+        self.lines.push(Polyline {
+            style,
+            points: vec![from, to],
+        });
+    }
+}
+
+const COLLINEAR_TOLERANCE: f32 = 1e-5;
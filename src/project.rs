@@ -0,0 +1,205 @@
+use std::sync::{Arc, Mutex};
+
+use egui::{Color32, Vec2};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::turtle::Turtle;
+
+// Project files
+//
+// A project file is a JSON document, which bundles everything that is needed to see
+// exactly the same as the author of the project saw:
+// {
+//     "format": "rugged_turtle_project",   - identifies the project files
+//     "version": 2,                        - version of the project file's schema
+//     "script": "forward(100)",            - the source code in the script editor
+//     "canvas_size": [800.0, 600.0],       - size of the canvas in pixels
+//     "background": [255, 255, 255, 255],  - color of the canvas (RGBA, 0-255)
+//     "locale": "EN",                      - language_id of the selected language
+//     "turtle": { ... }                    - optional, the turtle's state with its drawing
+// }
+//
+// When the schema changes, PROJECT_VERSION is increased and a migration is added to MIGRATIONS,
+// which converts the JSON document of the previous version to the new one.
+// The new fields of the turtle don't need a migration, they get their default values.
+
+pub const PROJECT_FORMAT: &str = "rugged_turtle_project";
+pub const PROJECT_VERSION: u64 = 2;
+pub const PROJECT_EXTENSIONS: [&str; 2] = ["rturtle", "json"];
+
+// The <n>th migration converts the project from the <n + 1>th version to the <n + 2>th version
+const MIGRATIONS: [fn(&mut Value); (PROJECT_VERSION - 1) as usize] = [history_entries];
+
+// Version 2: the console's history can contain messages (with their ids and arguments) besides the texts,
+// the texts of the first version are read as they are
+fn history_entries(_document: &mut Value) {}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Project {
+    pub format: String,
+    pub version: u64,
+    pub script: String,
+    pub canvas_size: [f32; 2],
+    pub background: [u8; 4],
+    pub locale: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub turtle: Option<Turtle>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ProjectError {
+    // The file isn't a JSON document, or it doesn't match the schema
+    Invalid(String),
+    // The project was saved by a newer version of the application
+    UnsupportedVersion(u64),
+}
+
+impl Project {
+    pub fn new(
+        script: String,
+        canvas_size: Vec2,
+        background: Color32,
+        locale: String,
+        turtle: Option<Turtle>,
+    ) -> Self {
+        Self {
+            format: PROJECT_FORMAT.to_string(),
+            version: PROJECT_VERSION,
+            script,
+            canvas_size: [canvas_size.x, canvas_size.y],
+            background: background.to_srgba_unmultiplied(),
+            locale,
+            turtle,
+        }
+    }
+    pub fn canvas_size(&self) -> Vec2 {
+        Vec2::new(self.canvas_size[0], self.canvas_size[1])
+    }
+    pub fn background(&self) -> Color32 {
+        let [r, g, b, a] = self.background;
+        Color32::from_rgba_unmultiplied(r, g, b, a)
+    }
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+    // Reads the project, and migrates it to the current version of the schema
+    pub fn from_json(json: &str) -> Result<Self, ProjectError> {
+        let mut document: Value =
+            serde_json::from_str(json).map_err(|e| ProjectError::Invalid(e.to_string()))?;
+        if document.get("format").and_then(Value::as_str) != Some(PROJECT_FORMAT) {
+            return Err(ProjectError::Invalid(String::from("format")));
+        }
+        let version = match document.get("version").and_then(Value::as_u64) {
+            Some(version) if version >= 1 => version,
+            _ => return Err(ProjectError::Invalid(String::from("version"))),
+        };
+        if version > PROJECT_VERSION {
+            return Err(ProjectError::UnsupportedVersion(version));
+        }
+        for migration in &MIGRATIONS[(version - 1) as usize..] {
+            migration(&mut document);
+        }
+        document["version"] = Value::from(PROJECT_VERSION);
+        serde_json::from_value(document).map_err(|e| ProjectError::Invalid(e.to_string()))
+    }
+}
+
+// Result of a file operation, which may finish later (e.g. on the web)
+#[derive(Debug, PartialEq, Clone)]
+pub enum FileEvent {
+    Opened(String),
+    Saved,
+    Failed(String),
+}
+
+// Opens and saves the project files with the file dialogs of the platform
+#[derive(Default, Clone)]
+pub struct ProjectFiles {
+    events: Arc<Mutex<Vec<FileEvent>>>,
+}
+
+impl ProjectFiles {
+    fn push(&self, event: FileEvent) {
+        if let Ok(mut events) = self.events.lock() {
+            events.push(event);
+        }
+    }
+    // Returns the next finished file operation
+    pub fn poll(&self) -> Option<FileEvent> {
+        match self.events.lock() {
+            Ok(mut events) if !events.is_empty() => Some(events.remove(0)),
+            _ => None,
+        }
+    }
+    // Reads the file from the specified path (used where there are no file dialogs)
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn open_path(&self, path: &str) {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => self.push(FileEvent::Opened(contents)),
+            Err(e) => self.push(FileEvent::Failed(e.to_string())),
+        }
+    }
+    // Writes the file to the specified path (used where there are no file dialogs)
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn save_path(&self, path: &str, contents: String) {
+        match std::fs::write(path, contents) {
+            Ok(()) => self.push(FileEvent::Saved),
+            Err(e) => self.push(FileEvent::Failed(e.to_string())),
+        }
+    }
+    #[cfg(not(any(target_os = "android", target_arch = "wasm32")))]
+    pub fn open(&self, title: &str) {
+        // Desktop version (the file dialog blocks until the file is picked)
+        if let Some(path) = rfd::FileDialog::new()
+            .set_title(title)
+            .add_filter(PROJECT_FORMAT, &PROJECT_EXTENSIONS)
+            .pick_file()
+        {
+            self.open_path(&path.display().to_string());
+        }
+    }
+    #[cfg(not(any(target_os = "android", target_arch = "wasm32")))]
+    pub fn save(&self, title: &str, contents: String) {
+        if let Some(path) = rfd::FileDialog::new()
+            .set_title(title)
+            .add_filter(PROJECT_FORMAT, &PROJECT_EXTENSIONS)
+            .set_file_name(format!("project.{}", PROJECT_EXTENSIONS[0]))
+            .save_file()
+        {
+            self.save_path(&path.display().to_string(), contents);
+        }
+    }
+    #[cfg(target_arch = "wasm32")]
+    pub fn open(&self, title: &str) {
+        // Web version (the file is uploaded by the browser)
+        let dialog = rfd::AsyncFileDialog::new()
+            .set_title(title)
+            .add_filter(PROJECT_FORMAT, &PROJECT_EXTENSIONS);
+        let files = self.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            if let Some(file) = dialog.pick_file().await {
+                match String::from_utf8(file.read().await) {
+                    Ok(contents) => files.push(FileEvent::Opened(contents)),
+                    Err(e) => files.push(FileEvent::Failed(e.to_string())),
+                }
+            }
+        });
+    }
+    #[cfg(target_arch = "wasm32")]
+    pub fn save(&self, title: &str, contents: String) {
+        // Web version (the file is downloaded by the browser)
+        let dialog = rfd::AsyncFileDialog::new()
+            .set_title(title)
+            .set_file_name(format!("project.{}", PROJECT_EXTENSIONS[0]));
+        let files = self.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            if let Some(file) = dialog.save_file().await {
+                match file.write(contents.as_bytes()).await {
+                    Ok(()) => files.push(FileEvent::Saved),
+                    Err(e) => files.push(FileEvent::Failed(e.to_string())),
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,41 @@
+use std::sync::OnceLock;
+
+use rand::{rngs::Xoshiro256PlusPlus, RngExt, SeedableRng};
+
+// The seed given on the command line (e.g. "--seed 42"), the new turtles start from it,
+// so the random drawings can be compared with their reference outputs
+static DEFAULT_SEED: OnceLock<u64> = OnceLock::new();
+
+pub fn set_default_seed(seed: u64) {
+    let _ = DEFAULT_SEED.set(seed);
+}
+
+// Random number generator of the turtle, it gives the same numbers on every platform
+// It's only created when it's first used (the default turtle is created often),
+// and it isn't saved with the turtle, because it isn't a visible part of it
+#[derive(Default, Debug, Clone)]
+pub struct Random {
+    generator: Option<Xoshiro256PlusPlus>,
+}
+
+impl Random {
+    // The same seed gives the same random numbers, e.g. seed(42)
+    pub fn seed(&mut self, seed: u64) {
+        self.generator = Some(Xoshiro256PlusPlus::seed_from_u64(seed));
+    }
+    fn generator(&mut self) -> &mut Xoshiro256PlusPlus {
+        self.generator
+            .get_or_insert_with(|| match DEFAULT_SEED.get() {
+                Some(seed) => Xoshiro256PlusPlus::seed_from_u64(*seed),
+                None => rand::make_rng(),
+            })
+    }
+    // Whole number from <min> to <max> (both of them are included)
+    pub fn integer(&mut self, min: i64, max: i64) -> i64 {
+        self.generator().random_range(min..=max)
+    }
+    // Number from 0 (included) to 1 (excluded)
+    pub fn float(&mut self) -> f64 {
+        self.generator().random()
+    }
+}
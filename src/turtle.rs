@@ -1,26 +1,120 @@
-use egui::{ahash::HashMap, Color32, Pos2};
+use std::f64::consts::{FRAC_PI_2, TAU};
+
+use egui::{ahash::HashMap, Pos2, Rect, Vec2};
 use serde::{Deserialize, Serialize};
 
-use crate::commands::Variable;
+use crate::{
+    commands::{Procedure, Variable},
+    history::{HistoryEntry, MessageId},
+    keywords::KeywordTable,
+    path::{PathStore, PenStyle},
+    random::Random,
+};
+
+// Specifies what happens when the turtle reaches the edge of the canvas
+#[derive(Default, Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Copy)]
+pub enum BoundaryMode {
+    // The turtle can leave the canvas
+    #[default]
+    Window,
+    // The turtle reappears on the opposite side of the canvas
+    Wrap,
+    // The turtle can't leave the canvas
+    Fence,
+}
+
+// The unit of the angles in the commands (e.g. right(90)) and in the functions (e.g. sin(90))
+#[derive(Default, Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Copy)]
+pub enum AngleUnit {
+    #[default]
+    Degrees,
+    Radians,
+}
+
+impl AngleUnit {
+    pub fn to_radians(self, angle: f64) -> f64 {
+        match self {
+            AngleUnit::Degrees => angle.to_radians(),
+            AngleUnit::Radians => angle,
+        }
+    }
+    pub fn from_radians(self, radians: f64) -> f64 {
+        match self {
+            AngleUnit::Degrees => radians.to_degrees(),
+            AngleUnit::Radians => radians,
+        }
+    }
+}
 
-#[derive(Default, Debug, PartialEq, Serialize, Deserialize, Clone)]
+// Specifies how the heading of the turtle is measured
+#[derive(Default, Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Copy)]
+pub enum Orientation {
+    // 0 is north (up), and the heading grows clockwise (like in Logo and on a compass)
+    #[default]
+    Compass,
+    // 0 is east (right), and the heading grows counter-clockwise (like in mathematics)
+    Math,
+}
+
+#[derive(Default, Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
 pub struct Turtle {
     pub position: Pos2,
     pub width: f32,
     pub height: f32,
-    pub angle: f32,
+    // The direction of the turtle in radians, counter-clockwise from north (between 0 and 2*PI)
+    pub angle: f64,
+    pub angle_unit: AngleUnit,
+    pub orientation: Orientation,
     pub icon_path: String,
-    pub path: Vec<Vec<Pos2>>,
-    pub pencolor: Color32,
-    pub path_color: Vec<Color32>,
-    pub penwidth: f32,
-    pub path_width: Vec<f32>,
+    pub pen: PenStyle,
     pub pen_up: bool,
+    pub path: PathStore,
+    pub canvas: Option<Rect>,
+    pub boundary_mode: BoundaryMode,
+    // The global variables
     pub variables: HashMap<String, Variable>,
-    pub command_history: Vec<String>,
+    // Variables of the blocks and the procedure that are being executed (the innermost is the last)
+    #[serde(skip)]
+    pub scopes: Vec<HashMap<String, Variable>>,
+    // The procedures defined by the user
+    pub procedures: HashMap<String, Procedure>,
+    // The number of blocks (and procedure calls) that are being executed inside of each other
+    #[serde(skip)]
+    pub depth: usize,
+    // The number of blocks (loop iterations and procedure calls) executed by the current program
+    #[serde(skip)]
+    pub steps: usize,
+    // Generator of the random numbers (e.g. random(10)), seed(<number>) makes it repeatable
+    #[serde(skip)]
+    pub random: Random,
+    // The aliases of the commands in the language of the application
+    #[serde(skip)]
+    pub keywords: KeywordTable,
+    // The lines of the console (the messages are displayed in the selected language)
+    pub command_history: Vec<HistoryEntry>,
 }
 
+// Drawings with more points than this are not saved with the session
+pub const MAX_SESSION_POINTS: usize = 100_000;
+
 impl Turtle {
+    // Saves the turtle with the session, very large drawings are left out of it,
+    // but the rest of the turtle is saved (and the console of the next session tells why)
+    pub fn save_session<R>(&mut self, save: impl FnOnce(&Turtle) -> R) -> R {
+        if self.path.point_count() <= MAX_SESSION_POINTS {
+            return save(self);
+        }
+        let path = std::mem::take(&mut self.path);
+        self.command_history.push(HistoryEntry::message(
+            MessageId::SessionDrawingTooLarge,
+            &[],
+        ));
+        let result = save(self);
+        self.command_history.pop();
+        self.path = path;
+        result
+    }
     pub fn set_position(&mut self, x: f32, y: f32) {
         self.position = Pos2::new(x, y);
     }
@@ -28,4 +122,158 @@ impl Turtle {
         self.width = width;
         self.height = height;
     }
+    // Looks up the variable from the innermost scope to the global variables
+    pub fn get_variable(&self, name: &str) -> Option<&Variable> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name))
+            .or_else(|| self.variables.get(name))
+    }
+    // The scope of the block that is being executed (the global variables outside of the blocks)
+    pub fn local_scope(&mut self) -> &mut HashMap<String, Variable> {
+        match self.scopes.last_mut() {
+            Some(scope) => scope,
+            None => &mut self.variables,
+        }
+    }
+    // The scope, where the variable is declared (or the local scope if it isn't declared yet)
+    pub fn variable_scope(&mut self, name: &str) -> &mut HashMap<String, Variable> {
+        match self
+            .scopes
+            .iter()
+            .rposition(|scope| scope.contains_key(name))
+        {
+            Some(index) => &mut self.scopes[index],
+            None if self.variables.contains_key(name) => &mut self.variables,
+            None => self.local_scope(),
+        }
+    }
+    // Moves the turtle to the specified point, it leaves a trail if the pen is down
+    fn move_to(&mut self, point: Pos2) {
+        if !self.pen_up {
+            self.path.add_segment(self.position, point, self.pen);
+        }
+        self.position = point;
+    }
+    // Turns the turtle to the left (counter-clockwise) with the angle given in the angle unit,
+    // the negative angles turn it to the right
+    pub fn rotate(&mut self, angle: f64) {
+        self.angle = (self.angle + self.angle_unit.to_radians(angle)).rem_euclid(TAU);
+    }
+    // The direction of the turtle in the angle unit and the orientation (between 0 and a full turn)
+    // e.g. after right(90) it's 90 with compass orientation and 0 with math orientation
+    pub fn heading(&self) -> f64 {
+        let radians = match self.orientation {
+            Orientation::Compass => TAU - self.angle,
+            Orientation::Math => self.angle + FRAC_PI_2,
+        };
+        let full_turn = self.angle_unit.from_radians(TAU);
+        // The rounding errors of the radians are removed (e.g. 89.99999999999999 -> 90)
+        let heading = (self.angle_unit.from_radians(radians.rem_euclid(TAU)) * 1e9).round() / 1e9;
+        if heading >= full_turn {
+            heading - full_turn
+        } else {
+            heading
+        }
+    }
+    // Turns the turtle to the direction given in the angle unit and the orientation
+    pub fn set_heading(&mut self, heading: f64) {
+        let radians = self.angle_unit.to_radians(heading);
+        self.angle = match self.orientation {
+            Orientation::Compass => -radians,
+            Orientation::Math => radians - FRAC_PI_2,
+        }
+        .rem_euclid(TAU);
+    }
+    // Moves the turtle forward with the specified distance
+    // Returns false, if the fence stopped the turtle at the edge of the canvas
+    pub fn forward(&mut self, distance: f32) -> bool {
+        let mut offset = Vec2::new(-self.angle.sin() as f32, -self.angle.cos() as f32) * distance;
+        let canvas = match self.canvas {
+            Some(canvas) if canvas.is_positive() => canvas,
+            _ => {
+                self.move_to(self.position + offset);
+                return true;
+            }
+        };
+        match self.boundary_mode {
+            BoundaryMode::Window => {
+                self.move_to(self.position + offset);
+                true
+            }
+            BoundaryMode::Fence => {
+                if canvas.contains(self.position + offset) {
+                    self.move_to(self.position + offset);
+                    return true;
+                }
+                // The turtle goes until the edge of the canvas, if it's on the canvas
+                if canvas.contains(self.position) {
+                    let exit = exit_ratio(canvas, self.position, offset).0;
+                    self.move_to(self.position + offset * exit);
+                }
+                false
+            }
+            BoundaryMode::Wrap => {
+                // The turtle is moved back to the canvas, if it was outside of it
+                if !canvas.contains(self.position) {
+                    self.position = Pos2::new(
+                        canvas.min.x + (self.position.x - canvas.min.x).rem_euclid(canvas.width()),
+                        canvas.min.y + (self.position.y - canvas.min.y).rem_euclid(canvas.height()),
+                    );
+                }
+                // Limiting the number of wraps for extremely long distances
+                for _ in 0..MAX_WRAPS {
+                    if canvas.contains(self.position + offset) {
+                        self.move_to(self.position + offset);
+                        break;
+                    }
+                    let (exit, crosses_x, crosses_y) = exit_ratio(canvas, self.position, offset);
+                    let mut edge = self.position + offset * exit;
+                    self.move_to(edge);
+                    // The turtle jumps to the opposite edge, so the path is split there
+                    if crosses_x {
+                        edge.x = if offset.x > 0_f32 {
+                            canvas.min.x
+                        } else {
+                            canvas.max.x
+                        };
+                    }
+                    if crosses_y {
+                        edge.y = if offset.y > 0_f32 {
+                            canvas.min.y
+                        } else {
+                            canvas.max.y
+                        };
+                    }
+                    self.position = edge;
+                    offset *= 1_f32 - exit;
+                }
+                true
+            }
+        }
+    }
+}
+
+const MAX_WRAPS: usize = 10000;
+
+// Calculates the ratio of the <offset> at which the turtle leaves the canvas from <start>,
+// and also whether it crosses the vertical (x) and horizontal (y) edges at that point
+fn exit_ratio(canvas: Rect, start: Pos2, offset: Vec2) -> (f32, bool, bool) {
+    let ratio_x = if offset.x > 0_f32 {
+        (canvas.max.x - start.x) / offset.x
+    } else if offset.x < 0_f32 {
+        (canvas.min.x - start.x) / offset.x
+    } else {
+        f32::INFINITY
+    };
+    let ratio_y = if offset.y > 0_f32 {
+        (canvas.max.y - start.y) / offset.y
+    } else if offset.y < 0_f32 {
+        (canvas.min.y - start.y) / offset.y
+    } else {
+        f32::INFINITY
+    };
+    let ratio = ratio_x.min(ratio_y).clamp(0_f32, 1_f32);
+    (ratio, ratio_x <= ratio_y, ratio_y <= ratio_x)
 }
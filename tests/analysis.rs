@@ -0,0 +1,165 @@
+// The errors, which can be found without executing the scripts (e.g. in the code editors)
+use rugged_turtle::{
+    analysis::{check_script, lint_script, symbols, SymbolKind, WarningKind},
+    error::ErrorKind,
+    keywords::KeywordTable,
+    locale::{import_locales, Locale},
+    parsing::{parse, tokenize},
+};
+
+fn errors(script: &str) -> Vec<(ErrorKind, String)> {
+    check_script(script, &KeywordTable::default())
+        .into_iter()
+        .map(|error| (error.kind, error.token))
+        .collect()
+}
+
+fn warnings(script: &str) -> Vec<(WarningKind, String)> {
+    lint_script(script, &KeywordTable::default())
+        .into_iter()
+        .map(|warning| (warning.kind, warning.token))
+        .collect()
+}
+
+#[test]
+fn correct_scripts_have_no_errors() {
+    for script in [
+        "repeat(4) { forward(10); right(90) }",
+        "square(10); to square(size) { repeat(4) { fd(size); rt(90) } }",
+        "repeat(i, 0, 10) { if_needed = i; break }",
+        "foreach(x, [1, 2]) { repeat(2) { continue } }",
+        "",
+    ] {
+        assert_eq!(errors(script), [], "{}", script);
+    }
+}
+
+#[test]
+fn every_error_is_found() {
+    assert_eq!(
+        errors("fd(10); jump(5); to sq(a, b) { break }; sq(1); sq(1, 2); repeat(2) { to x(1) }"),
+        [
+            (ErrorKind::UnknownCommand, "jump".to_string()),
+            (ErrorKind::LoopControlOutsideLoop, "break".to_string()),
+            (
+                ErrorKind::WrongArgumentCount { expected: 2 },
+                "sq".to_string()
+            ),
+            (ErrorKind::InvalidVariableName, "1".to_string()),
+            (ErrorKind::MissingBlock, "x".to_string()),
+        ]
+    );
+    assert_eq!(
+        errors("local forward(10); to fd { }; to square(10)"),
+        [
+            (ErrorKind::UnexpectedToken, "forward".to_string()),
+            (ErrorKind::InvalidProcedureName, "fd".to_string()),
+            (ErrorKind::InvalidVariableName, "10".to_string()),
+            (ErrorKind::MissingBlock, "square".to_string()),
+        ]
+    );
+    // Only the syntax error is reported
+    assert_eq!(
+        errors("jump(5); fd(10"),
+        [(ErrorKind::UnbalancedBrackets, "(".to_string())]
+    );
+    let nested = format!("{}{}", "repeat(1) {".repeat(3000), "}".repeat(3000));
+    assert_eq!(
+        errors(&nested),
+        [(ErrorKind::NestingLimit, "repeat".to_string())]
+    );
+    assert_eq!(warnings(&nested), []);
+}
+
+#[test]
+fn symbols_of_the_script() {
+    let statements = parse(&tokenize(
+        "size = 10; to square(side) { repeat(i, 0, 4) { fd(side) } }; foreach(x, [1]) { local y = x }",
+    ))
+    .unwrap();
+    let symbols: Vec<(SymbolKind, &str)> = symbols(&statements, &KeywordTable::default())
+        .into_iter()
+        .map(|symbol| (symbol.kind, symbol.name.text.as_str()))
+        .collect();
+    assert_eq!(
+        symbols,
+        [
+            (SymbolKind::Variable, "size"),
+            (SymbolKind::Procedure, "square"),
+            (SymbolKind::Variable, "side"),
+            (SymbolKind::Variable, "i"),
+            (SymbolKind::Variable, "x"),
+            (SymbolKind::Variable, "y"),
+        ]
+    );
+}
+
+#[test]
+fn scripts_without_warnings() {
+    for script in [
+        "size = 10; to square(side) { repeat(4) { fd(side); rt(90) } }; square(size)",
+        "repeat(i, 0, 4) { fd(i) }; foreach(x, [1, 2]) { print(\"x = {x * 2}\") }",
+        "repeat(i, 0, 2) { local i = 5; i = i + 1 }",
+        "repeat(4) { fd(10); if_needed = 1; continue }; print(if_needed)",
+        // The warnings of the scripts with syntax errors aren't needed
+        "x = 1; fd(10",
+        "",
+    ] {
+        assert_eq!(warnings(script), [], "{}", script);
+    }
+}
+
+#[test]
+fn every_warning_is_found() {
+    assert_eq!(
+        warnings(
+            "x = 1; x = 2; to shape(size) { repeat(size, 0, 2) { fd(size) } }; \
+             repeat(i, 0, 4) { i = i + 1; break; fd(i); rt(90) }"
+        ),
+        [
+            (WarningKind::UnusedVariable, "x".to_string()),
+            (WarningKind::ShadowedLoopVariable, "size".to_string()),
+            (WarningKind::LoopVariableWrite, "i".to_string()),
+            (WarningKind::UnreachableCode, "break".to_string()),
+        ]
+    );
+    // The loop variable of a procedure doesn't hide the variables outside of it
+    assert_eq!(
+        warnings("foreach(x, [1]) { to shape() { foreach(x, [2]) { fd(x) } }; fd(x) }"),
+        []
+    );
+    assert_eq!(
+        warnings("repeat(i, 0, 2) { repeat(i, 0, 2) { fd(i) } }"),
+        [(WarningKind::ShadowedLoopVariable, "i".to_string())]
+    );
+    // The stop command leaves the procedure (or the program), it's allowed outside of the loops
+    assert_eq!(
+        warnings("to side(n) { fd(n); stop; rt(90); fd(n) }; side(10); stop; fd(1)"),
+        [
+            (WarningKind::UnreachableCode, "stop".to_string()),
+            (WarningKind::UnreachableCode, "stop".to_string())
+        ]
+    );
+    assert_eq!(errors("to side(n) { stop }; stop"), []);
+}
+
+#[test]
+fn warnings_are_localized() {
+    let warning = &lint_script(
+        "\nrepeat(4) {\n  continue; fd(10) }",
+        &KeywordTable::default(),
+    )[0];
+    assert_eq!(
+        warning.message(&Locale::default()),
+        "3:13 W003: This command is never executed, because \"continue\" leaves the block before it!"
+    );
+    let (locales, _) = import_locales();
+    let hungarian = locales
+        .iter()
+        .find(|locale| locale.language_id == "HU")
+        .unwrap();
+    assert_eq!(
+        warning.message(hungarian),
+        "3:13 W003: Ez a parancs sosem fut le, mert a(z) \"continue\" előtte kilép a blokkból!"
+    );
+}
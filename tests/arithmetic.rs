@@ -0,0 +1,308 @@
+// Compares the results of the expression evaluator with reference values,
+// which are calculated by Rust with the same precedence and associativity
+use rugged_turtle::{
+    arithmetic::{evaluate, parse_number_value},
+    commands::{execute_command, VariableTypes},
+    error::ErrorKind,
+    parsing::tokenize,
+    turtle::Turtle,
+};
+
+fn number(expression: &str, turtle: &mut Turtle) -> f64 {
+    match parse_number_value(&tokenize(expression), turtle) {
+        Ok(value) => value,
+        Err(error) => panic!("{:?} failed: {:?}", expression, error),
+    }
+}
+
+fn error(expression: &str) -> ErrorKind {
+    match evaluate(&tokenize(expression), &mut Turtle::default()) {
+        Ok(value) => panic!("{:?} should be an error, but it's {:?}", expression, value),
+        Err(error) => error.kind,
+    }
+}
+
+fn assert_values(cases: &[(&str, f64)], turtle: &mut Turtle) {
+    for (expression, expected) in cases {
+        let value = number(expression, turtle);
+        assert!(
+            value == *expected || (value.is_nan() && expected.is_nan()),
+            "{:?} = {}, but it should be {}",
+            expression,
+            value,
+            expected
+        );
+    }
+}
+
+#[test]
+fn precedence() {
+    assert_values(
+        &[
+            ("1 + 2 * 3", 1.0 + 2.0 * 3.0),
+            ("(1 + 2) * 3", (1.0 + 2.0) * 3.0),
+            ("10 - 4 / 2", 10.0 - 4.0 / 2.0),
+            ("2 * 3 + 4 * 5", 2.0 * 3.0 + 4.0 * 5.0),
+            ("7 : 2 + 1", (7.0_f64 / 2.0).floor() + 1.0),
+            ("7 % 4 * 2", 7.0 % 4.0 * 2.0),
+            ("1 + 2 ^ 3 * 2", 1.0 + 2.0_f64.powf(3.0) * 2.0),
+            ("2 * 3 ^ 2", 2.0 * 3.0_f64.powf(2.0)),
+            ("((2))", 2.0),
+            (
+                "(1 + (2 - (3 * (4 / 8))))",
+                1.0 + (2.0 - (3.0 * (4.0 / 8.0))),
+            ),
+        ],
+        &mut Turtle::default(),
+    );
+}
+
+#[test]
+fn left_associativity() {
+    assert_values(
+        &[
+            ("10 - 4 - 3", 10.0 - 4.0 - 3.0),
+            ("8 / 4 * 2", 8.0 / 4.0 * 2.0),
+            ("8 / 4 / 2", 8.0 / 4.0 / 2.0),
+            ("2 * 6 / 4", 2.0 * 6.0 / 4.0),
+            ("20 : 3 : 2", ((20.0_f64 / 3.0).floor() / 2.0).floor()),
+            ("100 % 7 % 3", 100.0 % 7.0 % 3.0),
+            ("1 - 2 + 3 - 4", 1.0 - 2.0 + 3.0 - 4.0),
+            ("9 / 3 % 2 * 5", 9.0 / 3.0 % 2.0 * 5.0),
+        ],
+        &mut Turtle::default(),
+    );
+}
+
+#[test]
+fn exponentiation() {
+    assert_values(
+        &[
+            ("2 ^ 10", 2.0_f64.powf(10.0)),
+            ("2 ^ 3 ^ 2", 2.0_f64.powf(3.0_f64.powf(2.0))),
+            ("(2 ^ 3) ^ 2", 2.0_f64.powf(3.0).powf(2.0)),
+            ("4 ^ 0.5", 4.0_f64.powf(0.5)),
+            ("2 ^ -1", 2.0_f64.powf(-1.0)),
+            ("-2 ^ 2", -(2.0_f64.powf(2.0))),
+            ("(-2) ^ 2", (-2.0_f64).powf(2.0)),
+            ("(-8) ^ (1 / 3)", (-8.0_f64).powf(1.0 / 3.0)),
+        ],
+        &mut Turtle::default(),
+    );
+}
+
+#[test]
+fn signs() {
+    assert_values(
+        &[
+            ("-5", -5.0),
+            ("+5", 5.0),
+            ("--5", 5.0),
+            ("-+-5", 5.0),
+            ("2 * -3", 2.0 * -3.0),
+            ("2 - -3", 2.0 - -3.0),
+            ("-2 * 3", -2.0 * 3.0),
+            ("-(1 + 2)", -(1.0 + 2.0)),
+            ("4 / -2 * 3", 4.0 / -2.0 * 3.0),
+            ("-7 : 2", (-7.0_f64 / 2.0).floor()),
+            ("7 % -3", 7.0 % -3.0),
+        ],
+        &mut Turtle::default(),
+    );
+}
+
+#[test]
+fn variables_and_functions() {
+    let mut turtle = Turtle::default();
+    execute_command(
+        "x = 3; side = -x ^ 2; xs = [1, 2, 4]".to_string(),
+        &mut turtle,
+    )
+    .unwrap();
+    assert_values(
+        &[
+            ("side", -(3.0_f64.powf(2.0))),
+            ("-x", -3.0),
+            ("x - x - x", 3.0 - 3.0 - 3.0),
+            ("2 ^ x ^ 0", 2.0_f64.powf(3.0_f64.powf(0.0))),
+            ("xs[1] ^ xs[2]", 2.0_f64.powf(4.0)),
+            ("-xs[2]", -4.0),
+            ("length(xs) * -2", 3.0 * -2.0),
+            ("number(\"1.5\") * 2 ^ 2", 1.5 * 2.0_f64.powf(2.0)),
+            ("length(range(0, 10, 2)) - 1", 5.0 - 1.0),
+        ],
+        &mut turtle,
+    );
+}
+
+#[test]
+fn special_values() {
+    assert_values(
+        &[
+            ("1 / 0", f64::INFINITY),
+            ("-1 / 0", f64::NEG_INFINITY),
+            ("0 / 0", f64::NAN),
+            ("0 ^ 0", 1.0),
+            ("10 ^ 400", f64::INFINITY),
+        ],
+        &mut Turtle::default(),
+    );
+}
+
+#[test]
+fn texts_and_lists() {
+    let mut turtle = Turtle::default();
+    for (expression, expected) in [
+        ("\"a\" + 1 + 2", "a12"),
+        ("1 + 2 + \"a\"", "3a"),
+        ("\"a\" + 2 * 3", "a6"),
+        ("\"{2 ^ 3}\"", "8"),
+        ("[1] + [2 - 3]", "[1, -1]"),
+    ] {
+        let value = evaluate(&tokenize(expression), &mut turtle).unwrap();
+        assert_eq!(value.to_string(), expected, "{:?}", expression);
+    }
+    assert!(matches!(
+        evaluate(&tokenize("-\"a\""), &mut turtle),
+        Err(error) if error.kind == ErrorKind::ExpectedNumber
+    ));
+    assert_eq!(
+        evaluate(&tokenize("[1, 2] + []"), &mut turtle),
+        Ok(VariableTypes::List {
+            value: vec![
+                VariableTypes::Number { value: 1.0 },
+                VariableTypes::Number { value: 2.0 }
+            ]
+        })
+    );
+}
+
+#[test]
+fn malformed_expressions() {
+    for (expression, kind) in [
+        ("", ErrorKind::InvalidExpression),
+        ("-", ErrorKind::InvalidExpression),
+        ("2 +", ErrorKind::InvalidExpression),
+        ("* 2", ErrorKind::InvalidExpression),
+        ("2 * * 3", ErrorKind::InvalidExpression),
+        ("2 ^", ErrorKind::InvalidExpression),
+        ("2 3", ErrorKind::InvalidExpression),
+        ("()", ErrorKind::InvalidExpression),
+        ("(1 + 2", ErrorKind::UnbalancedBrackets),
+        ("1 + 2)", ErrorKind::UnbalancedBrackets),
+        ("(1]", ErrorKind::UnbalancedBrackets),
+        ("y + 1", ErrorKind::UndefinedVariable),
+        ("1 - \"a\"", ErrorKind::ExpectedNumber),
+        ("2 ^ \"a\"", ErrorKind::ExpectedNumber),
+    ] {
+        assert_eq!(error(expression), kind, "{:?}", expression);
+    }
+}
+
+#[test]
+fn angles_and_headings() {
+    let mut turtle = Turtle::default();
+    assert_values(
+        &[
+            ("heading()", 0.0),
+            ("sin(30)", 30.0_f64.to_radians().sin()),
+            ("cos(60) * 2", 60.0_f64.to_radians().cos() * 2.0),
+            ("tan(45)", 45.0_f64.to_radians().tan()),
+            ("asin(1)", 1.0_f64.asin().to_degrees()),
+            ("atan(1) * 4", 1.0_f64.atan().to_degrees() * 4.0),
+        ],
+        &mut turtle,
+    );
+    for (script, expected) in [
+        ("rt(90)", 90.0),
+        ("lt(180)", 270.0),
+        ("rt(450)", 0.0),
+        ("lt(-720.5)", 0.5),
+        ("setheading(-30)", 330.0),
+        ("math", 120.0),
+        ("lt(30)", 150.0),
+        ("setheading(0); compass", 90.0),
+        ("radians; setheading(1)", 1.0),
+        ("rt(-2 * 3.14159265358979)", 1.0),
+    ] {
+        execute_command(script.to_string(), &mut turtle).unwrap();
+        let heading = number("heading()", &mut turtle);
+        assert!(
+            (heading - expected).abs() < 1e-9,
+            "heading() = {} after {:?}, but it should be {}",
+            heading,
+            script,
+            expected
+        );
+    }
+    assert_values(
+        &[("sin(1)", 1.0_f64.sin()), ("acos(0)", 0.0_f64.acos())],
+        &mut turtle,
+    );
+    assert_eq!(
+        error("heading(1)"),
+        ErrorKind::WrongArgumentCount { expected: 0 }
+    );
+    assert_eq!(error("sin(\"a\")"), ErrorKind::ExpectedNumber);
+}
+
+#[test]
+fn deeply_nested_expressions() {
+    let nested = |open: &str, inside: &str, close: &str, depth: usize| {
+        format!("{}{}{}", open.repeat(depth), inside, close.repeat(depth))
+    };
+    for expression in [
+        nested("(", "1", ")", 1000),
+        nested("[", "1", "]", 1000),
+        nested("length(", "1", ")", 1000),
+        format!("{}1", "-".repeat(3000)),
+        format!("\"{{{}}}\"", nested("(", "1", ")", 1000)),
+    ] {
+        let result = evaluate(&tokenize(&expression), &mut Turtle::default());
+        let error = result.expect_err("the expression is nested too deep");
+        assert_eq!(error.kind, ErrorKind::ExpressionNestingLimit);
+        // The error points inside of the expression
+        assert!(error.span.end <= expression.len());
+    }
+    let mut turtle = Turtle::default();
+    assert_values(
+        &[
+            (&nested("(", "1", ")", 50), 1.0),
+            (&format!("{}1", "-".repeat(50)), 1.0),
+        ],
+        &mut turtle,
+    );
+    assert_eq!(
+        execute_command(format!("x = {}1", "-".repeat(3000)), &mut turtle)
+            .map_err(|error| error.kind),
+        Err(ErrorKind::ExpressionNestingLimit)
+    );
+}
+
+#[test]
+fn long_ranges() {
+    let mut turtle = Turtle::default();
+    assert_values(
+        &[
+            ("length(range(0, 100000))", 100000.0),
+            ("length(range(0, 1, 0.00001))", 100000.0),
+            ("length(range(100000, 0, -1))", 100000.0),
+        ],
+        &mut turtle,
+    );
+    for expression in [
+        "range(0, 100001)",
+        "range(0, 1e9, 0.001)",
+        "range(0, -1e300, -1)",
+    ] {
+        assert_eq!(
+            error(expression),
+            ErrorKind::RangeLength { max: 100_000 },
+            "{:?}",
+            expression
+        );
+    }
+    let error = evaluate(&tokenize("range(1, 1e6 + 1, 2 - 1)"), &mut turtle).unwrap_err();
+    assert_eq!(error.token, "1, 1e6+1, 2-1");
+    assert_eq!((error.span.start, error.span.end), (6, 23));
+}
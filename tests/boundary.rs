@@ -0,0 +1,130 @@
+// The turtle leaves the canvas, reappears on the opposite side (wrap) or stops at the edge (fence)
+use egui::{pos2, Pos2, Rect};
+use rugged_turtle::{
+    commands::execute_command,
+    error::ErrorKind,
+    turtle::{BoundaryMode, Turtle},
+};
+
+// A turtle in the middle of the 100x100 canvas, it's heading east
+fn start(mode: BoundaryMode) -> Turtle {
+    let mut turtle = Turtle {
+        canvas: Some(Rect::from_min_max(pos2(0.0, 0.0), pos2(100.0, 100.0))),
+        boundary_mode: mode,
+        ..Default::default()
+    };
+    turtle.set_position(50.0, 50.0);
+    turtle.set_heading(90.0);
+    turtle
+}
+
+fn lines(turtle: &Turtle) -> Vec<Vec<Pos2>> {
+    turtle
+        .path
+        .lines
+        .iter()
+        .map(|line| line.points.clone())
+        .collect()
+}
+
+fn assert_near(point: Pos2, expected: Pos2) {
+    assert!(
+        point.distance(expected) < 1e-3,
+        "{:?} != {:?}",
+        point,
+        expected
+    );
+}
+
+#[test]
+fn wrap() {
+    let mut turtle = start(BoundaryMode::Wrap);
+    assert!(turtle.forward(80.0));
+    assert_eq!(
+        lines(&turtle),
+        [
+            vec![pos2(50.0, 50.0), pos2(100.0, 50.0)],
+            vec![pos2(0.0, 50.0), pos2(30.0, 50.0)]
+        ]
+    );
+    assert_eq!(turtle.position, pos2(30.0, 50.0));
+    // The turtle can stop at the edge
+    assert!(turtle.forward(70.0));
+    assert_eq!(turtle.position, pos2(100.0, 50.0));
+    assert_eq!(turtle.path.lines.len(), 2);
+    // Going around the canvas more than once
+    let mut turtle = start(BoundaryMode::Wrap);
+    turtle.set_heading(0.0);
+    turtle.forward(260.0);
+    assert_eq!(turtle.path.lines.len(), 4);
+    assert_near(turtle.position, pos2(50.0, 90.0));
+}
+
+#[test]
+fn wrap_at_the_corner() {
+    // Heading south-east from the middle, the turtle leaves the canvas at its corner
+    let mut turtle = start(BoundaryMode::Wrap);
+    turtle.set_heading(135.0);
+    assert!(turtle.forward(60.0 * 2_f32.sqrt()));
+    let lines = lines(&turtle);
+    assert_eq!(lines.len(), 2, "{:?}", lines);
+    assert_near(lines[0][1], pos2(100.0, 100.0));
+    // It reappears at the opposite corner
+    assert_near(lines[1][0], pos2(0.0, 0.0));
+    assert_near(turtle.position, pos2(10.0, 10.0));
+}
+
+#[test]
+fn wrap_from_outside() {
+    // The turtle is moved to the same place of the canvas, before it moves
+    let mut turtle = start(BoundaryMode::Wrap);
+    turtle.set_position(250.0, -30.0);
+    assert!(turtle.forward(10.0));
+    assert_eq!(lines(&turtle), [vec![pos2(50.0, 70.0), pos2(60.0, 70.0)]]);
+}
+
+#[test]
+fn wrap_limit() {
+    // The extremely long distances are cut short after 10000 wraps
+    let mut turtle = start(BoundaryMode::Wrap);
+    assert!(turtle.forward(1e9));
+    assert_eq!(turtle.path.lines.len(), 10_000);
+    assert!(turtle.canvas.unwrap().contains(turtle.position));
+}
+
+#[test]
+fn fence() {
+    let mut turtle = start(BoundaryMode::Fence);
+    assert!(turtle.forward(50.0));
+    assert_eq!(turtle.position, pos2(100.0, 50.0));
+    // The turtle stops at the edge
+    let mut turtle = start(BoundaryMode::Fence);
+    assert!(!turtle.forward(80.0));
+    assert_eq!(lines(&turtle), [vec![pos2(50.0, 50.0), pos2(100.0, 50.0)]]);
+    assert!(!turtle.forward(1.0));
+    assert_eq!(turtle.position, pos2(100.0, 50.0));
+    // Outside of the canvas the turtle can only move back to the canvas
+    turtle.set_position(-20.0, 50.0);
+    assert!(!turtle.forward(10.0));
+    assert_eq!(turtle.position, pos2(-20.0, 50.0));
+    assert!(turtle.forward(30.0));
+    assert_eq!(turtle.position, pos2(10.0, 50.0));
+    // The command reports the fence
+    let mut turtle = start(BoundaryMode::Window);
+    let error = execute_command(String::from("fence; fd(30); fd(30)"), &mut turtle).unwrap_err();
+    assert_eq!(error.kind, ErrorKind::Fence);
+    assert_eq!(error.span.start, 15);
+    assert_eq!(turtle.position, pos2(100.0, 50.0));
+}
+
+#[test]
+fn window() {
+    let mut turtle = start(BoundaryMode::Window);
+    assert!(turtle.forward(200.0));
+    assert_eq!(turtle.position, pos2(250.0, 50.0));
+    // Without a canvas the turtle can always move
+    let mut turtle = start(BoundaryMode::Fence);
+    turtle.canvas = None;
+    assert!(turtle.forward(200.0));
+    assert_eq!(turtle.position, pos2(250.0, 50.0));
+}
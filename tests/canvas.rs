@@ -0,0 +1,115 @@
+// The drawing looks the same on the canvas and in the exported SVG image
+use egui::{pos2, Color32};
+use rugged_turtle::{
+    canvas::{split_dashes, stroke_color},
+    commands::execute_command,
+    error::ErrorKind,
+    path::{PenDash, PenMode},
+    to_svg,
+    turtle::Turtle,
+};
+
+#[test]
+fn pen_modes() {
+    let pen = Color32::from_rgba_unmultiplied(255, 0, 0, 128);
+    let background = Color32::from_rgb(0, 64, 255);
+    assert_eq!(stroke_color(pen, PenMode::Paint, background), pen);
+    assert_eq!(stroke_color(pen, PenMode::Erase, background), background);
+    // The inverse color of the background with the opacity of the pen
+    assert_eq!(
+        stroke_color(pen, PenMode::InverseBackground, background),
+        Color32::from_rgba_unmultiplied(255, 191, 0, 128)
+    );
+    assert_eq!(
+        stroke_color(Color32::RED, PenMode::InverseBackground, Color32::WHITE),
+        Color32::BLACK
+    );
+    // The same color over the earlier lines, they aren't inverted
+    let mut turtle = Turtle::default();
+    execute_command(
+        String::from("pc(255, 0, 0, 255); fd(10); px; rt(180); fd(20)"),
+        &mut turtle,
+    )
+    .unwrap();
+    let colors: Vec<Color32> = turtle
+        .path
+        .lines
+        .iter()
+        .map(|line| turtle.path.styles[line.style])
+        .map(|style| stroke_color(style.color, style.mode, Color32::WHITE))
+        .collect();
+    assert_eq!(colors, [Color32::RED, Color32::BLACK]);
+    // The projects saved with the earlier name of the mode are opened
+    assert_eq!(
+        serde_json::from_str::<PenMode>("\"Reverse\"").unwrap(),
+        PenMode::InverseBackground
+    );
+}
+
+#[test]
+fn reversed_line_in_svg() {
+    let mut turtle = Turtle::default();
+    execute_command(
+        String::from("pencolor(255, 0, 0, 255); penwidth(2); penreverse; forward(50); right(90); forward(50)"),
+        &mut turtle,
+    )
+    .unwrap();
+    // The line has the inverse color of the background, like on the canvas
+    assert_eq!(
+        to_svg(&turtle, Color32::from_rgb(0, 64, 255)),
+        "\
+<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"54\" height=\"54\" viewBox=\"-2 -52 54 54\">
+<rect x=\"-2\" y=\"-52\" width=\"54\" height=\"54\" fill=\"rgb(0, 64, 255)\"/>
+<polyline fill=\"none\" points=\"0,0 0,-50 50,-50 \" stroke-width=\"2\" stroke=\"rgb(255, 191, 0)\" \
+stroke-opacity=\"1\" stroke-linecap=\"butt\" stroke-linejoin=\"miter\"/>
+</svg>
+"
+    );
+}
+
+#[test]
+fn straight_line_in_svg() {
+    let mut turtle = Turtle::default();
+    execute_command(
+        String::from("pencolor(0, 0, 0, 255); forward(100)"),
+        &mut turtle,
+    )
+    .unwrap();
+    let svg = to_svg(&turtle, Color32::WHITE);
+    // The line is inside of the image, the image is as wide as the line
+    assert!(
+        svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"2\" height=\"102\" viewBox=\"-1 -101 2 102\">"),
+        "{}",
+        svg
+    );
+    // The empty drawing is around the turtle
+    let mut turtle = Turtle::default();
+    turtle.set_position(10.0, 20.0);
+    assert!(to_svg(&turtle, Color32::WHITE).contains("viewBox=\"9 19 2 2\""));
+}
+
+#[test]
+fn tiny_dashes() {
+    let line = [pos2(0.0, 0.0), pos2(100.0, 0.0)];
+    let dashes = split_dashes(&line, PenDash { on: 3.0, off: 2.0 });
+    assert_eq!(dashes.len(), 20);
+    assert_eq!(dashes[1], [pos2(5.0, 0.0), pos2(8.0, 0.0)]);
+    // The dashes, which are too short to change the length of the line, can't split it forever
+    let dashes = split_dashes(
+        &[pos2(0.0, 0.0), pos2(1e9, 0.0), pos2(1e9, 10.0)],
+        PenDash {
+            on: 1e-8,
+            off: 1e-8,
+        },
+    );
+    assert!(dashes.len() <= 10_001);
+    assert_eq!(dashes.last().unwrap().last(), Some(&pos2(1e9, 10.0)));
+    // The pen's dashes and gaps have a minimal length
+    for input in ["pendash(0.00000001)", "pendash(0.05, 1)", "pendash(1, 0)"] {
+        let error = execute_command(input.to_string(), &mut Turtle::default()).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::InvalidPenDash, "{}", input);
+    }
+    let mut turtle = Turtle::default();
+    execute_command(String::from("pendash(0.1); pendash(0, 0)"), &mut turtle).unwrap();
+    assert_eq!(turtle.pen.dash, None);
+}
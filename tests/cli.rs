@@ -0,0 +1,88 @@
+// The command line arguments are checked before the commands are executed,
+// and their errors are printed in the language of the "--language" option
+use std::path::PathBuf;
+
+use rugged_turtle::cli::{
+    language_argument, parse_arguments, select_locale, Action, ArgumentError,
+};
+
+fn parse(arguments: &str) -> Result<Action, ArgumentError> {
+    parse_arguments(arguments.split_whitespace().map(str::to_string))
+}
+
+#[test]
+fn commands() {
+    assert_eq!(parse(""), Ok(Action::App));
+    assert_eq!(
+        parse("run square.txt --svg square.svg --language HU"),
+        Ok(Action::Run {
+            script: PathBuf::from("square.txt"),
+            svg: Some(PathBuf::from("square.svg")),
+        })
+    );
+    assert_eq!(
+        parse("fmt a.txt b.txt --normalize"),
+        Ok(Action::Format {
+            scripts: vec![PathBuf::from("a.txt"), PathBuf::from("b.txt")],
+            normalize: true,
+            check: false,
+        })
+    );
+}
+
+#[test]
+fn wrong_arguments() {
+    assert_eq!(parse("run"), Err(ArgumentError::MissingFiles));
+    assert_eq!(parse("fmt --check"), Err(ArgumentError::MissingFiles));
+    assert_eq!(
+        parse("run a.txt --svg"),
+        Err(ArgumentError::MissingValue(String::from("--svg")))
+    );
+    assert_eq!(
+        parse("run a.txt b.txt"),
+        Err(ArgumentError::UnknownArgument(String::from("b.txt")))
+    );
+    assert_eq!(
+        parse("--seed 1.5"),
+        Err(ArgumentError::InvalidSeed(String::from("1.5")))
+    );
+    let without = |option: &str, command: &str| {
+        Err(ArgumentError::OptionWithoutCommand {
+            option: option.to_string(),
+            command: command.to_string(),
+        })
+    };
+    assert_eq!(parse("fmt a.txt --svg a.svg"), without("--svg", "run"));
+    assert_eq!(parse("run a.txt --check"), without("--check", "fmt"));
+    assert_eq!(
+        parse("--language HU"),
+        without("--language", "run, locale-check, fmt")
+    );
+}
+
+#[test]
+fn localized_messages() {
+    let arguments: Vec<String> = ["fmt", "--language", "hu", "--svg", "a.svg"]
+        .map(String::from)
+        .to_vec();
+    assert_eq!(language_argument(&arguments), Some("hu"));
+    let (hungarian, others) = select_locale(language_argument(&arguments)).unwrap();
+    assert_eq!(hungarian.language_id, "HU");
+    assert!(others.iter().all(|locale| locale.language_id != "HU"));
+    let error = parse_arguments(arguments).unwrap_err();
+    assert_eq!(
+        error.message(&hungarian),
+        "A(z) --svg kapcsoló csak ezzel használható: run!"
+    );
+    let (english, _) = select_locale(None).unwrap();
+    assert_eq!(
+        error.message(&english),
+        "The --svg option can only be used with run!"
+    );
+    assert!(english.cli_usage.starts_with("Usage:"));
+    assert!(hungarian.cli_usage.starts_with("Használat:"));
+    assert_eq!(
+        select_locale(Some("XX")).unwrap_err(),
+        ArgumentError::UnknownLanguage(String::from("XX"))
+    );
+}
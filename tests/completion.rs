@@ -0,0 +1,108 @@
+// The console's input can be completed, recalled and explained while it's typed
+use rugged_turtle::{
+    commands::execute_command,
+    completion::{complete, usage_hint},
+    history::InputHistory,
+    keywords::KeywordTable,
+    turtle::Turtle,
+};
+
+fn completed(input: &str, cursor: usize, turtle: &Turtle) -> Option<(String, usize)> {
+    complete(input, cursor, &KeywordTable::default(), turtle)
+        .map(|completion| (completion.input, completion.cursor))
+}
+
+fn usage(input: &str) -> Option<String> {
+    let keywords = KeywordTable::default();
+    usage_hint(input, input.chars().count(), &keywords)
+        .map(|documentation| documentation.usage.clone())
+}
+
+#[test]
+fn aliases_are_completed() {
+    let turtle = Turtle::default();
+    assert_eq!(
+        completed("forw", 4, &turtle),
+        Some(("forward".to_string(), 7))
+    );
+    assert_eq!(
+        completed("penw(5); forw(10)", 4, &turtle),
+        Some(("penwidth(5); forw(10)".to_string(), 8))
+    );
+    assert_eq!(
+        completed("forward(10); forw(10)", 17, &turtle),
+        Some(("forward(10); forward(10)".to_string(), 20))
+    );
+    // Only the common beginning of the names is completed
+    let completion = complete("penu", 4, &KeywordTable::default(), &turtle).unwrap();
+    assert_eq!(completion.input, "penup");
+    assert_eq!(completion.candidates, ["penup"]);
+    let completion = complete("pend", 4, &KeywordTable::default(), &turtle).unwrap();
+    assert_eq!(completion.input, "pend");
+    assert_eq!(completion.candidates, ["pendash", "pendown"]);
+}
+
+#[test]
+fn names_of_the_user_are_completed() {
+    let mut turtle = Turtle::default();
+    execute_command(
+        "size = 10; to square(side) { repeat(4) { forward(side); right(90) } }".to_string(),
+        &mut turtle,
+    )
+    .unwrap();
+    assert_eq!(completed("siz", 3, &turtle), Some(("size".to_string(), 4)));
+    assert_eq!(
+        completed("squa", 4, &turtle),
+        Some(("square".to_string(), 6))
+    );
+    assert_eq!(completed("si", 2, &turtle), Some(("si".to_string(), 2)));
+}
+
+#[test]
+fn only_the_names_are_completed() {
+    let turtle = Turtle::default();
+    assert_eq!(completed("print(\"forw", 11, &turtle), None);
+    assert_eq!(completed("// forw", 7, &turtle), None);
+    assert_eq!(completed("forward(", 8, &turtle), None);
+    assert_eq!(completed("xyz", 3, &turtle), None);
+}
+
+#[test]
+fn usage_of_the_typed_command() {
+    assert_eq!(usage("fd"), Some("forward(<distance>)".to_string()));
+    assert_eq!(
+        usage("forward(10 * (2"),
+        Some("forward(<distance>)".to_string())
+    );
+    assert_eq!(
+        usage("repeat(4) { forward(10); pw("),
+        Some("penwidth(<width>)".to_string())
+    );
+    assert_eq!(
+        usage("pencolor(rnd("),
+        Some("random(<min>, <max>)".to_string())
+    );
+    assert_eq!(usage("forward(10); "), None);
+    assert_eq!(usage("repeat(4) { "), None);
+    assert_eq!(usage("size = 10"), None);
+}
+
+#[test]
+fn inputs_are_recalled() {
+    let mut history = InputHistory::default();
+    assert_eq!(history.recall_previous("typed"), None);
+    for input in ["forward(10)", "right(90)", "right(90)", "  "] {
+        history.push(input);
+    }
+    assert_eq!(history.inputs(), ["forward(10)", "right(90)"]);
+    assert_eq!(history.recall_previous("typed"), Some("right(90)"));
+    assert_eq!(history.recall_previous("right(90)"), Some("forward(10)"));
+    assert_eq!(history.recall_previous("forward(10)"), Some("forward(10)"));
+    assert_eq!(history.recall_next(), Some("right(90)"));
+    assert_eq!(history.recall_next(), Some("typed"));
+    assert_eq!(history.recall_next(), None);
+    // A new input starts again from the last one
+    history.recall_previous("");
+    history.push("left(90)");
+    assert_eq!(history.recall_previous(""), Some("left(90)"));
+}
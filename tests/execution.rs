@@ -0,0 +1,220 @@
+// The results of the executed scripts: the values of the variables and the lines of the console
+use rugged_turtle::{
+    commands::execute_command, documentation::Manual, error::ErrorKind, history::history_lines,
+    keywords::KeywordTable, locale::Locale, turtle::Turtle,
+};
+
+fn run(script: &str) -> Turtle {
+    let mut turtle = Turtle::default();
+    execute_command(script.to_string(), &mut turtle).unwrap();
+    turtle
+}
+
+fn error(script: &str) -> ErrorKind {
+    let mut turtle = Turtle::default();
+    execute_command(script.to_string(), &mut turtle)
+        .unwrap_err()
+        .kind
+}
+
+// The value of the variable as it's printed
+fn value(turtle: &Turtle, name: &str) -> Option<String> {
+    turtle
+        .get_variable(name)
+        .map(|variable| variable.variable_type.to_string())
+}
+
+// The lines, which are printed by the script
+fn output(script: &str) -> Vec<String> {
+    history_lines(
+        &run(script).command_history,
+        &Locale::default(),
+        &KeywordTable::default().documented(&[&Manual::default()]),
+    )
+}
+
+#[test]
+fn stop() {
+    // The stop command leaves the procedure, even from its loops
+    assert_eq!(
+        output("to count() { for(i, 0, 10) { print(\"{i}\"); repeat(1) { stop } }; print(\"end\") }; count(); count(); print(\"after\")"),
+        ["0", "0", "after"]
+    );
+    // Outside of the procedures it stops the program
+    assert_eq!(
+        output("print(1); repeat(3) { print(2); stop }; print(3)"),
+        ["1", "2"]
+    );
+    assert_eq!(output("print(1); stop; print(2)"), ["1"]);
+}
+
+#[test]
+fn printed_variables() {
+    // The variables are printed with their values, not with their expressions
+    assert_eq!(
+        output("a = 3; g = a * 2; print(g); evaluate(g); print(g + 1); print(\"{g}\")"),
+        ["g = 6", "g = 6", "7", "6"]
+    );
+    assert_eq!(
+        output("xs = [1, 2 + 3]; s = \"a\" + \"b\"; print(xs); print(s)"),
+        ["xs = [1, 5]", "s = ab"]
+    );
+}
+
+#[test]
+fn comments() {
+    // The comments are ignored until the end of their line, even after the commands
+    assert_eq!(
+        output("print(1) // print(2)\nprint(3) # print(4)\n# print(5)\nprint(6)"),
+        ["1", "3", "6"]
+    );
+    assert_eq!(
+        output("print(1 /* print(2) */ + 1); /* print(3)\nprint(4) */ print(5)"),
+        ["2", "5"]
+    );
+    // The comment signs inside of the texts aren't comments
+    assert_eq!(output("print(\"a // b # c\") // d"), ["a // b # c"]);
+    let turtle = run("x = 1 # x = 2\ny = 3 // ; y = 4");
+    assert_eq!(value(&turtle, "x"), Some(String::from("1")));
+    assert_eq!(value(&turtle, "y"), Some(String::from("3")));
+}
+
+#[test]
+fn loops() {
+    // The end of the range is left out
+    assert_eq!(
+        output("for(i, 3, 0, -1) { print(\"{i}\") }; for(i, 0, 1, 0.5) { print(\"{i}\") }"),
+        ["3", "2", "1", "0", "0.5"]
+    );
+    // The loop variable is only visible inside of the loop
+    let turtle = run("for(i, 0, 3) { x = i }; foreach(e, [1, 2]) { y = e }");
+    assert_eq!(value(&turtle, "i"), None);
+    assert_eq!(value(&turtle, "e"), None);
+    assert_eq!(
+        error("for(i, 0, 3) { }; print(i)"),
+        ErrorKind::UndefinedVariable
+    );
+    // The earlier variable of the same name is left unchanged
+    assert_eq!(
+        value(&run("i = 10; for(i, 0, 3) { }"), "i"),
+        Some(String::from("10"))
+    );
+    // The loop variable can't be changed
+    assert_eq!(
+        error("for(i, 0, 3) { i = 5 }"),
+        ErrorKind::ImmutableVariable
+    );
+}
+
+#[test]
+fn break_and_continue() {
+    assert_eq!(
+        output("for(i, 0, 10) { print(\"{i}\"); break; print(\"no\") }; print(\"end\")"),
+        ["0", "end"]
+    );
+    assert_eq!(
+        output("for(i, 0, 3) { print(\"{i}\"); continue; print(\"no\") }"),
+        ["0", "1", "2"]
+    );
+    // They leave only the innermost loop
+    assert_eq!(
+        output("for(i, 0, 2) { repeat(5) { print(\"{i}\"); break }; for(j, 0, 2) { continue; print(\"no\") } }"),
+        ["0", "1"]
+    );
+    assert_eq!(
+        output("n = 0; repeat(10) { n = n + 1; repeat(2) { break }; n = n + 1 }; print(n)"),
+        ["n = 20"]
+    );
+    assert_eq!(error("break"), ErrorKind::LoopControlOutsideLoop);
+    assert_eq!(
+        error("to skip() { continue }; repeat(2) { skip() }"),
+        ErrorKind::LoopControlOutsideLoop
+    );
+}
+
+#[test]
+fn scopes() {
+    // The variables of the blocks are dropped at the end of the blocks,
+    // the outer variables are updated
+    let turtle = run("x = 1; repeat(2) { x = x + 1; y = 5 }");
+    assert_eq!(value(&turtle, "x"), Some(String::from("3")));
+    assert_eq!(value(&turtle, "y"), None);
+    // The local variables hide the outer ones until the end of their block
+    assert_eq!(
+        output("x = 1; repeat(1) { local x = 10; x = x + 1; print(x) }; print(x)"),
+        ["x = 11", "x = 1"]
+    );
+    // The global variables exist after their block
+    let turtle = run("repeat(1) { global g = 2; local l = 3 }");
+    assert_eq!(value(&turtle, "g"), Some(String::from("2")));
+    assert_eq!(value(&turtle, "l"), None);
+    // The procedures see their parameters and the global variables (the variables of the program),
+    // but not the variables of the caller's blocks
+    assert_eq!(
+        output("global g = 1; to show(x) { print(x); print(g) }; x = 5; show(2); print(x)"),
+        ["x = 2", "g = 1", "x = 5"]
+    );
+    assert_eq!(
+        error("to show() { print(y) }; repeat(1) { local y = 1; show() }"),
+        ErrorKind::UndefinedVariable
+    );
+    assert_eq!(
+        error("repeat(1) { local z = 1 }; print(z)"),
+        ErrorKind::UndefinedVariable
+    );
+}
+
+#[test]
+fn constants() {
+    let turtle = run("const c = 2 * 3; d = c + 1");
+    assert_eq!(value(&turtle, "c"), Some(String::from("6")));
+    assert_eq!(value(&turtle, "d"), Some(String::from("7")));
+    for script in [
+        "const c = 1; c = 2",
+        "const c = 1; repeat(1) { c = 2 }",
+        "const c = 1; const c = 2",
+        "const c = 1; global c = 2",
+    ] {
+        assert_eq!(error(script), ErrorKind::ImmutableVariable, "{}", script);
+    }
+    // A local variable can hide the constant
+    assert_eq!(
+        output("const c = 1; repeat(1) { local c = 2; print(c) }; print(c)"),
+        ["c = 2", "c = 1"]
+    );
+}
+
+#[test]
+fn texts() {
+    let turtle = run(
+        "s = \"héllo world\"; n = length(s); part = substring(s, 6, 5); first = substring(s, 0, 1)",
+    );
+    assert_eq!(value(&turtle, "n"), Some(String::from("11")));
+    assert_eq!(value(&turtle, "part"), Some(String::from("world")));
+    assert_eq!(value(&turtle, "first"), Some(String::from("h")));
+    assert_eq!(
+        output("s = \"ab\" + 1; print(s); print(length(s)); print(length(\"\")); print(substring(s, 1, 2))"),
+        ["s = ab1", "3", "0", "b1"]
+    );
+    assert_eq!(output("x = 2; print(\"x = {x}, {x * 2}\")"), ["x = 2, 4"]);
+}
+
+#[test]
+fn indexes() {
+    let turtle = run(
+        "points = [[1, 2], [3, [4, \"five\"]]]; a = points[1][0]; b = points[1][1][1]; c = points[1][1][1][2]; d = points[0]",
+    );
+    assert_eq!(value(&turtle, "a"), Some(String::from("3")));
+    assert_eq!(value(&turtle, "b"), Some(String::from("five")));
+    assert_eq!(value(&turtle, "c"), Some(String::from("v")));
+    assert_eq!(value(&turtle, "d"), Some(String::from("[1, 2]")));
+    assert_eq!(
+        output("xs = [1, [2, 3]]; print(xs[1][1] + xs[0]); print(length(xs[1]))"),
+        ["4", "2"]
+    );
+    assert_eq!(
+        error("xs = [1, [2, 3]]; y = xs[1][2]"),
+        ErrorKind::InvalidIndex { length: 2 }
+    );
+    assert_eq!(error("xs = [1, 2]; y = xs[0][0]"), ErrorKind::ExpectedList);
+}
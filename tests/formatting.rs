@@ -0,0 +1,149 @@
+// The formatter writes one statement in every line and indents the blocks,
+// the formatted scripts do the same as the original ones
+use rugged_turtle::{
+    commands::execute_command,
+    documentation::Manual,
+    error::ErrorKind,
+    formatting::format_script,
+    keywords::{KeywordTable, Keywords},
+    locale::import_locales,
+    turtle::Turtle,
+};
+
+// The aliases of the language and the English aliases are normalized to the aliases of the language
+fn table(language: &str) -> KeywordTable {
+    let (locales, _) = import_locales();
+    let locale = locales
+        .iter()
+        .find(|locale| locale.language_id == language)
+        .unwrap();
+    let hungarian = &locales
+        .iter()
+        .find(|locale| locale.language_id == "HU")
+        .unwrap()
+        .keywords;
+    KeywordTable::new(&[&locale.keywords, hungarian, &Keywords::default()])
+        .documented(&[&locale.documentation, &Manual::default()])
+}
+
+#[test]
+fn statements_and_blocks() {
+    assert_eq!(
+        format_script("repeat(4){fd( 10 );rt(90)}", None).unwrap(),
+        "repeat(4) {\n    fd(10)\n    rt(90)\n}\n"
+    );
+    assert_eq!(
+        format_script(
+            "x=-1+2*-3;points=[1,2 , 3] ; print(points[0],len(\"a b\")[0])",
+            None
+        )
+        .unwrap(),
+        "x = -1 + 2 * -3\npoints = [1, 2, 3]\nprint(points[0], len(\"a b\")[0])\n"
+    );
+    // The block can start in the next line after its command
+    assert_eq!(
+        format_script(
+            "to square(size)\n{\nrepeat(4)\n  {forward(size)\nright(90)}}",
+            None
+        )
+        .unwrap(),
+        "to square(size) {\n    repeat(4) {\n        forward(size)\n        right(90)\n    }\n}\n"
+    );
+    assert_eq!(format_script("", None).unwrap(), "");
+    assert_eq!(format_script(" \n\n ", None).unwrap(), "");
+}
+
+#[test]
+fn comments_and_empty_lines_are_kept() {
+    assert_eq!(
+        format_script("# square\nrepeat(4) { // side\n fd(10) /* move */ ; rt(90)\n\n\n}\n\n\n\nprint(\"done\")", None)
+            .unwrap(),
+        "# square\nrepeat(4) { // side\n    fd(10) /* move */\n    rt(90)\n}\n\nprint(\"done\")\n"
+    );
+}
+
+#[test]
+fn aliases_are_normalized() {
+    let english = table("EN");
+    assert_eq!(
+        format_script("e(10)", Some(&english)).unwrap(),
+        "forward(10)\n"
+    );
+    assert_eq!(
+        format_script(
+            "to sq(size) { i(4) { fd(size); j(90) } }; local x = rnd(1, 6) + r(\"ab\"); lc(kerek); help(e)",
+            Some(&english)
+        )
+        .unwrap(),
+        "to sq(size) {\n    repeat(4) {\n        forward(size)\n        right(90)\n    }\n}\n\
+         local x = random(1, 6) + r(\"ab\")\nlinecap(round)\nhelp(forward)\n"
+    );
+    let hungarian = table("HU");
+    assert_eq!(
+        format_script(
+            "repeat(2) { fd(10); elore(5) }; global y = len(\"a\")",
+            Some(&hungarian)
+        )
+        .unwrap(),
+        "ismetles(2) {\n    elore(10)\n    elore(5)\n}\nglobalis y = hossz(\"a\")\n"
+    );
+    // The procedures and the variables keep their names
+    assert_eq!(
+        format_script("square = 5; square(square)", Some(&english)).unwrap(),
+        "square = 5\nsquare(square)\n"
+    );
+}
+
+#[test]
+fn scripts_with_syntax_errors_are_not_formatted() {
+    let nested = format!("{}{}", "repeat(1) {".repeat(3000), "}".repeat(3000));
+    for (script, kind) in [
+        ("repeat(4) { fd(10)", ErrorKind::UnbalancedBrackets),
+        ("print(\"a)", ErrorKind::UnterminatedString),
+        ("fd(10) rt(90)", ErrorKind::UnexpectedToken),
+        (nested.as_str(), ErrorKind::NestingLimit),
+    ] {
+        assert_eq!(
+            format_script(script, None).map_err(|error| error.kind),
+            Err(kind)
+        );
+    }
+}
+
+// The changed script draws and prints the same as the original script
+// (the random numbers are the same in both scripts)
+fn assert_draws_the_same(original: &str, changed: &str) {
+    let mut expected = Turtle::default();
+    let mut turtle = Turtle::default();
+    execute_command(format!("seed(1)\n{}", original), &mut expected).unwrap();
+    execute_command(format!("seed(1)\n{}", changed), &mut turtle).unwrap();
+    assert_eq!(turtle.path.lines, expected.path.lines, "{}", changed);
+    assert_eq!(turtle.path.labels, expected.path.labels, "{}", changed);
+    assert_eq!(
+        turtle.command_history, expected.command_history,
+        "{}",
+        changed
+    );
+}
+
+#[test]
+fn formatted_examples_draw_the_same() {
+    let keywords = KeywordTable::default();
+    let english = table("EN");
+    let manual = keywords.manual().unwrap();
+    for documentation in manual.commands().into_iter().chain(manual.functions()) {
+        for example in &documentation.examples {
+            let formatted = format_script(example, None).unwrap();
+            assert_eq!(
+                format_script(&formatted, None).unwrap(),
+                formatted,
+                "{}",
+                example
+            );
+            // The examples use the English aliases, they are normalized to the English keywords
+            let normalized = format_script(example, Some(&english)).unwrap();
+            assert_draws_the_same(example, &normalized);
+            assert_draws_the_same(example, &formatted);
+        }
+    }
+}
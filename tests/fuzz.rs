@@ -0,0 +1,258 @@
+// Feeds random and randomly mutated inputs into the interpreter,
+// every malformed input has to be reported as an error instead of a panic
+use rand::{rngs::StdRng, RngExt, SeedableRng};
+use rugged_turtle::{
+    arithmetic::parse_number_value,
+    commands::execute_command,
+    error::ErrorKind,
+    parsing::{parse, tokenize},
+    random::set_default_seed,
+    turtle::Turtle,
+};
+
+const SEED: u64 = 2025;
+const CASES: usize = 5000;
+
+// Building blocks of the random inputs
+#[rustfmt::skip]
+const FRAGMENTS: &[&str] = &[
+    "forward", "fd", "right", "left", "pencolor", "penwidth", "penup", "pendown", "pendash",
+    "linecap", "linejoin", "round", "wrap", "fence", "window", "penerase", "penreverse", "print",
+    "eval", "clear", "reset", "for", "repeat", "break", "continue", "help", "to", "local", "const", "label", "length", "foreach", "append", "range", "random", "pick", "seed", "radians", "math", "setheading", "heading", "sin", "atan", "x", "i", "_", "é", "0", "1", "2", "9", "0.5",
+    "1e3", "(", "(", ")", ")", "{", "}", ",", ",", ";", ";", "=", "+", "-", "*", "/", ":", "%", "^",
+    " ", "\n", "\t", "#", ".", "\"", "\\", "{", "}", "[", "]",
+];
+
+// Valid scripts, which are mutated by deleting, duplicating and inserting characters
+const SCRIPTS: &[&str] = &[
+    "forward(100); right(90); forward(50)",
+    "x = 5; for(i, 0, 4) { fd(x * 2 + i); rt(90) }; print(x)",
+    "pencolor(255, 0, 0, 255); penwidth(3); pendash(5, 2); linecap(round); fd((2 + 3) * 4)",
+    "fence; for(i, 0, 3) { for(j, 0, 2) { fd(10 / (j + 1)); lt(45) } }; wrap; eval(i)",
+    "to poly(n, s) { for(i, 0, n) { fd(s); rt(360 / n) } }\nconst side = 20; poly(3, side); poly(4, -side)",
+    "s = \"side \" + 1; label(\"{s}: {length(s)}\", 12); print(substring(s, 0, 4) + \"\\n\")",
+    "xs = [1, [2, \"a\"]]\nappend(xs, range(0, 3)); foreach(x, xs) { print(x); eval(xs[1][0] + length(x)) }",
+    "seed(3); for(i, 0, 20) { rt(random(-90, 90)); fd(random(10) * randomfloat()) }; print(pick([1, 2]))",
+    "radians; math; seth(atan(1)); fd(50); degrees; compass; rt(heading() + asin(0.5)); print(heading())",
+    "repeat(4) {\n  for(i, 10, -x, -2.5) { fd(i); continue }\n  rt(90) // turn\n  break\n}",
+];
+
+fn random_input(rng: &mut StdRng) -> String {
+    let length = rng.random_range(0..24);
+    (0..length)
+        .map(|_| FRAGMENTS[rng.random_range(0..FRAGMENTS.len())])
+        .collect()
+}
+
+fn mutated_script(rng: &mut StdRng) -> String {
+    let mut script: Vec<char> = SCRIPTS[rng.random_range(0..SCRIPTS.len())]
+        .chars()
+        .collect();
+    for _ in 0..rng.random_range(1..6) {
+        let index = rng.random_range(0..script.len());
+        match rng.random_range(0..3) {
+            0 => {
+                script.remove(index);
+            }
+            1 => script.insert(index, script[index]),
+            _ => {
+                let fragment = FRAGMENTS[rng.random_range(0..FRAGMENTS.len())];
+                fragment
+                    .chars()
+                    .rev()
+                    .for_each(|char| script.insert(index, char));
+            }
+        }
+        if script.is_empty() {
+            break;
+        }
+    }
+    script.into_iter().collect()
+}
+
+fn run(input: &str) {
+    // The random numbers of the inputs are the same in every test run
+    set_default_seed(SEED);
+    let mut turtle = Turtle::default();
+    if let Err(error) = execute_command(input.to_string(), &mut turtle) {
+        // The error has to point inside of the input
+        assert!(
+            error.span.start <= error.span.end && error.span.end <= input.len(),
+            "invalid span {:?} for {:?}",
+            error.span,
+            input
+        );
+        assert!(input.is_char_boundary(error.span.start) && input.is_char_boundary(error.span.end));
+    }
+}
+
+#[test]
+fn random_commands_never_panic() {
+    let mut rng = StdRng::seed_from_u64(SEED);
+    for _ in 0..CASES {
+        run(&random_input(&mut rng));
+    }
+}
+
+#[test]
+fn mutated_scripts_never_panic() {
+    let mut rng = StdRng::seed_from_u64(SEED);
+    for _ in 0..CASES {
+        run(&mutated_script(&mut rng));
+    }
+}
+
+#[test]
+fn random_expressions_never_panic() {
+    let mut rng = StdRng::seed_from_u64(SEED);
+    for _ in 0..CASES {
+        let input = random_input(&mut rng);
+        let _ = parse_number_value(&tokenize(&input), &mut Turtle::default());
+    }
+}
+
+// The inputs, which are nested <depth> levels deep, e.g. nested("(", "1", ")", 2) -> "((1))"
+fn nested(open: &str, inside: &str, close: &str, depth: usize) -> String {
+    format!("{}{}{}", open.repeat(depth), inside, close.repeat(depth))
+}
+
+#[test]
+fn deeply_nested_inputs_are_errors() {
+    for (input, kind) in [
+        (nested("repeat(1){", "", "}", 3000), ErrorKind::NestingLimit),
+        (
+            nested("repeat(1)\n{", "fd(1)", "}\n", 101),
+            ErrorKind::NestingLimit,
+        ),
+        (
+            format!("x = {}", nested("[", "", "]", 1000)),
+            ErrorKind::ExpressionNestingLimit,
+        ),
+        (
+            format!("fd({})", nested("(", "1", ")", 1000)),
+            ErrorKind::ExpressionNestingLimit,
+        ),
+    ] {
+        assert_eq!(
+            parse(&tokenize(&input))
+                .map(|_| ())
+                .map_err(|error| error.kind),
+            Err(kind.clone())
+        );
+        run(&input);
+    }
+    // The scripts, which aren't nested too deep, are executed
+    let mut turtle = Turtle::default();
+    execute_command(nested("repeat(1){", "fd(1)", "}", 50), &mut turtle).unwrap();
+    execute_command(format!("fd({})", nested("(", "1", ")", 50)), &mut turtle).unwrap();
+}
+
+#[test]
+fn endless_loops_are_errors() {
+    for input in [
+        "repeat(1e15) { fd(1) }",
+        "for(i, 0, 1, 0.000000001) { }",
+        "repeat(1000) { repeat(1001) { } }",
+    ] {
+        let error = execute_command(input.to_string(), &mut Turtle::default()).unwrap_err();
+        assert_eq!(
+            error.kind,
+            ErrorKind::StepLimit { max: 1_000_000 },
+            "{}",
+            input
+        );
+    }
+    // The steps are counted from the start of every program
+    let mut turtle = Turtle::default();
+    for _ in 0..2 {
+        execute_command(
+            String::from("repeat(1000) { repeat(999) { } }"),
+            &mut turtle,
+        )
+        .unwrap();
+    }
+}
+
+#[test]
+fn growing_values_are_errors() {
+    for input in [
+        "l = [1]; repeat(60) { l = l + l }",
+        "s = \"ab\"; repeat(60) { s = s + s }",
+        "s = \"ab\"; repeat(60) { s = \"{s}{s}\" }",
+        "l = [1]; repeat(60) { l = [l, l] }",
+        "l = [1]; repeat(60) { append(l, l) }",
+        "r = range(0, 100000); l = []; repeat(20) { append(l, r) }",
+    ] {
+        let mut turtle = Turtle::default();
+        let error = execute_command(input.to_string(), &mut turtle).unwrap_err();
+        assert_eq!(
+            error.kind,
+            ErrorKind::ValueSize { max: 1_000_000 },
+            "{}",
+            input
+        );
+        // The variables keep their last values, which aren't too large
+        for name in ["l", "s"] {
+            if let Some(variable) = turtle.get_variable(name) {
+                assert!(variable.variable_type.size() <= 1_000_000, "{}", input);
+            }
+        }
+    }
+    let mut turtle = Turtle::default();
+    execute_command(
+        String::from("l = []; repeat(1000) { append(l, \"text\") }; l = l + l"),
+        &mut turtle,
+    )
+    .unwrap();
+    assert_eq!(turtle.get_variable("l").unwrap().variable_type.size(), 8001);
+}
+
+#[test]
+fn malformed_commands_are_errors() {
+    for input in [
+        "forward()",
+        "pencolor(1,2)",
+        "for(i)",
+        "=5",
+        "}",
+        "{",
+        "fd(10",
+        "fd(10))",
+        "fd(1)}{",
+        "rt(()",
+        "fd(-)",
+        "for(,0,2){fd(1)}",
+        "print(\"x)",
+        "print(\"{x\")",
+        "label(1 + \"a\", -1)",
+        "fd([1][1])",
+        "foreach(x, 1) {}",
+        "append(y, 1)",
+        "print(random(0))",
+        "print(pick([]))",
+        "seed(0.5)",
+        "setheading(1 / 0)",
+        "rt(asin(2))",
+    ] {
+        assert!(
+            execute_command(input.to_string(), &mut Turtle::default()).is_err(),
+            "{:?} should be an error",
+            input
+        );
+    }
+}
+
+#[test]
+fn reset_inside_of_blocks() {
+    for input in [
+        "repeat(1) { reset }",
+        "to p() { reset }; p()",
+        "repeat(2) { fd(10); reset; x = 1 }; fd(5)",
+        "to p(n) { repeat(n) { reset; print(n) } }; p(2); print(1)",
+    ] {
+        let mut turtle = Turtle::default();
+        execute_command(input.to_string(), &mut turtle).unwrap();
+        assert_eq!(turtle.depth, 0, "{}", input);
+        assert!(turtle.scopes.is_empty(), "{}", input);
+    }
+}
@@ -0,0 +1,131 @@
+// The help is generated from the documentation of the commands in the locale files,
+// every command and function has to be documented, and every example has to work
+use std::fs;
+
+use rugged_turtle::{
+    commands::execute_command,
+    documentation::Manual,
+    error::ErrorKind,
+    history::history_lines,
+    keywords::{KeywordTable, Keywords},
+    locale::Locale,
+    turtle::Turtle,
+};
+use serde::Deserialize;
+
+const LOCALE_FILES: [&str; 2] = ["src/locale/locale_en.yml", "src/locale/locale_hu.yml"];
+
+// Only the keywords and the documentation are read from the locale files
+#[derive(Deserialize)]
+struct LocaleFile {
+    keywords: Keywords,
+    documentation: Manual,
+}
+
+fn language(path: &str) -> KeywordTable {
+    let contents = fs::read_to_string(path).unwrap();
+    let locale: LocaleFile = yaml_serde::from_str(&contents)
+        .unwrap_or_else(|error| panic!("{} can't be read: {}", path, error));
+    KeywordTable::new(&[&locale.keywords, &Keywords::default()])
+        .documented(&[&locale.documentation, &Manual::default()])
+}
+
+fn help(script: &str, keywords: KeywordTable) -> Result<Vec<String>, ErrorKind> {
+    let mut turtle = Turtle {
+        keywords,
+        ..Default::default()
+    };
+    execute_command(script.to_string(), &mut turtle).map_err(|error| error.kind)?;
+    Ok(history_lines(
+        &turtle.command_history,
+        &Locale::default(),
+        &turtle.keywords,
+    ))
+}
+
+#[test]
+fn everything_is_documented() {
+    let keywords = Keywords::default();
+    for path in LOCALE_FILES {
+        let contents = fs::read_to_string(path).unwrap();
+        let manual = yaml_serde::from_str::<LocaleFile>(&contents)
+            .unwrap()
+            .documentation;
+        assert!(
+            manual.commands.keys().eq(keywords.commands.keys()),
+            "{}",
+            path
+        );
+        assert!(
+            manual.functions.keys().eq(keywords.functions.keys()),
+            "{}",
+            path
+        );
+        for documentation in manual.commands.values().chain(manual.functions.values()) {
+            assert!(!documentation.usage.is_empty(), "{}", path);
+            assert!(!documentation.description.is_empty(), "{}", path);
+            assert!(!documentation.examples.is_empty(), "{}", path);
+        }
+    }
+    assert_eq!(
+        language("src/locale/locale_en.yml").manual(),
+        KeywordTable::default().manual()
+    );
+}
+
+#[test]
+fn examples_can_be_executed() {
+    for path in LOCALE_FILES {
+        let keywords = language(path);
+        let manual = keywords.manual().unwrap();
+        for documentation in manual.commands().into_iter().chain(manual.functions()) {
+            for example in &documentation.examples {
+                let mut turtle = Turtle {
+                    keywords: keywords.clone(),
+                    ..Default::default()
+                };
+                if let Err(error) = execute_command(example.clone(), &mut turtle) {
+                    panic!("{}: {:?} failed: {:?}", path, example, error);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn help_prints_the_documentation() {
+    let lines = help("help", KeywordTable::default()).unwrap();
+    assert!(lines.contains(&String::from(
+        "forward(<distance>) - moves the turtle forward by the distance (in pixels), its pen draws the path"
+    )));
+    assert!(lines.contains(&String::from(
+        "length(<text or list>) - the number of characters in the text, or the number of elements in the list"
+    )));
+    assert_eq!(help("help()", KeywordTable::default()), Ok(lines));
+    assert_eq!(
+        help("help(fd)", KeywordTable::default()).unwrap(),
+        vec![
+            "forward(<distance>) - moves the turtle forward by the distance (in pixels), its pen draws the path",
+            "Aliases: f, fd, forward",
+            "Examples:",
+            "    forward(100)",
+        ]
+    );
+    // The aliases of the selected language are the first ones
+    let hungarian = help(
+        "segitseg(elore); help(len)",
+        language("src/locale/locale_hu.yml"),
+    )
+    .unwrap();
+    assert_eq!(hungarian[0], "elore(<távolság>) - előre lépteti a teknőst a megadott távolsággal (pixelben), a tolla megrajzolja az útját");
+    assert_eq!(hungarian[1], "Rövidítések: e, elore, f, fd, forward");
+    assert!(hungarian.contains(&String::from("Rövidítések: hossz, len, length")));
+    for script in ["help(elore)", "help(x)", "help(1 + 2)"] {
+        assert_eq!(
+            help(script, KeywordTable::default()),
+            Err(ErrorKind::UnknownCommand),
+            "{}",
+            script
+        );
+    }
+}
@@ -0,0 +1,132 @@
+// The script editor highlights the source code with the tokens of the interpreter
+use rugged_turtle::{
+    highlighting::{highlight, Highlight},
+    keywords::KeywordTable,
+    turtle::Turtle,
+};
+
+// The highlighted parts without the whitespaces
+fn parts(source: &str, cursor: Option<usize>) -> Vec<(&str, Highlight)> {
+    highlight(source, &KeywordTable::default(), &Turtle::default(), cursor)
+        .into_iter()
+        .map(|(range, highlight)| (&source[range], highlight))
+        .filter(|(text, _)| !text.trim().is_empty())
+        .collect()
+}
+
+#[test]
+fn tokens_are_highlighted() {
+    use Highlight::*;
+    assert_eq!(
+        parts(
+            "to square(side) { repeat(4) { fd(side); rt(90) } } # comment\nsquare(50); print(\"done\"); linecap(square)",
+            None
+        ),
+        [
+            ("to", Keyword),
+            ("square", Procedure),
+            ("(", Bracket),
+            ("side", Variable),
+            (")", Bracket),
+            ("{", Bracket),
+            ("repeat", Keyword),
+            ("(", Bracket),
+            ("4", Number),
+            (")", Bracket),
+            ("{", Bracket),
+            ("fd", Keyword),
+            ("(", Bracket),
+            ("side", Variable),
+            (")", Bracket),
+            (";", Plain),
+            ("rt", Keyword),
+            ("(", Bracket),
+            ("90", Number),
+            (")", Bracket),
+            ("}", Bracket),
+            ("}", Bracket),
+            ("# comment", Comment),
+            ("square", Procedure),
+            ("(", Bracket),
+            ("50", Number),
+            (")", Bracket),
+            (";", Plain),
+            ("print", Keyword),
+            ("(", Bracket),
+            ("\"done\"", String),
+            (")", Bracket),
+            (";", Plain),
+            ("linecap", Keyword),
+            ("(", Bracket),
+            ("square", Keyword),
+            (")", Bracket),
+        ]
+    );
+}
+
+#[test]
+fn parts_cover_the_source_code() {
+    let source = "x = 1  /* ő */\n  print(x)  ";
+    let parts = highlight(source, &KeywordTable::default(), &Turtle::default(), None);
+    let mut end = 0;
+    for (range, _) in &parts {
+        assert_eq!(range.start, end);
+        end = range.end;
+    }
+    assert_eq!(end, source.len());
+}
+
+#[test]
+fn brackets_are_matched() {
+    use Highlight::*;
+    let brackets = |source: &str, cursor: Option<usize>| -> Vec<Highlight> {
+        parts(source, cursor)
+            .into_iter()
+            .filter(|(text, _)| "(){}[]".contains(*text))
+            .map(|(_, highlight)| highlight)
+            .collect()
+    };
+    // The bracket before the cursor is matched, then the one after it
+    assert_eq!(
+        brackets("fd(len([1]))", Some(12)),
+        [
+            MatchingBracket,
+            Bracket,
+            Bracket,
+            Bracket,
+            Bracket,
+            MatchingBracket
+        ]
+    );
+    assert_eq!(
+        brackets("fd(len([1]))", Some(6)),
+        [
+            Bracket,
+            MatchingBracket,
+            Bracket,
+            Bracket,
+            MatchingBracket,
+            Bracket
+        ]
+    );
+    assert_eq!(
+        brackets("fd(len([1]))", Some(1)),
+        [Bracket, Bracket, Bracket, Bracket, Bracket, Bracket]
+    );
+    // The brackets in the strings and the comments don't count
+    assert_eq!(brackets("print(\")\") // (", None), [Bracket, Bracket]);
+    assert_eq!(
+        brackets("fd(10]; { rt(90)", None),
+        [
+            UnbalancedBracket,
+            UnbalancedBracket,
+            UnbalancedBracket,
+            Bracket,
+            Bracket
+        ]
+    );
+    assert_eq!(
+        brackets("fd(10))", Some(7)),
+        [Bracket, Bracket, UnbalancedBracket]
+    );
+}
@@ -0,0 +1,116 @@
+// The console's history is stored with the ids of the messages,
+// so it's displayed in the language, which is selected when it's displayed
+use rugged_turtle::{
+    commands::execute_command,
+    documentation::Manual,
+    history::{history_lines, HelpTopic, HistoryEntry, MessageId},
+    keywords::{KeywordTable, Keywords},
+    locale::{load_locales, Locale},
+    turtle::Turtle,
+};
+
+fn keywords(locale: &Locale) -> KeywordTable {
+    KeywordTable::new(&[&locale.keywords, &Keywords::default()])
+        .documented(&[&locale.documentation, &Manual::default()])
+}
+
+#[test]
+fn history_follows_the_language() {
+    let (locales, _) = load_locales(&[]);
+    let (english, hungarian) = (&locales[0], &locales[1]);
+    let mut turtle = Turtle {
+        keywords: keywords(hungarian),
+        ..Default::default()
+    };
+    turtle
+        .command_history
+        .push(HistoryEntry::message(MessageId::TerminalHelp, &[]));
+    execute_command(
+        String::from("kiir(\"a\\nb\"); segitseg(elore)"),
+        &mut turtle,
+    )
+    .unwrap();
+    let error = execute_command(String::from("elore(1/0)"), &mut turtle).unwrap_err();
+    turtle.command_history.push(HistoryEntry::Error(error));
+    assert_eq!(
+        turtle.command_history[2],
+        HistoryEntry::Help {
+            topic: HelpTopic::Command(String::from("forward"))
+        }
+    );
+    let lines = history_lines(&turtle.command_history, hungarian, &turtle.keywords);
+    assert_eq!(lines[0], hungarian.terminal_help_message);
+    assert_eq!(lines[1..3], ["a", "b"]);
+    assert!(lines[3].starts_with("elore(<távolság>)"));
+    assert!(lines.last().unwrap().contains("A beírt távolságot (1/0)"));
+    // The same history in English
+    let lines = history_lines(&turtle.command_history, english, &keywords(english));
+    assert_eq!(lines[0], english.terminal_help_message);
+    assert_eq!(lines[1..3], ["a", "b"]);
+    assert!(lines[3].starts_with("forward(<distance>)"));
+    assert!(lines
+        .last()
+        .unwrap()
+        .contains("The turtle can't travel the specified distance (1/0)"));
+}
+
+#[test]
+fn history_of_the_older_versions() {
+    // The history used to be saved as texts
+    let history: Vec<HistoryEntry> = serde_json::from_str(r#"["a", "b"]"#).unwrap();
+    assert_eq!(
+        history,
+        vec![
+            HistoryEntry::Text(String::from("a")),
+            HistoryEntry::Text(String::from("b"))
+        ]
+    );
+    let history = vec![
+        HistoryEntry::Text(String::from("square")),
+        HistoryEntry::message(MessageId::AmbiguousKeyword, &[("alias", "r".into())]),
+        HistoryEntry::Help {
+            topic: HelpTopic::All,
+        },
+    ];
+    let json = serde_json::to_string(&history).unwrap();
+    assert!(json.starts_with(r#"["square",{"id":"AmbiguousKeyword""#));
+    assert_eq!(
+        serde_json::from_str::<Vec<HistoryEntry>>(&json).unwrap(),
+        history
+    );
+}
+
+#[test]
+fn restored_errors_follow_the_language() {
+    let (locales, _) = load_locales(&[]);
+    let (english, hungarian) = (&locales[0], &locales[1]);
+    let mut turtle = Turtle {
+        keywords: keywords(english),
+        ..Default::default()
+    };
+    // The errors of the execution and of the parser are stored like in the application
+    for script in ["forward(1/0)", "repeat(3) {", "x = range(0, 1e9)"] {
+        let error = execute_command(script.to_string(), &mut turtle).unwrap_err();
+        turtle.command_history.push(HistoryEntry::Error(error));
+    }
+    let english_lines = history_lines(&turtle.command_history, english, &turtle.keywords);
+    assert_eq!(english_lines.len(), 3);
+    // The session is saved with the kinds and the tokens of the errors, without their texts
+    let session = serde_json::to_string(&turtle).unwrap();
+    for line in &english_lines {
+        let text = line.split_once(": ").unwrap().1;
+        assert!(!session.contains(text), "{}", text);
+    }
+    let restored: Turtle = serde_json::from_str(&session).unwrap();
+    assert_eq!(restored.command_history, turtle.command_history);
+    let hungarian_lines = history_lines(&restored.command_history, hungarian, &keywords(hungarian));
+    assert!(hungarian_lines[0].contains("A beírt távolságot (1/0)"));
+    for (english_line, hungarian_line) in english_lines.iter().zip(&hungarian_lines) {
+        assert_ne!(english_line, hungarian_line);
+        // The location and the code of the error doesn't depend on the language
+        assert_eq!(
+            english_line.split_once(": ").unwrap().0,
+            hungarian_line.split_once(": ").unwrap().0
+        );
+    }
+}
@@ -0,0 +1,155 @@
+// The keywords of the languages are loaded from the locale files,
+// the interpreter finds the commands by the keywords of the selected and the fallback language
+use std::fs;
+
+use rugged_turtle::{
+    commands::execute_command,
+    documentation::Manual,
+    error::ErrorKind,
+    keywords::{Ambiguity, KeywordTable, Keywords},
+    turtle::Turtle,
+};
+use serde::Deserialize;
+
+const LOCALE_FILES: [&str; 2] = ["src/locale/locale_en.yml", "src/locale/locale_hu.yml"];
+
+// Only the keywords are read from the locale files
+#[derive(Deserialize)]
+struct LocaleFile {
+    keywords: Keywords,
+}
+
+fn keywords(path: &str) -> Keywords {
+    let contents = fs::read_to_string(path).unwrap();
+    yaml_serde::from_str::<LocaleFile>(&contents)
+        .unwrap_or_else(|error| panic!("{} can't be read: {}", path, error))
+        .keywords
+}
+
+fn run(script: &str, keywords: KeywordTable) -> Result<Turtle, ErrorKind> {
+    let mut turtle = Turtle {
+        keywords,
+        ..Default::default()
+    };
+    execute_command(script.to_string(), &mut turtle).map_err(|error| error.kind)?;
+    Ok(turtle)
+}
+
+#[test]
+fn locale_files_declare_every_keyword() {
+    let english = Keywords::default();
+    for path in LOCALE_FILES {
+        let keywords = keywords(path);
+        assert_eq!(keywords.ambiguities(), vec![], "{}", path);
+        for (names, english_names) in [
+            (&keywords.commands, &english.commands),
+            (&keywords.functions, &english.functions),
+            (&keywords.values, &english.values),
+        ] {
+            assert!(names.keys().eq(english_names.keys()), "{}", path);
+            assert!(
+                names.values().all(|aliases| !aliases.trim().is_empty()),
+                "{}",
+                path
+            );
+        }
+    }
+    // The English keywords are read from the English locale file, they name the documented commands
+    assert_eq!(
+        keywords("src/locale/locale_en.yml").commands,
+        english.commands
+    );
+    let manual = Manual::default();
+    assert!(english.commands.keys().eq(manual.commands.keys()));
+    assert!(english.functions.keys().eq(manual.functions.keys()));
+}
+
+#[test]
+fn selected_and_fallback_languages() {
+    let hungarian = keywords("src/locale/locale_hu.yml");
+    let table = || KeywordTable::new(&[&hungarian, &Keywords::default()]);
+    // "r" rotates, and the Hungarian "i" repeats
+    let turtle = run("i(3) { elore(10); r(90) }", table()).unwrap();
+    assert_eq!(turtle.heading(), 270.0);
+    assert!(!turtle.path.lines.is_empty());
+    assert_eq!(
+        run(
+            "x = hossz(\"abc\") + len([1]); lc(kerek); vonalsarok(round)",
+            table()
+        )
+        .unwrap()
+        .get_variable("x")
+        .unwrap()
+        .variable_type
+        .get_value(),
+        4.0
+    );
+    // Only the English keywords can be used without the Hungarian language
+    assert_eq!(
+        run("elore(10)", KeywordTable::default()).err(),
+        Some(ErrorKind::UnknownCommand)
+    );
+    assert_eq!(
+        run("x = hossz(\"abc\")", KeywordTable::default()).err(),
+        Some(ErrorKind::UnknownFunction)
+    );
+    assert_eq!(
+        run("linecap(kerek)", KeywordTable::default()).err(),
+        Some(ErrorKind::InvalidLineCap)
+    );
+    // The procedures can't be named as the keywords of the languages
+    assert_eq!(
+        run("to elore() {}", table()).err(),
+        Some(ErrorKind::InvalidProcedureName)
+    );
+    assert!(run("to elore() {}; elore()", KeywordTable::default()).is_ok());
+}
+
+#[test]
+fn ambiguous_keywords() {
+    let mut keywords = Keywords::default();
+    keywords
+        .commands
+        .insert(String::from("repeat"), String::from("r rep repeat"));
+    keywords
+        .functions
+        .insert(String::from("length"), String::from("r len length"));
+    assert_eq!(
+        keywords.ambiguities(),
+        vec![Ambiguity {
+            alias: String::from("r"),
+            names: vec![String::from("repeat"), String::from("right")],
+        }]
+    );
+    // The ambiguous keyword can't be used, unless the fallback language declares it
+    assert_eq!(
+        run("r(90)", KeywordTable::new(&[&keywords])).err(),
+        Some(ErrorKind::UnknownCommand)
+    );
+    // The commands and the functions don't share their keywords
+    let turtle = run(
+        "r(90); x = r(\"ab\")",
+        KeywordTable::new(&[&keywords, &Keywords::default()]),
+    )
+    .unwrap();
+    assert_eq!(turtle.heading(), 90.0);
+    assert_eq!(
+        turtle.get_variable("x").unwrap().variable_type.get_value(),
+        2.0
+    );
+}
+
+#[test]
+fn canonical_aliases() {
+    let table = KeywordTable::default();
+    assert_eq!(table.canonical_command("fd"), Some("forward"));
+    assert_eq!(table.canonical_command("to"), Some("to"));
+    assert_eq!(table.canonical_function("rnd"), Some("random"));
+    assert_eq!(table.canonical_value("round"), Some("round"));
+    assert_eq!(table.canonical_command("rnd"), None);
+    // Without the documentation of the language its first aliases are the canonical ones
+    let hungarian = keywords("src/locale/locale_hu.yml");
+    let table = KeywordTable::new(&[&hungarian, &Keywords::default()]);
+    assert_eq!(table.canonical_command("fd"), Some("e"));
+    assert_eq!(table.canonical_value("round"), Some("kerek"));
+}
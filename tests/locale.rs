@@ -0,0 +1,297 @@
+// The included languages are always loaded, the locale directories can override them
+// or add new languages, and the invalid locale files are reported
+// The missing keys of the translations are taken from the default locale
+use std::{env, fs, path::PathBuf};
+
+use rugged_turtle::{
+    error::{ErrorKind, Span, TurtleError},
+    locale::{check_locale, load_locales, Locale, LocaleError, LocaleErrorKind, LocaleReport},
+    message::{format_message, placeholders, Argument, PluralRule},
+};
+
+// An empty directory for the locale files of a test
+fn locale_directory(name: &str) -> PathBuf {
+    let directory = env::temp_dir().join(format!("rugged_turtle_{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&directory);
+    fs::create_dir_all(&directory).unwrap();
+    directory
+}
+
+fn language_ids(directories: &[PathBuf]) -> Vec<String> {
+    load_locales(directories)
+        .0
+        .into_iter()
+        .map(|locale| locale.language_id)
+        .collect()
+}
+
+#[test]
+fn included_languages() {
+    let (locales, errors) = load_locales(&[]);
+    assert_eq!(errors, vec![]);
+    assert_eq!(language_ids(&[]), vec!["EN", "HU"]);
+    assert_eq!(locales[1].language_name, "Magyar");
+    // The missing directories are skipped
+    assert_eq!(
+        language_ids(&[PathBuf::from("missing/locale")]),
+        vec!["EN", "HU"]
+    );
+}
+
+#[test]
+fn overridden_and_new_languages() {
+    let english = fs::read_to_string("src/locale/locale_en.yml").unwrap();
+    let first = locale_directory("first");
+    let second = locale_directory("second");
+    fs::write(
+        first.join("custom.yml"),
+        english
+            .replace("language_id: EN", "language_id: XX")
+            .replace("language_name: English", "language_name: Custom"),
+    )
+    .unwrap();
+    fs::write(
+        first.join("english.yaml"),
+        english.replace("run_button: Run", "run_button: Go"),
+    )
+    .unwrap();
+    // The later directories are preferred
+    fs::write(
+        second.join("english.yml"),
+        english.replace("run_button: Run", "run_button: Start"),
+    )
+    .unwrap();
+    // Only the YAML files are loaded
+    fs::write(second.join("notes.txt"), "language_id: YY").unwrap();
+    let (locales, errors) = load_locales(&[first.clone(), second.clone()]);
+    assert_eq!(errors, vec![]);
+    let ids: Vec<&str> = locales
+        .iter()
+        .map(|locale| locale.language_id.as_str())
+        .collect();
+    assert_eq!(ids, vec!["EN", "HU", "XX"]);
+    assert_eq!(locales[0].run_button, "Start");
+    assert_eq!(locales[2].language_name, "Custom");
+    let _ = fs::remove_dir_all(first);
+    let _ = fs::remove_dir_all(second);
+}
+
+#[test]
+fn invalid_locale_files() {
+    let directory = locale_directory("invalid");
+    fs::write(directory.join("broken.yml"), "language_id: [").unwrap();
+    // The language_id can't be missing
+    fs::write(directory.join("incomplete.yml"), "run_button: Go").unwrap();
+    let (locales, errors) = load_locales(std::slice::from_ref(&directory));
+    assert_eq!(locales.len(), 2);
+    let paths: Vec<PathBuf> = errors
+        .iter()
+        .map(|error| PathBuf::from(&error.path))
+        .collect();
+    assert_eq!(
+        paths,
+        vec![
+            directory.join("broken.yml"),
+            directory.join("incomplete.yml")
+        ]
+    );
+    assert!(errors.iter().all(|error| matches!(
+        &error.kind,
+        LocaleErrorKind::Invalid { error } if !error.is_empty()
+    )));
+    let _ = fs::remove_dir_all(directory);
+}
+
+#[test]
+fn partial_translations() {
+    let directory = locale_directory("partial");
+    fs::write(
+        directory.join("partial.yml"),
+        "language_id: XX\nrun_button: Go\nkeywords:\n  commands:\n    forward: go forward\n",
+    )
+    .unwrap();
+    let (locales, errors) = load_locales(std::slice::from_ref(&directory));
+    assert_eq!(errors, vec![]);
+    let partial = &locales[2];
+    let english = Locale::default();
+    assert_eq!(partial.run_button, "Go");
+    assert_eq!(partial.language_name, english.language_name);
+    assert_eq!(partial.cancel_button, english.cancel_button);
+    assert_eq!(partial.keywords.commands["forward"], "go forward");
+    assert_eq!(partial.keywords.functions, english.keywords.functions);
+    assert_eq!(partial.documentation, english.documentation);
+    let _ = fs::remove_dir_all(directory);
+}
+
+#[test]
+fn locale_check() {
+    for path in ["src/locale/locale_en.yml", "src/locale/locale_hu.yml"] {
+        let contents = fs::read_to_string(path).unwrap();
+        assert_eq!(
+            check_locale(&contents),
+            Ok(LocaleReport::default()),
+            "{}",
+            path
+        );
+    }
+    let report = check_locale(
+        "language_id: XX\nrun_button: Go {x}\nvar_immutable_error: '{name} {{x}}'\ninvalid_index_error: '{length} {index'\nunused: x\nkeywords:\n  commands:\n    jump: j\n",
+    )
+    .unwrap();
+    assert!(report.missing.contains(&String::from("language_name")));
+    assert!(report.missing.contains(&String::from("keywords.functions")));
+    assert!(report
+        .missing
+        .contains(&String::from("keywords.commands.forward")));
+    assert!(!report.missing.contains(&String::from("run_button")));
+    assert_eq!(report.extra, vec!["keywords.commands.jump", "unused"]);
+    assert_eq!(
+        report.placeholders,
+        vec![
+            (
+                String::from("run_button"),
+                Some(vec![String::from("x")]),
+                vec![]
+            ),
+            (
+                String::from("invalid_index_error"),
+                None,
+                vec![String::from("index"), String::from("length")]
+            ),
+        ]
+    );
+    assert!(check_locale("run_button: [").is_err());
+    assert!(check_locale("run_button: Go").is_err());
+}
+
+#[test]
+fn named_placeholders() {
+    let format = |message: &str, arguments: &[(&str, Argument)], rule: PluralRule| {
+        format_message(message, arguments, ".", rule)
+    };
+    let count = |value: f64| [("count", Argument::from(value))];
+    let times = "{count} {count|time|times}";
+    assert_eq!(format(times, &count(1.0), PluralRule::OneOther), "1 time");
+    assert_eq!(format(times, &count(2.0), PluralRule::OneOther), "2 times");
+    assert_eq!(
+        format(times, &count(0.5), PluralRule::OneOther),
+        "0.5 times"
+    );
+    assert_eq!(format(times, &count(2.0), PluralRule::Other), "2 time");
+    let few = "{count|one|few|many}";
+    for (number, form) in [(1.0, "one"), (22.0, "few"), (12.0, "many"), (25.0, "many")] {
+        assert_eq!(format(few, &count(number), PluralRule::OneFewMany), form);
+    }
+    // The forms of 1, 2, 5, 21 and 22 in each rule
+    let forms = "{count|first|second|third}";
+    for (rule, expected) in [
+        (
+            PluralRule::OneOther,
+            ["first", "second", "second", "second", "second"],
+        ),
+        (PluralRule::Other, ["first"; 5]),
+        (
+            PluralRule::OneFewMany,
+            ["first", "second", "third", "first", "second"],
+        ),
+    ] {
+        let chosen = [1.0, 2.0, 5.0, 21.0, 22.0].map(|number| format(forms, &count(number), rule));
+        assert_eq!(chosen, expected, "{:?}", rule);
+    }
+    // The texts, which are numbers, choose the form too
+    assert_eq!(
+        format(times, &[("count", "1".into())], PluralRule::OneOther),
+        "1 time"
+    );
+    assert_eq!(
+        format(times, &[("count", "x".into())], PluralRule::OneOther),
+        "x times"
+    );
+    // The arguments can be in any order, the unknown ones are kept
+    assert_eq!(
+        format(
+            "{{{end}}} {start} {unknown}",
+            &[("start", "a".into()), ("end", "b".into())],
+            PluralRule::OneOther
+        ),
+        "{b} a {unknown}"
+    );
+    assert_eq!(
+        placeholders("{b} {a|x|y} {{c}} {b}"),
+        Some(vec![String::from("a"), String::from("b")])
+    );
+    for invalid in ["{}", "{a", "a}", "{a{b}}"] {
+        assert_eq!(placeholders(invalid), None, "{}", invalid);
+    }
+    // The numbers are written with the decimal separator of the language
+    let (locales, _) = load_locales(&[]);
+    let error = TurtleError::new(
+        ErrorKind::InvalidLoopInterval {
+            start: 0.5,
+            end: -1.5,
+        },
+        "repeat",
+        Span::default(),
+    );
+    assert!(error.message(&locales[0]).contains("(0.5)"));
+    assert!(error.message(&locales[1]).contains("(0,5)"));
+    assert!(error.message(&locales[1]).contains("(-1,5)"));
+    let error = TurtleError::new(
+        ErrorKind::WrongArgumentCount { expected: 1 },
+        "square",
+        Span::default(),
+    );
+    assert_eq!(
+        error.message(&locales[0]),
+        "0:0 E027: The \"square\" procedure or function needs 1 argument!"
+    );
+}
+
+#[test]
+fn placeholders_are_validated() {
+    let directory = locale_directory("placeholders");
+    fs::write(
+        directory.join("reordered.yml"),
+        "language_id: XX\n\
+         invalid_index_error: \"{length} > {index}\"\n\
+         var_immutable_error: \"{variable}\"\n\
+         invalid_seed_error: \"{}\"\n",
+    )
+    .unwrap();
+    let (locales, errors) = load_locales(std::slice::from_ref(&directory));
+    let path = directory.join("reordered.yml").display().to_string();
+    let english = Locale::default();
+    let locale = &locales[2];
+    // The translators can reorder the placeholders
+    assert_eq!(locale.invalid_index_error, "{length} > {index}");
+    // The messages with unknown placeholders are replaced by the default messages
+    assert_eq!(locale.var_immutable_error, english.var_immutable_error);
+    assert_eq!(locale.invalid_seed_error, english.invalid_seed_error);
+    assert_eq!(
+        errors,
+        vec![
+            LocaleError {
+                path: path.clone(),
+                kind: LocaleErrorKind::InvalidPlaceholders {
+                    key: String::from("var_immutable_error"),
+                    expected: vec![String::from("name")],
+                },
+            },
+            LocaleError {
+                path: path.clone(),
+                kind: LocaleErrorKind::InvalidPlaceholders {
+                    key: String::from("invalid_seed_error"),
+                    expected: vec![String::from("value")],
+                },
+            },
+        ]
+    );
+    assert_eq!(
+        errors[0].message(&english),
+        format!(
+            "The \"var_immutable_error\" text of the language file \"{}\" can only contain these placeholders: {{name}}! The default text is used instead.",
+            path
+        )
+    );
+    let _ = fs::remove_dir_all(directory);
+}
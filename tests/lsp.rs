@@ -0,0 +1,269 @@
+// The language server answers the messages of the code editors
+use std::io::Cursor;
+
+use rugged_turtle::lsp::{read_message, serve, write_message, Server};
+use serde_json::{json, Value};
+
+const URI: &str = "file:///square.txt";
+
+fn request(server: &mut Server, method: &str, params: Value) -> Value {
+    let replies =
+        server.handle(&json!({"jsonrpc": "2.0", "id": 1, "method": method, "params": params}));
+    assert_eq!(replies.len(), 1);
+    assert_eq!(replies[0]["id"], 1);
+    replies[0]["result"].clone()
+}
+
+// Opens the document, and returns its diagnostics
+fn open(server: &mut Server, text: &str) -> Value {
+    let replies = server.handle(&json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didOpen",
+        "params": {"textDocument": {"uri": URI, "languageId": "turtle", "version": 1, "text": text}},
+    }));
+    assert_eq!(replies[0]["method"], "textDocument/publishDiagnostics");
+    assert_eq!(replies[0]["params"]["uri"], URI);
+    replies[0]["params"]["diagnostics"].clone()
+}
+
+fn at(line: u64, character: u64) -> Value {
+    json!({"textDocument": {"uri": URI}, "position": {"line": line, "character": character}})
+}
+
+#[test]
+fn diagnostics() {
+    let mut server = Server::default();
+    let result = request(&mut server, "initialize", json!({"capabilities": {}}));
+    assert_eq!(result["capabilities"]["documentFormattingProvider"], true);
+    assert_eq!(open(&mut server, "fd(10)\nrt(90)"), json!([]));
+    assert_eq!(
+        open(&mut server, "fd(10)\n\"ő\"; jump(5)"),
+        json!([{
+            "range": {"start": {"line": 1, "character": 0}, "end": {"line": 1, "character": 3}},
+            "severity": 1,
+            "code": "E020",
+            "source": "rugged_turtle",
+            "message": "\"\"ő\"\" is not expected here! The commands need to be separated by \";\" or line breaks.",
+        }])
+    );
+    // The whole document is sent with the changes
+    let replies = server.handle(&json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didChange",
+        "params": {
+            "textDocument": {"uri": URI, "version": 2},
+            "contentChanges": [{"text": "fd(10)\nőz(5)"}],
+        },
+    }));
+    let diagnostics = &replies[0]["params"]["diagnostics"];
+    assert_eq!(diagnostics[0]["code"], "E017");
+    assert_eq!(
+        diagnostics[0]["range"],
+        json!({"start": {"line": 1, "character": 0}, "end": {"line": 1, "character": 2}})
+    );
+    let replies = server.handle(&json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didClose",
+        "params": {"textDocument": {"uri": URI}},
+    }));
+    assert_eq!(replies[0]["params"]["diagnostics"], json!([]));
+    assert_eq!(
+        request(&mut server, "textDocument/hover", at(0, 0)),
+        Value::Null
+    );
+}
+
+#[test]
+fn warnings() {
+    let mut server = Server::default();
+    request(&mut server, "initialize", json!({"capabilities": {}}));
+    assert_eq!(
+        open(&mut server, "size = 10\nrepeat(4) { break; fd(10) }"),
+        json!([
+            {
+                "range": {"start": {"line": 0, "character": 0}, "end": {"line": 0, "character": 4}},
+                "severity": 2,
+                "code": "W001",
+                "source": "rugged_turtle",
+                "message": "The \"size\" variable is never used!",
+            },
+            {
+                "range": {"start": {"line": 1, "character": 19}, "end": {"line": 1, "character": 25}},
+                "severity": 2,
+                "code": "W003",
+                "source": "rugged_turtle",
+                "message": "This command is never executed, because \"break\" leaves the block before it!",
+            },
+        ])
+    );
+    // The errors come before the warnings
+    let diagnostics = open(&mut server, "size = 10\njump(5)");
+    assert_eq!(diagnostics[0]["code"], "E017");
+    assert_eq!(diagnostics[1]["code"], "W001");
+}
+
+#[test]
+fn deeply_nested_documents() {
+    let mut server = Server::default();
+    request(&mut server, "initialize", json!({"capabilities": {}}));
+    let text = format!("{}fd(1){}", "repeat(1) {".repeat(3000), "}".repeat(3000));
+    let diagnostics = open(&mut server, &text);
+    assert_eq!(diagnostics.as_array().unwrap().len(), 1);
+    assert_eq!(diagnostics[0]["code"], "E028");
+    // The other requests work without the statements of the document
+    assert!(request(&mut server, "textDocument/completion", at(0, 0))
+        .as_array()
+        .is_some_and(|items| !items.is_empty()));
+    assert_eq!(
+        request(&mut server, "textDocument/definition", at(0, 0)),
+        Value::Null
+    );
+    assert_eq!(
+        request(&mut server, "textDocument/formatting", at(0, 0)),
+        json!([])
+    );
+    let text = format!("x = {}1{}", "[".repeat(1000), "]".repeat(1000));
+    assert_eq!(open(&mut server, &text)[0]["code"], "E037");
+}
+
+#[test]
+fn messages_in_the_selected_language() {
+    let mut server = Server::default();
+    request(
+        &mut server,
+        "initialize",
+        json!({"capabilities": {}, "initializationOptions": {"language": "hu"}}),
+    );
+    assert_eq!(open(&mut server, "elore(10)"), json!([]));
+    let diagnostics = open(&mut server, "ismetles(2) { folytat }; folytat");
+    assert_eq!(diagnostics.as_array().unwrap().len(), 1);
+    assert_eq!(diagnostics[0]["code"], "E024");
+    let message = diagnostics[0]["message"].as_str().unwrap();
+    assert!(message.contains("\"folytat\""), "{}", message);
+    assert!(!message.contains("inside of a loop"), "{}", message);
+}
+
+#[test]
+fn completion_hover_and_definition() {
+    let mut server = Server::default();
+    request(&mut server, "initialize", json!({"capabilities": {}}));
+    open(
+        &mut server,
+        "size = 10\nto shape(side) { repeat(4) { fd(side); rt(90) } }\nshape(size)",
+    );
+    let items = request(&mut server, "textDocument/completion", at(2, 0));
+    let item = |label: &str| {
+        items
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|item| item["label"] == label)
+            .cloned()
+            .unwrap_or(Value::Null)
+    };
+    assert_eq!(item("shape")["kind"], 3);
+    assert_eq!(item("size")["kind"], 6);
+    assert_eq!(item("side")["kind"], 6);
+    assert_eq!(item("fd")["kind"], 14);
+    assert_eq!(
+        item("fd")["detail"],
+        "forward(<distance>) - moves the turtle forward by the distance (in pixels), its pen draws the path"
+    );
+    assert_eq!(item("round")["kind"], 21);
+    let hover = request(&mut server, "textDocument/hover", at(1, 30));
+    assert_eq!(
+        hover["contents"]["value"],
+        "forward(<distance>) - moves the turtle forward by the distance (in pixels), its pen draws the path\nAliases: f, fd, forward\nExamples:\n    forward(100)"
+    );
+    assert_eq!(
+        hover["range"],
+        json!({"start": {"line": 1, "character": 29}, "end": {"line": 1, "character": 31}})
+    );
+    assert_eq!(
+        request(&mut server, "textDocument/hover", at(0, 1)),
+        Value::Null
+    );
+    // The procedure and the variable are defined in the first and the second line
+    assert_eq!(
+        request(&mut server, "textDocument/definition", at(2, 3)),
+        json!({"uri": URI, "range": {"start": {"line": 1, "character": 3}, "end": {"line": 1, "character": 8}}})
+    );
+    assert_eq!(
+        request(&mut server, "textDocument/definition", at(2, 8)),
+        json!({"uri": URI, "range": {"start": {"line": 0, "character": 0}, "end": {"line": 0, "character": 4}}})
+    );
+    assert_eq!(
+        request(&mut server, "textDocument/definition", at(1, 30)),
+        Value::Null
+    );
+}
+
+#[test]
+fn formatting() {
+    let mut server = Server::default();
+    let options =
+        json!({"textDocument": {"uri": URI}, "options": {"tabSize": 4, "insertSpaces": true}});
+    open(&mut server, "repeat(4){fd(10);rt(90)}");
+    assert_eq!(
+        request(&mut server, "textDocument/formatting", options.clone()),
+        json!([{
+            "range": {"start": {"line": 0, "character": 0}, "end": {"line": 0, "character": 24}},
+            "newText": "repeat(4) {\n    fd(10)\n    rt(90)\n}\n",
+        }])
+    );
+    open(&mut server, "repeat(4) {\n    fd(10)\n}\n");
+    assert_eq!(
+        request(&mut server, "textDocument/formatting", options.clone()),
+        json!([])
+    );
+    open(&mut server, "repeat(4) {");
+    assert_eq!(
+        request(&mut server, "textDocument/formatting", options),
+        json!([])
+    );
+}
+
+#[test]
+fn unknown_messages() {
+    let mut server = Server::default();
+    let replies = server
+        .handle(&json!({"jsonrpc": "2.0", "id": 7, "method": "workspace/symbol", "params": {}}));
+    assert_eq!(replies[0]["error"]["code"], -32601);
+    assert_eq!(
+        server.handle(&json!({"jsonrpc": "2.0", "method": "$/cancelRequest", "params": {"id": 7}})),
+        Vec::<Value>::new()
+    );
+    assert_eq!(server.handle(&Value::Null)[0]["error"]["code"], -32700);
+}
+
+#[test]
+fn messages_over_the_standard_input_and_output() {
+    let mut input: Vec<u8> = vec![];
+    for message in [
+        json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {"capabilities": {}}}),
+        json!({"jsonrpc": "2.0", "method": "initialized", "params": {}}),
+        json!({"jsonrpc": "2.0", "id": 2, "method": "shutdown"}),
+        json!({"jsonrpc": "2.0", "method": "exit"}),
+    ] {
+        write_message(&mut input, &message).unwrap();
+    }
+    let mut output: Vec<u8> = vec![];
+    assert_eq!(serve(Cursor::new(input), &mut output), 0);
+    let mut output = Cursor::new(output);
+    let initialized = read_message(&mut output).unwrap().unwrap();
+    assert_eq!(initialized["id"], 1);
+    assert_eq!(
+        initialized["result"]["serverInfo"]["name"],
+        "rugged_turtle-lsp"
+    );
+    assert_eq!(
+        read_message(&mut output).unwrap(),
+        Some(json!({"jsonrpc": "2.0", "id": 2, "result": null}))
+    );
+    assert_eq!(read_message(&mut output).unwrap(), None);
+    // The server exits with an error without a shutdown request
+    let mut input: Vec<u8> = vec![];
+    write_message(&mut input, &json!({"jsonrpc": "2.0", "method": "exit"})).unwrap();
+    assert_eq!(serve(Cursor::new(input), Vec::new()), 1);
+    assert_eq!(serve(Cursor::new(Vec::new()), Vec::new()), 1);
+}
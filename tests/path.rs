@@ -0,0 +1,71 @@
+// The drawing of the turtle is stored as polylines, which are merged when it's possible
+use egui::{pos2, Color32};
+use rugged_turtle::path::{PathStore, PenStyle};
+
+fn style(color: Color32) -> PenStyle {
+    PenStyle {
+        color,
+        width: 1.0,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn collinear_segments_are_merged() {
+    let mut path = PathStore::default();
+    let black = style(Color32::BLACK);
+    path.add_segment(pos2(0.0, 0.0), pos2(10.0, 0.0), black);
+    path.add_segment(pos2(10.0, 0.0), pos2(25.0, 0.0), black);
+    assert_eq!(path.lines.len(), 1);
+    assert_eq!(path.lines[0].points, [pos2(0.0, 0.0), pos2(25.0, 0.0)]);
+    // The turn keeps the corner
+    path.add_segment(pos2(25.0, 0.0), pos2(25.0, 10.0), black);
+    assert_eq!(
+        path.lines[0].points,
+        [pos2(0.0, 0.0), pos2(25.0, 0.0), pos2(25.0, 10.0)]
+    );
+    // Going back on the same line isn't merged, the segment would disappear
+    path.add_segment(pos2(25.0, 10.0), pos2(25.0, 5.0), black);
+    assert_eq!(path.lines[0].points.len(), 4);
+    // Zero length segments aren't stored
+    path.add_segment(pos2(25.0, 5.0), pos2(25.0, 5.0), black);
+    assert_eq!(path.point_count(), 4);
+}
+
+#[test]
+fn new_lines_are_started() {
+    let mut path = PathStore::default();
+    let black = style(Color32::BLACK);
+    let red = style(Color32::RED);
+    path.add_segment(pos2(0.0, 0.0), pos2(10.0, 0.0), black);
+    // The segment doesn't continue the last line
+    path.add_segment(pos2(0.0, 5.0), pos2(10.0, 5.0), black);
+    // The segment continues the last line, but with another style
+    path.add_segment(pos2(10.0, 5.0), pos2(20.0, 5.0), red);
+    path.add_segment(pos2(20.0, 5.0), pos2(30.0, 5.0), black);
+    assert_eq!(path.lines.len(), 4);
+    // Every style is stored only once
+    assert_eq!(path.styles.len(), 2);
+    assert_eq!(path.style(&path.lines[3]), black);
+    assert_eq!(path.style(&path.lines[2]), red);
+}
+
+#[test]
+fn revision_changes_with_the_drawing() {
+    let mut path = PathStore::default();
+    let revision = path.revision();
+    path.add_segment(pos2(0.0, 0.0), pos2(0.0, 0.0), style(Color32::BLACK));
+    assert_eq!(path.revision(), revision);
+    path.add_segment(pos2(0.0, 0.0), pos2(10.0, 0.0), style(Color32::BLACK));
+    let drawn = path.revision();
+    assert_ne!(drawn, revision);
+    // The merged segment changes the drawing too
+    path.add_segment(pos2(10.0, 0.0), pos2(20.0, 0.0), style(Color32::BLACK));
+    assert_ne!(path.revision(), drawn);
+    // The loaded store gets a new revision, but it's the same drawing
+    let loaded: PathStore = serde_json::from_str(&serde_json::to_string(&path).unwrap()).unwrap();
+    assert_ne!(loaded.revision(), path.revision());
+    assert_eq!(loaded, path);
+    let empty: PathStore = serde_json::from_str("{}").unwrap();
+    assert_eq!(empty, PathStore::default());
+}
@@ -0,0 +1,130 @@
+// The project files of the older versions are migrated to the current version of the schema,
+// and the files of the newer versions are rejected
+use egui::{pos2, vec2, Color32};
+use rugged_turtle::{
+    commands::execute_command,
+    history::{HistoryEntry, MessageId},
+    project::{Project, ProjectError, PROJECT_FORMAT, PROJECT_VERSION},
+    turtle::Turtle,
+};
+
+// Saved by the first version, the console's history only had texts
+const VERSION_1: &str = r#"{
+  "format": "rugged_turtle_project",
+  "version": 1,
+  "script": "fd(10); print(5)",
+  "canvas_size": [800.0, 600.0],
+  "background": [255, 255, 255, 255],
+  "locale": "HU",
+  "turtle": {
+    "position": {"x": 0.0, "y": -10.0},
+    "pen": {"color": [0, 0, 0, 255], "width": 2.0, "mode": "Paint", "dash": null, "cap": "Butt", "join": "Miter"},
+    "path": {
+      "styles": [{"color": [0, 0, 0, 255], "width": 2.0, "mode": "Paint", "dash": null, "cap": "Butt", "join": "Miter"}],
+      "lines": [{"style": 0, "points": [{"x": 0.0, "y": 0.0}, {"x": 0.0, "y": -10.0}]}]
+    },
+    "command_history": ["5"]
+  }
+}"#;
+
+#[test]
+fn first_version() {
+    let project = Project::from_json(VERSION_1).unwrap();
+    assert_eq!(project.version, PROJECT_VERSION);
+    assert_eq!(project.script, "fd(10); print(5)");
+    assert_eq!(project.canvas_size(), vec2(800.0, 600.0));
+    assert_eq!(project.background(), Color32::WHITE);
+    assert_eq!(project.locale, "HU");
+    let turtle = project.turtle.unwrap();
+    assert_eq!(turtle.position, pos2(0.0, -10.0));
+    assert_eq!(turtle.pen.width, 2.0);
+    assert_eq!(turtle.path.lines.len(), 1);
+    assert_eq!(
+        turtle.path.lines[0].points,
+        [pos2(0.0, 0.0), pos2(0.0, -10.0)]
+    );
+    assert_eq!(
+        turtle.command_history,
+        [HistoryEntry::Text(String::from("5"))]
+    );
+}
+
+#[test]
+fn current_version() {
+    let mut turtle = Turtle::default();
+    execute_command(
+        String::from("x = 5; pw(3); fd(10); rt(90); fd(x); print(x)"),
+        &mut turtle,
+    )
+    .unwrap();
+    turtle
+        .command_history
+        .push(HistoryEntry::message(MessageId::ProjectSaved, &[]));
+    let project = Project::new(
+        String::from("fd(10)"),
+        vec2(640.0, 480.0),
+        Color32::from_rgb(10, 20, 30),
+        String::from("EN"),
+        Some(turtle.clone()),
+    );
+    let json = project.to_json();
+    let opened = Project::from_json(&json).unwrap();
+    assert_eq!(opened.to_json(), json);
+    assert_eq!(opened.format, PROJECT_FORMAT);
+    assert_eq!(opened.version, PROJECT_VERSION);
+    assert_eq!(opened.canvas_size(), vec2(640.0, 480.0));
+    assert_eq!(opened.background(), Color32::from_rgb(10, 20, 30));
+    let opened_turtle = opened.turtle.unwrap();
+    assert_eq!(opened_turtle.path, turtle.path);
+    assert_eq!(opened_turtle.variables, turtle.variables);
+    assert_eq!(opened_turtle.command_history, turtle.command_history);
+    // The turtle is optional
+    let project = Project::new(
+        String::new(),
+        vec2(1.0, 1.0),
+        Color32::BLACK,
+        String::from("EN"),
+        None,
+    );
+    assert!(!project.to_json().contains("\"turtle\""));
+    assert!(Project::from_json(&project.to_json())
+        .unwrap()
+        .turtle
+        .is_none());
+}
+
+#[test]
+fn rejected_files() {
+    let future = VERSION_1.replace(
+        "\"version\": 1",
+        &format!("\"version\": {}", PROJECT_VERSION + 1),
+    );
+    assert_eq!(
+        Project::from_json(&future).unwrap_err(),
+        ProjectError::UnsupportedVersion(PROJECT_VERSION + 1)
+    );
+    for (json, field) in [
+        (
+            VERSION_1.replace("\"version\": 1", "\"version\": 0"),
+            "version",
+        ),
+        (VERSION_1.replace("\"version\": 1,", ""), "version"),
+        (
+            VERSION_1.replace("rugged_turtle_project", "other_project"),
+            "format",
+        ),
+    ] {
+        assert_eq!(
+            Project::from_json(&json).unwrap_err(),
+            ProjectError::Invalid(String::from(field))
+        );
+    }
+    assert!(matches!(
+        Project::from_json("{"),
+        Err(ProjectError::Invalid(_))
+    ));
+    assert!(matches!(
+        Project::from_json(&VERSION_1.replace("\"script\": \"fd(10); print(5)\",", "")),
+        Err(ProjectError::Invalid(_))
+    ));
+}
@@ -0,0 +1,92 @@
+// The random numbers have to be repeatable with the same seed,
+// so the random drawings can be compared with their reference outputs
+use rugged_turtle::{commands::execute_command, to_svg, turtle::Turtle};
+
+const RANDOM_WALK: &str = "
+seed(42)
+directions = [0, 90, 180, 270]
+for(i, 0, 100) {
+    rt(pick(directions))
+    fd(random(5, 15) * randomfloat())
+}";
+
+fn run(script: &str) -> Turtle {
+    let mut turtle = Turtle::default();
+    execute_command(script.to_string(), &mut turtle).unwrap();
+    turtle
+}
+
+#[test]
+fn same_seed_same_drawing() {
+    let first = run(RANDOM_WALK);
+    let second = run(RANDOM_WALK);
+    assert!(!first.path.lines.is_empty());
+    assert_eq!(first.path.lines, second.path.lines);
+    assert_eq!(
+        to_svg(&first, Default::default()),
+        to_svg(&second, Default::default())
+    );
+    let other = run(&RANDOM_WALK.replace("seed(42)", "seed(43)"));
+    assert_ne!(first.path.lines, other.path.lines);
+}
+
+#[test]
+fn random_numbers_are_in_range() {
+    let mut turtle = run("seed(1)");
+    for (expression, min, max) in [
+        ("random(3)", 0.0, 2.0),
+        ("random(-2, 2)", -2.0, 2.0),
+        ("random(7, 7)", 7.0, 7.0),
+        ("randomfloat()", 0.0, 1.0),
+    ] {
+        let mut values: Vec<f64> = vec![];
+        for _ in 0..200 {
+            execute_command(format!("value = {}", expression), &mut turtle).unwrap();
+            let value = turtle
+                .get_variable("value")
+                .unwrap()
+                .variable_type
+                .get_value();
+            assert!(min <= value && value <= max, "{} = {}", expression, value);
+            values.push(value);
+        }
+        if expression == "randomfloat()" {
+            assert!(values.iter().all(|value| *value < 1.0));
+        } else {
+            // Every whole number of the range is chosen sometimes
+            assert!(values.iter().all(|value| value.fract() == 0.0));
+            let mut number = min;
+            while number <= max {
+                assert!(
+                    values.contains(&number),
+                    "{} never gives {}",
+                    expression,
+                    number
+                );
+                number += 1.0;
+            }
+        }
+    }
+}
+
+#[test]
+fn pick_chooses_elements() {
+    let mut turtle = run("seed(2); letters = [\"a\", \"b\"]");
+    let mut picked: Vec<String> = vec![];
+    for _ in 0..50 {
+        execute_command(
+            "value = pick(letters) + pick(\"c\")".to_string(),
+            &mut turtle,
+        )
+        .unwrap();
+        picked.push(
+            turtle
+                .get_variable("value")
+                .unwrap()
+                .variable_type
+                .to_string(),
+        );
+    }
+    assert!(picked.iter().all(|value| value == "ac" || value == "bc"));
+    assert!(picked.contains(&String::from("ac")) && picked.contains(&String::from("bc")));
+}
@@ -0,0 +1,84 @@
+// The turtle is saved at the end of the session and restored at the start of the next one,
+// the drawings, which are too large, are left out of the session
+use egui::pos2;
+use rugged_turtle::{
+    commands::execute_command,
+    history::{HistoryEntry, MessageId},
+    turtle::{Turtle, MAX_SESSION_POINTS},
+};
+
+const SCRIPT: &str = "
+to square(size) { repeat(4) { fd(size); rt(90) } }
+size = 30
+names = [\"a\", \"b\"]
+rad; wrap; pencolor(255, 0, 0, 128); penwidth(3); pendash(4, 2)
+square(size); label(\"square\", 12)
+pu; fd(10); print(names)";
+
+fn restore(turtle: &mut Turtle) -> Turtle {
+    let session = turtle.save_session(|turtle| serde_json::to_string(turtle).unwrap());
+    serde_json::from_str(&session).unwrap()
+}
+
+#[test]
+fn restored_turtle() {
+    let mut turtle = Turtle::default();
+    execute_command(SCRIPT.to_string(), &mut turtle).unwrap();
+    let restored = restore(&mut turtle);
+    assert_eq!(
+        serde_json::to_value(&restored).unwrap(),
+        serde_json::to_value(&turtle).unwrap()
+    );
+    assert_eq!(restored.position, turtle.position);
+    assert_eq!(restored.angle, turtle.angle);
+    assert_eq!(restored.angle_unit, turtle.angle_unit);
+    assert_eq!(restored.boundary_mode, turtle.boundary_mode);
+    assert_eq!(restored.pen, turtle.pen);
+    assert!(restored.pen_up);
+    assert_eq!(restored.path, turtle.path);
+    assert_eq!(restored.path.labels.len(), 1);
+    assert_eq!(restored.variables, turtle.variables);
+    assert_eq!(restored.procedures, turtle.procedures);
+    assert_eq!(restored.command_history, turtle.command_history);
+    // The restored turtle continues the drawing
+    let mut restored = restored;
+    execute_command(String::from("pd; square(size)"), &mut restored).unwrap();
+    assert!(restored.path.lines.len() > turtle.path.lines.len());
+}
+
+#[test]
+fn large_drawings_are_left_out() {
+    let mut turtle = Turtle::default();
+    turtle
+        .command_history
+        .push(HistoryEntry::Text(String::from("zigzag")));
+    // A zigzag line, every segment turns, so they aren't merged
+    let point = |index: usize| pos2(index as f32, (index % 2) as f32);
+    let mut index = 0;
+    while turtle.path.point_count() <= MAX_SESSION_POINTS {
+        turtle
+            .path
+            .add_segment(point(index), point(index + 1), turtle.pen);
+        index += 1;
+    }
+    let restored = restore(&mut turtle);
+    assert_eq!(restored.path.point_count(), 0);
+    assert_eq!(
+        restored.command_history,
+        [
+            HistoryEntry::Text(String::from("zigzag")),
+            HistoryEntry::message(MessageId::SessionDrawingTooLarge, &[])
+        ]
+    );
+    // The turtle keeps its drawing and its history after it's saved
+    assert!(turtle.path.point_count() > MAX_SESSION_POINTS);
+    assert_eq!(turtle.command_history.len(), 1);
+    // The drawings up to the limit are saved
+    turtle.path = Default::default();
+    turtle
+        .path
+        .add_segment(pos2(0.0, 0.0), pos2(10.0, 0.0), turtle.pen);
+    let restored = restore(&mut turtle);
+    assert_eq!(restored.path, turtle.path);
+    assert_eq!(restored.command_history.len(), 1);
+}